@@ -2,6 +2,7 @@ use ansi_term::Colour;
 use compiler::hir::very_concrete_program::VCP;
 use constraint_writers::debug_writer::DebugWriter;
 use constraint_writers::ConstraintExporter;
+use constraint_generation::{BuildConfig, TemplateFilter};
 use program_structure::program_archive::ProgramArchive;
 
 
@@ -11,6 +12,12 @@ pub struct ExecutionConfig {
     pub civer_file: String,
     pub initial_constraints_file: String,
     pub structure_file: String,
+    pub bounds_file: String,
+    pub coverage_file: String,
+    pub assumptions_file: String,
+    pub html_file: String,
+    pub dot_file: String,
+    pub postconditions_file: String,
     pub json_constraints: String,
     pub no_rounds: usize,
     pub flag_s: bool,
@@ -32,16 +39,76 @@ pub struct ExecutionConfig {
     pub add_tags_info: bool,
     pub add_postconditions_info: bool,
     pub apply_deduction_assigned: bool,
-    pub file_solved_templates: Option<String> 
+    pub file_solved_templates: Option<String>,
+    pub print_bounds: bool,
+    pub coverage: bool,
+    pub assumptions: bool,
+    pub html: bool,
+    pub dot: bool,
+    pub dot_signal_ranges: bool,
+    pub infer_postconditions: bool,
+    pub suggest_tags: bool,
+    pub certificates: bool,
+    pub certificates_file: String,
+    pub unsat_core: bool,
+    pub portfolio: bool,
+    pub civer_only: Vec<String>,
+    pub civer_exclude: Vec<String>,
+    pub civer_budget: Option<u64>,
+    pub check_completeness: bool,
+    pub check_satisfiable: bool,
+    pub counterexamples: bool,
+    pub counterexamples_file: String,
 }
 
 pub fn execute_project(
     program_archive: ProgramArchive,
     config: ExecutionConfig,
 ) -> Result<VCP, ()> {
-    use constraint_generation::{build_circuit, BuildConfig};
-    let debug = DebugWriter::new(config.json_constraints).unwrap();
-    let build_config = BuildConfig {
+    use constraint_generation::build_circuit;
+    let debug = DebugWriter::new(config.json_constraints.clone()).unwrap();
+    let build_config = build_config(&config);
+    let custom_gates = program_archive.custom_gates;
+    let (exporter, vcp) = build_circuit(program_archive, build_config)?;
+    if config.r1cs_flag {
+        generate_output_r1cs(&config.r1cs, exporter.as_ref(), custom_gates)?;
+    }
+    if config.sym_flag {
+        generate_output_sym(&config.sym, exporter.as_ref())?;
+    }
+    if config.json_constraint_flag {
+        generate_json_constraints(&debug, exporter.as_ref())?;
+    }
+    Result::Ok(vcp)
+}
+
+pub fn verify_primes(
+    program_archive: ProgramArchive,
+    primes: &[String],
+    config: &ExecutionConfig,
+) -> Result<(), ()> {
+    constraint_generation::verify_primes(program_archive, primes, &build_config(config))
+}
+
+pub fn template_parameters(
+    program_archive: &mut ProgramArchive,
+    template: &str,
+    params: &[(String, Vec<i64>)],
+) -> Result<Vec<Vec<i64>>, ()> {
+    constraint_generation::template_parameters(program_archive, template, params)
+}
+
+pub fn verify_template(
+    program_archive: ProgramArchive,
+    template: &str,
+    tuples: &[Vec<i64>],
+    config: &ExecutionConfig,
+) -> Result<(), ()> {
+    constraint_generation::verify_template(program_archive, template, tuples, &build_config(config))
+}
+
+fn build_config(config: &ExecutionConfig) -> BuildConfig {
+    BuildConfig {
         no_rounds: config.no_rounds,
         flag_json_sub: config.json_substitution_flag,
         flag_s: config.flag_s,
@@ -50,7 +117,7 @@ pub fn execute_project(
         flag_verbose: config.flag_verbose,
         inspect_constraints: config.inspect_constraints_flag,
         flag_old_heuristics: config.flag_old_heuristics,
-        prime : config.prime,
+        prime: config.prime.clone(),
         civer: config.civer,
         verification_timeout: config.verification_timeout,
         check_tags: config.check_tags,
@@ -58,24 +125,51 @@ pub fn execute_project(
         check_safety: config.check_safety,
         add_tags_info: config.add_tags_info,
         add_postconditions_info: config.add_postconditions_info,
-        civer_file: config.civer_file,
-        initial_constraints_file: config.initial_constraints_file,
-        structure_file: config.structure_file,
+        civer_file: config.civer_file.clone(),
+        initial_constraints_file: config.initial_constraints_file.clone(),
+        structure_file: config.structure_file.clone(),
         apply_deduction_assigned: config.apply_deduction_assigned,
-        file_solved_templates: config.file_solved_templates
-    };
-    let custom_gates = program_archive.custom_gates;
-    let (exporter, vcp) = build_circuit(program_archive, build_config)?;
-    if config.r1cs_flag {
-        generate_output_r1cs(&config.r1cs, exporter.as_ref(), custom_gates)?;
+        file_solved_templates: config.file_solved_templates.clone(),
+        print_bounds: config.print_bounds,
+        bounds_file: config.bounds_file.clone(),
+        coverage: config.coverage,
+        coverage_file: config.coverage_file.clone(),
+        assumptions: config.assumptions,
+        assumptions_file: config.assumptions_file.clone(),
+        html: config.html,
+        html_file: config.html_file.clone(),
+        dot: config.dot,
+        dot_signal_ranges: config.dot_signal_ranges,
+        dot_file: config.dot_file.clone(),
+        infer_postconditions: config.infer_postconditions,
+        suggest_tags: config.suggest_tags,
+        postconditions_file: config.postconditions_file.clone(),
+        certificates: config.certificates,
+        certificates_file: config.certificates_file.clone(),
+        unsat_core: config.unsat_core,
+        portfolio: config.portfolio,
+        template_filter: TemplateFilter{ only: config.civer_only.clone(), exclude: config.civer_exclude.clone() },
+        budget: config.civer_budget,
+        check_completeness: config.check_completeness,
+        check_satisfiable: config.check_satisfiable,
+        counterexamples: config.counterexamples,
+        counterexamples_file: config.counterexamples_file.clone(),
     }
-    if config.sym_flag {
-        generate_output_sym(&config.sym, exporter.as_ref())?;
-    }
-    if config.json_constraint_flag {
-        generate_json_constraints(&debug, exporter.as_ref())?;
-    }
-    Result::Ok(vcp)
+}
+
+pub fn check_equivalence(
+    program_archive: ProgramArchive,
+    other_archive: ProgramArchive,
+    config: &ExecutionConfig,
+) -> Result<(), ()> {
+    constraint_generation::check_equivalence(
+        program_archive,
+        other_archive,
+        &config.prime,
+        config.verification_timeout,
+        config.flag_verbose,
+        &config.civer_file,
+    )
 }
 
 fn generate_output_r1cs(file: &str, exporter: &dyn ConstraintExporter, custom_gates: bool) -> Result<(), ()> {
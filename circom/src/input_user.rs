@@ -16,6 +16,14 @@ pub struct Input {
     pub out_civer: PathBuf,
     pub out_initial_constraints: PathBuf,
     pub out_structure: PathBuf,
+    pub out_bounds: PathBuf,
+    pub out_coverage: PathBuf,
+    pub out_assumptions: PathBuf,
+    pub out_html: PathBuf,
+    pub out_dot: PathBuf,
+    pub out_postconditions: PathBuf,
+    pub out_certificates: PathBuf,
+    pub out_counterexamples: PathBuf,
     //pub field: &'static str,
     pub c_flag: bool,
     pub wasm_flag: bool,
@@ -44,7 +52,28 @@ pub struct Input {
     pub add_tags_info: bool,
     pub add_postconditions_info: bool,
     pub apply_deduction_assigned: bool,
-    pub file_solved_templates: Option<PathBuf>
+    pub file_solved_templates: Option<PathBuf>,
+    pub equivalent_file: Option<PathBuf>,
+    pub primes: Vec<String>,
+    pub verify_template: Option<String>,
+    pub params: Vec<(String, Vec<i64>)>,
+    pub print_bounds: bool,
+    pub coverage: bool,
+    pub assumptions: bool,
+    pub html: bool,
+    pub dot: bool,
+    pub dot_signal_ranges: bool,
+    pub infer_postconditions: bool,
+    pub suggest_tags: bool,
+    pub certificates: bool,
+    pub unsat_core: bool,
+    pub portfolio: bool,
+    pub civer_only: Vec<String>,
+    pub civer_exclude: Vec<String>,
+    pub civer_budget: Option<u64>,
+    pub check_completeness: bool,
+    pub check_satisfiable: bool,
+    pub counterexamples: bool,
 }
 
 
@@ -57,6 +86,9 @@ const DAT: &'static str = "dat";
 const SYM: &'static str = "sym";
 const JSON: &'static str = "json";
 const CIVER: &'static str = "civer";
+const TXT: &'static str = "txt";
+const HTML: &'static str = "html";
+const DOT: &'static str = "dot";
 
 
 
@@ -82,7 +114,19 @@ impl Input {
         let (spec_libraries, civer) = input_processing::get_spec_libraries(&matches);
         let file_name_initial_constraints = format!("{}_initial_constraints", file_name);
         let file_structure = format!("{}_structure", file_name);
+        let file_bounds = format!("{}_bounds", file_name);
+        let file_coverage = format!("{}_coverage", file_name);
+        let file_assumptions = format!("{}_assumptions", file_name);
+        let file_html = format!("{}_report", file_name);
+        let file_postconditions = format!("{}_postconditions", file_name);
+        let file_certificates = format!("{}_certificates", file_name);
+        let mut out_counterexamples = output_path.clone();
+        out_counterexamples.push(format!("{}_counterexample", file_name));
         let file_solved_templates = input_processing::get_file_solved_templates(&matches)?;
+        let civer_budget = input_processing::get_civer_budget(&matches)?;
+        let equivalent_file = input_processing::get_equivalent_file(&matches)?;
+        let primes = input_processing::get_primes(&matches)?;
+        let params = input_processing::get_params(&matches)?;
 
         Result::Ok(Input {
             //field: P_BN128,
@@ -101,6 +145,14 @@ impl Input {
             
             out_initial_constraints: Input::build_output(&output_path, &file_name_initial_constraints, JSON),
             out_structure: Input::build_output(&output_path, &file_structure, JSON),
+            out_bounds: Input::build_output(&output_path, &file_bounds, JSON),
+            out_coverage: Input::build_output(&output_path, &file_coverage, JSON),
+            out_assumptions: Input::build_output(&output_path, &file_assumptions, JSON),
+            out_html: Input::build_output(&output_path, &file_html, HTML),
+            out_dot: Input::build_output(&output_path, &file_name, DOT),
+            out_postconditions: Input::build_output(&output_path, &file_postconditions, TXT),
+            out_certificates: Input::build_output(&output_path, &file_certificates, JSON),
+            out_counterexamples,
             out_json_constraints: Input::build_output(
                 &output_path,
                 &format!("{}_constraints", file_name),
@@ -133,7 +185,28 @@ impl Input {
             add_tags_info: input_processing::get_flag_add_tags_info(&matches),
             add_postconditions_info: input_processing::get_flag_add_postconditions_info(&matches),
             apply_deduction_assigned: input_processing::get_apply_deduction_assigned(&matches),
-            file_solved_templates
+            file_solved_templates,
+            equivalent_file,
+            primes,
+            verify_template: matches.value_of("verify_template").map(|template| template.to_string()),
+            params,
+            print_bounds: input_processing::get_flag_print_bounds(&matches),
+            coverage: input_processing::get_flag_coverage(&matches),
+            assumptions: input_processing::get_flag_assumptions(&matches),
+            html: input_processing::get_flag_html(&matches),
+            dot: input_processing::get_flag_dot(&matches),
+            dot_signal_ranges: input_processing::get_flag_dot_signal_ranges(&matches),
+            infer_postconditions: input_processing::get_flag_infer_postconditions(&matches),
+            suggest_tags: input_processing::get_flag_suggest_tags(&matches),
+            certificates: input_processing::get_flag_certificates(&matches),
+            unsat_core: input_processing::get_flag_unsat_core(&matches),
+            portfolio: input_processing::get_flag_portfolio(&matches),
+            civer_only: input_processing::get_patterns(&matches, "civer_only"),
+            civer_exclude: input_processing::get_patterns(&matches, "civer_exclude"),
+            civer_budget,
+            check_completeness: input_processing::get_flag_check_completeness(&matches),
+            check_satisfiable: input_processing::get_flag_check_satisfiable(&matches),
+            counterexamples: input_processing::get_flag_counterexamples(&matches),
         })
     }
 
@@ -176,6 +249,30 @@ impl Input {
     pub fn structure_file(&self) -> &str {
         self.out_structure.to_str().unwrap()
     }
+    pub fn bounds_file(&self) -> &str {
+        self.out_bounds.to_str().unwrap()
+    }
+    pub fn coverage_file(&self) -> &str {
+        self.out_coverage.to_str().unwrap()
+    }
+    pub fn assumptions_file(&self) -> &str {
+        self.out_assumptions.to_str().unwrap()
+    }
+    pub fn html_file(&self) -> &str {
+        self.out_html.to_str().unwrap()
+    }
+    pub fn dot_file(&self) -> &str {
+        self.out_dot.to_str().unwrap()
+    }
+    pub fn postconditions_file(&self) -> &str {
+        self.out_postconditions.to_str().unwrap()
+    }
+    pub fn certificates_file(&self) -> &str {
+        self.out_certificates.to_str().unwrap()
+    }
+    pub fn counterexamples_file(&self) -> &str {
+        self.out_counterexamples.to_str().unwrap()
+    }
     pub fn wat_file(&self) -> &str {
         self.out_wat_code.to_str().unwrap()
     }
@@ -282,6 +379,57 @@ impl Input {
     pub fn apply_deduction_assigned(&self) -> bool {
         self.apply_deduction_assigned
     }
+    pub fn print_bounds(&self) -> bool {
+        self.print_bounds
+    }
+    pub fn coverage(&self) -> bool {
+        self.coverage
+    }
+    pub fn assumptions(&self) -> bool {
+        self.assumptions
+    }
+    pub fn html(&self) -> bool {
+        self.html
+    }
+    pub fn dot(&self) -> bool {
+        self.dot
+    }
+    pub fn dot_signal_ranges(&self) -> bool {
+        self.dot_signal_ranges
+    }
+    pub fn infer_postconditions(&self) -> bool {
+        self.infer_postconditions
+    }
+    pub fn suggest_tags(&self) -> bool {
+        self.suggest_tags
+    }
+    pub fn certificates(&self) -> bool {
+        self.certificates
+    }
+    pub fn unsat_core(&self) -> bool {
+        self.unsat_core
+    }
+    pub fn portfolio(&self) -> bool {
+        self.portfolio
+    }
+    pub fn civer_only(&self) -> &Vec<String> {
+        &self.civer_only
+    }
+    pub fn civer_exclude(&self) -> &Vec<String> {
+        &self.civer_exclude
+    }
+    pub fn civer_budget(&self) -> Option<u64> {
+        self.civer_budget
+    }
+    pub fn check_completeness(&self) -> bool {
+        self.check_completeness
+    }
+    pub fn check_satisfiable(&self) -> bool {
+        self.check_satisfiable
+    }
+    pub fn counterexamples(&self) -> bool {
+        self.counterexamples
+    }
     pub fn file_solved_templates(&self) -> Option<String> {
         match &self.file_solved_templates{
             Some(value) =>{
@@ -290,6 +438,18 @@ impl Input {
             None => None
         }
     }
+    pub fn equivalent_file(&self) -> Option<String> {
+        self.equivalent_file.as_ref().map(|value| value.to_str().unwrap().to_string())
+    }
+    pub fn primes(&self) -> &Vec<String> {
+        &self.primes
+    }
+    pub fn verify_template(&self) -> Option<&str> {
+        self.verify_template.as_deref()
+    }
+    pub fn params(&self) -> &Vec<(String, Vec<i64>)> {
+        &self.params
+    }
 
 
 }
@@ -298,6 +458,7 @@ mod input_processing {
     use clap::{App, Arg, ArgMatches};
     use std::path::{Path, PathBuf};
     use crate::VERSION;
+    use constraint_generation::MAX_TEMPLATE_INSTANCES;
 
     pub fn get_input(matches: &ArgMatches) -> Result<PathBuf, ()> {
         let route = Path::new(matches.value_of("input").unwrap()).to_path_buf();
@@ -422,6 +583,63 @@ mod input_processing {
     pub fn get_apply_deduction_assigned(matches: &ArgMatches) -> bool {
         matches.is_present("apply_deduction_assigned")
     }
+    pub fn get_flag_print_bounds(matches: &ArgMatches) -> bool {
+        matches.is_present("flag_print_bounds")
+    }
+    pub fn get_flag_coverage(matches: &ArgMatches) -> bool {
+        matches.is_present("flag_coverage")
+    }
+    pub fn get_flag_assumptions(matches: &ArgMatches) -> bool {
+        matches.is_present("flag_assumptions")
+    }
+    pub fn get_flag_html(matches: &ArgMatches) -> bool {
+        matches.is_present("flag_html")
+    }
+    pub fn get_flag_dot(matches: &ArgMatches) -> bool {
+        matches.is_present("flag_dot")
+    }
+    pub fn get_flag_dot_signal_ranges(matches: &ArgMatches) -> bool {
+        matches.is_present("flag_dot_signal_ranges")
+    }
+    pub fn get_flag_infer_postconditions(matches: &ArgMatches) -> bool {
+        matches.is_present("flag_infer_postconditions")
+    }
+    pub fn get_flag_suggest_tags(matches: &ArgMatches) -> bool {
+        matches.is_present("flag_suggest_tags")
+    }
+    pub fn get_flag_certificates(matches: &ArgMatches) -> bool {
+        matches.is_present("flag_certificates")
+    }
+    pub fn get_flag_unsat_core(matches: &ArgMatches) -> bool {
+        matches.is_present("flag_unsat_core")
+    }
+    pub fn get_flag_portfolio(matches: &ArgMatches) -> bool {
+        matches.is_present("flag_portfolio")
+    }
+    pub fn get_patterns(matches: &ArgMatches, name: &str) -> Vec<String> {
+        matches.values_of(name).into_iter().flatten().map(|pattern| pattern.to_string()).collect()
+    }
+    pub fn get_civer_budget(matches: &ArgMatches) -> Result<Option<u64>, ()> {
+        match matches.value_of("civer_budget"){
+            Some(seconds) => match seconds.parse::<u64>(){
+                Ok(seconds) => Ok(Some(seconds)),
+                Err(_) => {
+                    eprintln!("{}", Colour::Red.paint("invalid time budget: ".to_owned() + seconds));
+                    Err(())
+                }
+            },
+            None => Ok(None),
+        }
+    }
+    pub fn get_flag_check_completeness(matches: &ArgMatches) -> bool {
+        matches.is_present("flag_check_completeness")
+    }
+    pub fn get_flag_check_satisfiable(matches: &ArgMatches) -> bool {
+        matches.is_present("flag_check_satisfiable")
+    }
+    pub fn get_flag_counterexamples(matches: &ArgMatches) -> bool {
+        matches.is_present("flag_counterexamples")
+    }
 
 
     pub fn get_file_solved_templates(matches: &ArgMatches) -> Result<Option<PathBuf>, ()> {
@@ -440,6 +658,77 @@ mod input_processing {
         }
     }
 
+    pub fn get_equivalent_file(matches: &ArgMatches) -> Result<Option<PathBuf>, ()> {
+        match matches.value_of("equivalent"){
+            Some(value) =>{
+                let route = Path::new(value).to_path_buf();
+                if route.is_file() {
+                    Result::Ok(Some(route))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("Circuit to compare does not exist: ".to_owned() + value)))
+                }
+            },
+            None => Ok(None)
+        }
+    }
+
+
+    pub fn get_primes(matches: &ArgMatches) -> Result<Vec<String>, ()> {
+        let mut primes = Vec::new();
+        if let Some(value) = matches.value_of("primes"){
+            for prime in value.split(',').map(|prime| prime.trim()){
+                if !is_valid_prime(prime){
+                    return Result::Err(eprintln!("{}", Colour::Red.paint("invalid prime number: ".to_owned() + prime)));
+                }
+                if !primes.iter().any(|other| other == prime){
+                    primes.push(prime.to_string());
+                }
+            }
+        }
+        Ok(primes)
+    }
+
+    // each parameter is given as name=values, where the values are separated by commas
+    // and may contain inclusive ranges, for example n=1..4,8,16
+    pub fn get_params(matches: &ArgMatches) -> Result<Vec<(String, Vec<i64>)>, ()> {
+        let mut params = Vec::new();
+        for param in matches.values_of("params").into_iter().flatten(){
+            let invalid = || eprintln!("{}", Colour::Red.paint("invalid parameter values: ".to_owned() + param));
+            let (name, spec) = param.split_once('=').ok_or_else(invalid)?;
+            let mut values = Vec::new();
+            for value in spec.split(','){
+                match value.trim().split_once(".."){
+                    Some((first, last)) => {
+                        let first = first.trim().parse::<i64>().map_err(|_| invalid())?;
+                        let last = last.trim().parse::<i64>().map_err(|_| invalid())?;
+                        if first > last{
+                            return Result::Err(invalid());
+                        }
+                        if values.len() as i128 + i128::from(last) - i128::from(first) >= MAX_TEMPLATE_INSTANCES as i128{
+                            eprintln!("{}", Colour::Red.paint(format!("too many parameter values: {}, at most {} instances of the template are verified", param, MAX_TEMPLATE_INSTANCES)));
+                            return Result::Err(());
+                        }
+                        values.extend(first..=last);
+                    }
+                    None => values.push(value.trim().parse::<i64>().map_err(|_| invalid())?),
+                }
+            }
+            if values.is_empty(){
+                return Result::Err(invalid());
+            }
+            params.push((name.trim().to_string(), values));
+        }
+        Ok(params)
+    }
+
+    fn is_valid_prime(prime: &str) -> bool {
+        prime == "bn128"
+            || prime == "bls12381"
+            || prime == "goldilocks"
+            || prime == "grumpkin"
+            || prime == "pallas"
+            || prime == "vesta"
+    }
 
     pub fn get_prime(matches: &ArgMatches) -> Result<String, ()> {
         
@@ -466,6 +755,10 @@ mod input_processing {
     }
 
     pub fn view() -> ArgMatches<'static> {
+        app().get_matches()
+    }
+
+    fn app() -> App<'static, 'static> {
         App::new("circom compiler")
             .version(VERSION)
             .author("IDEN3")
@@ -681,6 +974,161 @@ mod input_processing {
                     .display_order(980)
                     .help("Indicates if CIVER applies the rule for linear constraints"),
             )
+            .arg(
+                Arg::with_name("flag_dot")
+                    .long("dot")
+                    .takes_value(false)
+                    .display_order(980)
+                    .help("Writes the graph of template instances of the circuit in a .dot file (Graphviz)"),
+            )
+            .arg(
+                Arg::with_name("flag_dot_signal_ranges")
+                    .long("dot_signal_ranges")
+                    .takes_value(false)
+                    .requires("flag_dot")
+                    .display_order(980)
+                    .help("Includes in the edges of the .dot file the ranges of signals of the subcomponents"),
+            )
+            .arg(
+                Arg::with_name("flag_html")
+                    .long("html")
+                    .takes_value(false)
+                    .display_order(980)
+                    .help("Writes a self-contained HTML report of the verification with the tree of components of the circuit"),
+            )
+            .arg(
+                Arg::with_name("flag_coverage")
+                    .long("coverage")
+                    .takes_value(false)
+                    .display_order(980)
+                    .help("Writes in a json file the outputs of each template that are covered by tags, postconditions or tag specifications, and the percentage of constraints under a specification"),
+            )
+            .arg(
+                Arg::with_name("flag_assumptions")
+                    .long("assumptions")
+                    .takes_value(false)
+                    .display_order(980)
+                    .help("Writes in a json file the assumptions used by the verification of each template and prints the ones that are not proved by any template"),
+            )
+            .arg(
+                Arg::with_name("flag_print_bounds")
+                    .long("print_bounds")
+                    .takes_value(false)
+                    .display_order(980)
+                    .help("Writes the bounds deduced by CIVER for the signals of each template in a json file"),
+            )
+            .arg(
+                Arg::with_name("flag_infer_postconditions")
+                    .long("infer_postconditions")
+                    .takes_value(false)
+                    .display_order(980)
+                    .help("Writes the strongest postconditions of the outputs of each template that CIVER can prove"),
+            )
+            .arg(
+                Arg::with_name("flag_suggest_tags")
+                    .long("suggest_tags")
+                    .takes_value(false)
+                    .display_order(980)
+                    .help("Reports the outputs that satisfy the semantics of a tag given with --civer but are not declared with it"),
+            )
+            .arg(
+                Arg::with_name("flag_certificates")
+                    .long("certificates")
+                    .takes_value(false)
+                    .display_order(980)
+                    .help("Writes a certificate for the properties verified by CIVER that can be checked by civer_checker"),
+            )
+            .arg(
+                Arg::with_name("flag_unsat_core")
+                    .long("unsat_core")
+                    .takes_value(false)
+                    .display_order(980)
+                    .help("Reports the constraints, preconditions and contracts of the subcomponents needed by each proof of the SMT solver"),
+            )
+            .arg(
+                Arg::with_name("flag_portfolio")
+                    .long("portfolio")
+                    .takes_value(false)
+                    .display_order(980)
+                    .help("Checks weak safety running several configurations of the SMT solver in parallel and takes the first answer"),
+            )
+            .arg(
+                Arg::with_name("civer_only")
+                    .long("civer_only")
+                    .takes_value(true)
+                    .multiple(true)
+                    .number_of_values(1)
+                    .display_order(980)
+                    .help("Only verifies the template instances whose name matches the pattern, where * matches any sequence of characters (example: --civer_only 'Num2Bits*')"),
+            )
+            .arg(
+                Arg::with_name("civer_exclude")
+                    .long("civer_exclude")
+                    .takes_value(true)
+                    .multiple(true)
+                    .number_of_values(1)
+                    .display_order(980)
+                    .help("Does not verify the template instances whose name matches the pattern, their contracts are assumed in the components that use them"),
+            )
+            .arg(
+                Arg::with_name("civer_budget")
+                    .long("civer_budget")
+                    .takes_value(true)
+                    .display_order(980)
+                    .help("Maximum time in seconds of the whole verification, the templates that are not started when it runs out are not studied. The template being verified is not interrupted, but each of its queries has at most the remaining time as timeout"),
+            )
+            .arg(
+                Arg::with_name("flag_check_completeness")
+                    .long("check_completeness")
+                    .takes_value(false)
+                    .display_order(980)
+                    .help("Checks that the values computed by the witness generator (<-- and <==) satisfy the constraints of each template"),
+            )
+            .arg(
+                Arg::with_name("flag_check_satisfiable")
+                    .long("check_satisfiable")
+                    .takes_value(false)
+                    .display_order(980)
+                    .help("Checks that the constraints of each template admit a witness for every input satisfying its preconditions"),
+            )
+            .arg(
+                Arg::with_name("flag_counterexamples")
+                    .long("counterexamples")
+                    .takes_value(false)
+                    .display_order(980)
+                    .help("Writes the counterexamples of weak safety as an input file and two witnesses (.wtns) of the circuit"),
+            )
+            .arg(
+                Arg::with_name("equivalent")
+                    .long("equivalent")
+                    .takes_value(true)
+                    .display_order(980)
+                    .help("Checks that the main component of the given circom file produces the same outputs as the one of the input file, instead of compiling the circuit"),
+            )
+            .arg(
+                Arg::with_name("primes")
+                    .long("primes")
+                    .takes_value(true)
+                    .display_order(980)
+                    .help("Verifies the circuit over each of the given comma separated primes (bn128, bls12381, goldilocks, grumpkin, pallas, vesta) and reports the templates whose results differ, instead of compiling the circuit"),
+            )
+            .arg(
+                Arg::with_name("verify_template")
+                    .long("verify_template")
+                    .takes_value(true)
+                    .display_order(980)
+                    .help("Verifies the given template for each of the values of its parameters given with --params, instead of compiling the main component"),
+            )
+            .arg(
+                Arg::with_name("params")
+                    .long("params")
+                    .takes_value(true)
+                    .multiple(true)
+                    .number_of_values(1)
+                    .requires("verify_template")
+                    .display_order(980)
+                    .help("Values of a parameter of the template given with --verify_template, at most 10000 instances are verified (example: --params n=1..64, --params k=1,2,4)"),
+            )
             .arg(
                 Arg::with_name("solved_templates")
                     .short("solved_templates")
@@ -698,7 +1146,6 @@ mod input_processing {
                     .display_order(300)
                     .help("To choose the prime number to use to generate the circuit. Receives the name of the curve (bn128, bls12381, goldilocks, grumpkin, pallas, vesta)"),
             )
-            .get_matches()
     }
 
     pub fn get_link_libraries(matches: &ArgMatches) -> Vec<PathBuf> {
@@ -724,4 +1171,62 @@ mod input_processing {
         }
         (spec_libraries, civer)
     }
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        fn params(args: &[&str]) -> Result<Vec<(String, Vec<i64>)>, ()> {
+            let mut command = vec!["civer_circom", "circuit.circom", "--verify_template", "T"];
+            for arg in args {
+                command.push("--params");
+                command.push(arg);
+            }
+            get_params(&app().get_matches_from(command))
+        }
+
+        #[test]
+        fn params_ranges_and_lists() {
+            assert_eq!(params(&["n=1..4"]), Ok(vec![("n".to_string(), vec![1, 2, 3, 4])]));
+            assert_eq!(params(&["n=3..3"]), Ok(vec![("n".to_string(), vec![3])]));
+            assert_eq!(params(&["k = 1, 2 ,4..5"]), Ok(vec![("k".to_string(), vec![1, 2, 4, 5])]));
+            assert_eq!(params(&["n=-2..0"]), Ok(vec![("n".to_string(), vec![-2, -1, 0])]));
+        }
+
+        #[test]
+        fn params_several_parameters() {
+            assert_eq!(
+                params(&["n=1..2", "k=8"]),
+                Ok(vec![("n".to_string(), vec![1, 2]), ("k".to_string(), vec![8])])
+            );
+            assert_eq!(params(&[]), Ok(Vec::new()));
+        }
+
+        #[test]
+        fn params_ranges_beyond_the_instance_limit() {
+            let last = MAX_TEMPLATE_INSTANCES as i64;
+            assert_eq!(params(&[&format!("n=1..{}", last)]).map(|params| params[0].1.len()), Ok(MAX_TEMPLATE_INSTANCES));
+            assert_eq!(params(&[&format!("n=0..{}", last)]), Err(()));
+            assert_eq!(params(&[&format!("n=0,1..{}", last)]), Err(()));
+            assert_eq!(params(&["n=-9223372036854775808..9223372036854775807"]), Err(()));
+        }
+
+        #[test]
+        fn params_reversed_and_empty_ranges() {
+            assert_eq!(params(&["n=5..3"]), Err(()));
+            assert_eq!(params(&["n=1,5..3"]), Err(()));
+            assert_eq!(params(&["n="]), Err(()));
+            assert_eq!(params(&["n=1.."]), Err(()));
+            assert_eq!(params(&["n=..4"]), Err(()));
+        }
+
+        #[test]
+        fn params_malformed_input() {
+            assert_eq!(params(&["n"]), Err(()));
+            assert_eq!(params(&["n=a"]), Err(()));
+            assert_eq!(params(&["n=1,,2"]), Err(()));
+            assert_eq!(params(&["n=1...3"]), Err(()));
+            assert_eq!(params(&["n=1..2..3"]), Err(()));
+            assert_eq!(params(&["n=1", "k=x"]), Err(()));
+        }
+    }
 }
@@ -25,6 +25,10 @@ fn start() -> Result<(), ()> {
     use execution_user::ExecutionConfig;
     let user_input = Input::new()?;
     let mut program_archive = parser_user::parse_project(&user_input)?;
+    let template_tuples = match user_input.verify_template(){
+        Some(template) => execution_user::template_parameters(&mut program_archive, template, user_input.params())?,
+        None => Vec::new(),
+    };
     type_analysis_user::analyse_project(&mut program_archive)?;
 
     let config = ExecutionConfig {
@@ -47,6 +51,12 @@ fn start() -> Result<(), ()> {
         civer_file: user_input.civer_file().to_string(),
         initial_constraints_file: user_input.initial_constraints_file().to_string(),
         structure_file: user_input.structure_file().to_string(),
+        bounds_file: user_input.bounds_file().to_string(),
+        coverage_file: user_input.coverage_file().to_string(),
+        assumptions_file: user_input.assumptions_file().to_string(),
+        html_file: user_input.html_file().to_string(),
+        dot_file: user_input.dot_file().to_string(),
+        postconditions_file: user_input.postconditions_file().to_string(),
         verification_timeout: user_input.verification_timeout(),
         check_tags: user_input.check_tags(),
         check_postconditions: user_input.check_postconditions(),
@@ -55,7 +65,37 @@ fn start() -> Result<(), ()> {
         add_postconditions_info: user_input.add_postconditions_info(),
         apply_deduction_assigned: user_input.apply_deduction_assigned(),
         file_solved_templates: user_input.file_solved_templates(),
+        print_bounds: user_input.print_bounds(),
+        coverage: user_input.coverage(),
+        assumptions: user_input.assumptions(),
+        html: user_input.html(),
+        dot: user_input.dot(),
+        dot_signal_ranges: user_input.dot_signal_ranges(),
+        infer_postconditions: user_input.infer_postconditions(),
+        suggest_tags: user_input.suggest_tags(),
+        certificates: user_input.certificates(),
+        certificates_file: user_input.certificates_file().to_string(),
+        unsat_core: user_input.unsat_core(),
+        portfolio: user_input.portfolio(),
+        civer_only: user_input.civer_only().clone(),
+        civer_exclude: user_input.civer_exclude().clone(),
+        civer_budget: user_input.civer_budget(),
+        check_completeness: user_input.check_completeness(),
+        check_satisfiable: user_input.check_satisfiable(),
+        counterexamples: user_input.counterexamples(),
+        counterexamples_file: user_input.counterexamples_file().to_string(),
     };
+    if let Some(equivalent_file) = user_input.equivalent_file(){
+        let mut other_archive = parser_user::parse_file(&user_input, equivalent_file)?;
+        type_analysis_user::analyse_project(&mut other_archive)?;
+        return execution_user::check_equivalence(program_archive, other_archive, &config);
+    }
+    if let Some(template) = user_input.verify_template(){
+        return execution_user::verify_template(program_archive, template, &template_tuples, &config);
+    }
+    if !user_input.primes().is_empty(){
+        return execution_user::verify_primes(program_archive, user_input.primes(), &config);
+    }
     let circuit = execution_user::execute_project(program_archive, config)?;
     let compilation_config = CompilerConfig {
         vcp: circuit,
@@ -1,12 +1,25 @@
 use super::input_user::Input;
+use program_structure::ast::{build_main_component, Expression, MainComponent, Meta};
 use program_structure::error_definition::Report;
 use program_structure::program_archive::ProgramArchive;
 use crate::VERSION;
 
 
 pub fn parse_project(input_info: &Input) -> Result<ProgramArchive, ()> {
-    let initial_file = input_info.input_file().to_string();
-    let result_program_archive = parser::run_parser(initial_file, VERSION, input_info.get_link_libraries().to_vec(),input_info.get_spec_libraries().to_vec());
+    // the libraries verified with --verify_template do not need a main component,
+    // it is replaced by the instantiations of the template
+    let default_main = input_info.verify_template().map(|template|
+        build_main_component(Vec::new(), Expression::Call { meta: Meta::new(0, 0), id: template.to_string(), args: Vec::new() })
+    );
+    run_parser(input_info, input_info.input_file().to_string(), default_main)
+}
+
+pub fn parse_file(input_info: &Input, initial_file: String) -> Result<ProgramArchive, ()> {
+    run_parser(input_info, initial_file, None)
+}
+
+fn run_parser(input_info: &Input, initial_file: String, default_main: Option<MainComponent>) -> Result<ProgramArchive, ()> {
+    let result_program_archive = parser::run_parser(initial_file, VERSION, input_info.get_link_libraries().to_vec(),input_info.get_spec_libraries().to_vec(), default_main);
     match result_program_archive {
         Result::Err((file_library, report_collection)) => {
             Report::print_reports(&report_collection, &file_library);
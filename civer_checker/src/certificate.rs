@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use json::JsonValue;
+use num_bigint_dig::BigInt;
+
+pub type LinearExpression = HashMap<usize, BigInt>;
+pub type Interval = (BigInt, BigInt);
+
+// A * B - C = 0, the signal 0 is the constant 1
+#[derive(Clone)]
+pub struct Constraint {
+    pub a: LinearExpression,
+    pub b: LinearExpression,
+    pub c: LinearExpression,
+}
+
+impl Constraint {
+    // used to compare constraints independently of the order of their coefficients
+    pub fn key(&self) -> String {
+        fn expression_key(le: &LinearExpression) -> String {
+            let mut entries: Vec<(&usize, &BigInt)> =
+                le.iter().filter(|(_, coef)| **coef != BigInt::from(0)).collect();
+            entries.sort();
+            entries.iter().map(|(s, coef)| format!("{}:{}", s, coef)).collect::<Vec<_>>().join(",")
+        }
+        format!("[{}][{}][{}]", expression_key(&self.a), expression_key(&self.b), expression_key(&self.c))
+    }
+
+    pub fn is_linear(&self) -> bool {
+        non_zero(&self.a).is_empty() || non_zero(&self.b).is_empty()
+    }
+}
+
+pub fn non_zero(le: &LinearExpression) -> LinearExpression {
+    le.iter().filter(|(_, coef)| **coef != BigInt::from(0)).map(|(s, c)| (*s, c.clone())).collect()
+}
+
+pub struct BoundStep {
+    pub rule: String,
+    pub signal: usize,
+    pub bounds: Interval,
+    pub constraints: Vec<Constraint>,
+    pub premises: Vec<(usize, Interval)>,
+    pub source: Option<usize>,
+}
+
+pub struct DeterminismStep {
+    pub rule: String,
+    pub signal: usize,
+    pub constraint: Option<Constraint>,
+    pub sources: Vec<usize>,
+}
+
+pub struct SmtProof {
+    pub property: String,
+    pub core_constraints: Vec<Constraint>,
+    pub core_contracts: Vec<String>,
+    pub core_assumptions: Vec<String>,
+}
+
+pub struct Subcomponent {
+    pub template: String,
+    pub inputs: Vec<usize>,
+    pub outputs: Vec<usize>,
+}
+
+pub struct TemplateCertificate {
+    pub template: String,
+    pub results: Vec<(String, String)>,
+    pub inputs: Vec<usize>,
+    pub outputs: Vec<usize>,
+    pub signal_names: HashMap<usize, String>,
+    pub constraints: Vec<Constraint>,
+    pub subcomponents: Vec<Subcomponent>,
+    pub bound_steps: Vec<BoundStep>,
+    pub determinism_steps: Vec<DeterminismStep>,
+    pub smt_proofs: Vec<SmtProof>,
+}
+
+impl TemplateCertificate {
+    pub fn result(&self, property: &str) -> Option<&String> {
+        self.results.iter().find(|(p, _)| p == property).map(|(_, r)| r)
+    }
+
+    pub fn signal_name(&self, signal: usize) -> String {
+        match self.signal_names.get(&signal) {
+            Some(name) => format!("{} ({})", name, signal),
+            None => format!("signal {}", signal),
+        }
+    }
+}
+
+pub struct Certificates {
+    pub field: BigInt,
+    pub templates: Vec<TemplateCertificate>,
+}
+
+pub fn parse_certificates(text: &str) -> Result<Certificates, String> {
+    let value = json::parse(text).map_err(|e| format!("invalid json: {}", e))?;
+    let field = parse_bigint(&value["field"], "field")?;
+    let mut templates = Vec::new();
+    for template in value["templates"].members() {
+        templates.push(parse_template(template, &field)?);
+    }
+    Ok(Certificates { field, templates })
+}
+
+fn parse_template(value: &JsonValue, field: &BigInt) -> Result<TemplateCertificate, String> {
+    let template = value["template"].as_str().ok_or("missing template name")?.to_string();
+    let mut results = Vec::new();
+    for (property, result) in value["results"].entries() {
+        results.push((property.to_string(), result.as_str().unwrap_or("").to_string()));
+    }
+    let mut signal_names = HashMap::new();
+    for (signal, name) in value["signal_names"].entries() {
+        signal_names.insert(parse_signal(signal)?, name.as_str().unwrap_or("").to_string());
+    }
+    let mut subcomponents = Vec::new();
+    for subcomponent in value["subcomponents"].members() {
+        subcomponents.push(Subcomponent {
+            template: subcomponent["template"].as_str().unwrap_or("").to_string(),
+            inputs: parse_signals(&subcomponent["inputs"])?,
+            outputs: parse_signals(&subcomponent["outputs"])?,
+        });
+    }
+    let mut bound_steps = Vec::new();
+    for step in value["bound_steps"].members() {
+        let mut premises = Vec::new();
+        for premise in step["premises"].members() {
+            let signal = premise[0].as_usize().ok_or("invalid premise")?;
+            let bounds = (parse_bigint(&premise[1], "premise")?, parse_bigint(&premise[2], "premise")?);
+            premises.push((signal, bounds));
+        }
+        bound_steps.push(BoundStep {
+            rule: step["rule"].as_str().unwrap_or("").to_string(),
+            signal: step["signal"].as_usize().ok_or("invalid signal of bound step")?,
+            bounds: (parse_bigint(&step["min"], "min")?, parse_bigint(&step["max"], "max")?),
+            constraints: parse_constraints(&step["constraints"], field)?,
+            premises,
+            source: step["source"].as_usize(),
+        });
+    }
+    let mut determinism_steps = Vec::new();
+    for step in value["determinism_steps"].members() {
+        let constraint =
+            if step["constraint"].is_null() { None } else { Some(parse_constraint(&step["constraint"], field)?) };
+        determinism_steps.push(DeterminismStep {
+            rule: step["rule"].as_str().unwrap_or("").to_string(),
+            signal: step["signal"].as_usize().ok_or("invalid signal of determinism step")?,
+            constraint,
+            sources: parse_signals(&step["sources"])?,
+        });
+    }
+    let mut smt_proofs = Vec::new();
+    for proof in value["smt_proofs"].members() {
+        smt_proofs.push(SmtProof {
+            property: proof["property"].as_str().unwrap_or("").to_string(),
+            core_constraints: parse_constraints(&proof["core_constraints"], field)?,
+            core_contracts: proof["core_contracts"]
+                .members()
+                .map(|c| c.as_str().unwrap_or("").to_string())
+                .collect(),
+            core_assumptions: proof["core_assumptions"]
+                .members()
+                .map(|c| c.as_str().unwrap_or("").to_string())
+                .collect(),
+        });
+    }
+    Ok(TemplateCertificate {
+        template,
+        results,
+        inputs: parse_signals(&value["inputs"])?,
+        outputs: parse_signals(&value["outputs"])?,
+        signal_names,
+        constraints: parse_constraints(&value["constraints"], field)?,
+        subcomponents,
+        bound_steps,
+        determinism_steps,
+        smt_proofs,
+    })
+}
+
+fn parse_constraints(value: &JsonValue, field: &BigInt) -> Result<Vec<Constraint>, String> {
+    value.members().map(|c| parse_constraint(c, field)).collect()
+}
+
+fn parse_constraint(value: &JsonValue, field: &BigInt) -> Result<Constraint, String> {
+    Ok(Constraint {
+        a: parse_linear_expression(&value[0], field)?,
+        b: parse_linear_expression(&value[1], field)?,
+        c: parse_linear_expression(&value[2], field)?,
+    })
+}
+
+fn parse_linear_expression(value: &JsonValue, field: &BigInt) -> Result<LinearExpression, String> {
+    let mut le = HashMap::new();
+    for (signal, coef) in value.entries() {
+        let coef = parse_bigint(coef, "coefficient")? % field;
+        let coef = if coef < BigInt::from(0) { coef + field } else { coef };
+        le.insert(parse_signal(signal)?, coef);
+    }
+    Ok(le)
+}
+
+fn parse_signals(value: &JsonValue) -> Result<Vec<usize>, String> {
+    value.members().map(|s| s.as_usize().ok_or_else(|| "invalid signal".to_string())).collect()
+}
+
+fn parse_signal(value: &str) -> Result<usize, String> {
+    value.parse::<usize>().map_err(|_| format!("invalid signal {}", value))
+}
+
+fn parse_bigint(value: &JsonValue, what: &str) -> Result<BigInt, String> {
+    value
+        .as_str()
+        .and_then(|s| BigInt::parse_bytes(s.as_bytes(), 10))
+        .ok_or_else(|| format!("invalid {}", what))
+}
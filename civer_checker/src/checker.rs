@@ -0,0 +1,656 @@
+use std::collections::{HashMap, HashSet};
+use num_bigint_dig::BigInt;
+use crate::certificate::{
+    non_zero, Certificates, Constraint, BoundStep, Interval, LinearExpression,
+    TemplateCertificate,
+};
+
+type Bounds = HashMap<usize, Interval>;
+
+pub struct TemplateReport {
+    pub template: String,
+    // properties whose proof was completely re-checked
+    pub checked: Vec<String>,
+    // facts the proofs rely on that can not be re-checked from the certificate
+    pub trusted: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+impl TemplateReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+pub fn check_certificates(certificates: &Certificates) -> Vec<TemplateReport> {
+    let mut by_name = HashMap::new();
+    for template in &certificates.templates {
+        by_name.insert(template.template.as_str(), template);
+    }
+    certificates
+        .templates
+        .iter()
+        .map(|template| check_template(template, &certificates.field, &by_name))
+        .collect()
+}
+
+fn check_template(
+    certificate: &TemplateCertificate,
+    field: &BigInt,
+    templates: &HashMap<&str, &TemplateCertificate>,
+) -> TemplateReport {
+    let mut report = TemplateReport {
+        template: certificate.template.clone(),
+        checked: Vec::new(),
+        trusted: Vec::new(),
+        errors: Vec::new(),
+    };
+    let constraints: HashSet<String> = certificate.constraints.iter().map(|c| c.key()).collect();
+
+    let assumptions = certificate.bound_steps.iter().filter(|s| s.rule == "assumption").count();
+    if assumptions > 0 {
+        report.trusted.push(format!(
+            "{} bounds given by the preconditions, tags and postconditions of the subcomponents",
+            assumptions
+        ));
+    }
+    let state = check_bound_steps(certificate, field, &constraints, &mut report);
+
+    for property in ["tags", "postconditions", "safety"].iter() {
+        if certificate.result(property).map(|r| r.as_str()) != Some("VERIFIED") {
+            continue;
+        }
+        if *property == "safety" && !certificate.determinism_steps.is_empty() {
+            match check_determinism_steps(certificate, field, &state, &constraints) {
+                Ok(contracts) => {
+                    report.checked.push("safety".to_string());
+                    for name in contracts {
+                        report.trusted.push(contract_description(&name, templates));
+                    }
+                }
+                Err(error) => report.errors.push(format!("safety: {}", error)),
+            }
+            continue;
+        }
+        let proofs: Vec<_> =
+            certificate.smt_proofs.iter().filter(|p| p.property == *property).collect();
+        if proofs.is_empty() {
+            report.errors.push(format!("{}: verified without a proof in the certificate", property));
+        }
+        for proof in proofs {
+            if proof.core_constraints.iter().any(|c| !constraints.contains(&c.key())) {
+                report.errors.push(format!(
+                    "{}: the unsat core contains constraints that are not in the template",
+                    property
+                ));
+            } else {
+                report.trusted.push(format!(
+                    "{}: SMT proof using {} constraints, {} assumptions and {} contracts of the subcomponents",
+                    property,
+                    proof.core_constraints.len(),
+                    proof.core_assumptions.len(),
+                    proof.core_contracts.len()
+                ));
+            }
+        }
+    }
+    report
+}
+
+fn contract_description(name: &str, templates: &HashMap<&str, &TemplateCertificate>) -> String {
+    let verified = templates
+        .get(name)
+        .and_then(|t| t.result("safety"))
+        .is_some_and(|r| r == "VERIFIED");
+    if verified {
+        format!("safety of the subcomponent {}", name)
+    } else {
+        format!("safety of the subcomponent {} (not verified in this certificate)", name)
+    }
+}
+
+// Replays the bound steps. Each step is checked using only its premises, which must
+// be implied by the bounds obtained in the previous steps
+fn check_bound_steps(
+    certificate: &TemplateCertificate,
+    field: &BigInt,
+    constraints: &HashSet<String>,
+    report: &mut TemplateReport,
+) -> Bounds {
+    let mut state = Bounds::new();
+    for (i, step) in certificate.bound_steps.iter().enumerate() {
+        if step.rule == "assumption" {
+            state.insert(step.signal, step.bounds.clone());
+            continue;
+        }
+        match check_bound_step(step, field, &state, constraints) {
+            Ok(()) => {
+                state.insert(step.signal, step.bounds.clone());
+            }
+            Err(error) => report.errors.push(format!(
+                "bound step {} ({}) of {}: {}",
+                i,
+                step.rule,
+                certificate.signal_name(step.signal),
+                error
+            )),
+        }
+    }
+    state
+}
+
+fn check_bound_step(
+    step: &BoundStep,
+    field: &BigInt,
+    state: &Bounds,
+    constraints: &HashSet<String>,
+) -> Result<(), String> {
+    if step.constraints.is_empty() {
+        return Err("the step does not contain any constraint".to_string());
+    }
+    if step.constraints.iter().any(|c| !constraints.contains(&c.key())) {
+        return Err("uses a constraint that is not in the template".to_string());
+    }
+    let mut premises = Bounds::new();
+    for (signal, bounds) in &step.premises {
+        if !contains(bounds, &get_bounds(state, *signal, field)) {
+            return Err(format!(
+                "the premise [{}, {}] of the signal {} does not follow from the previous steps",
+                bounds.0, bounds.1, signal
+            ));
+        }
+        premises.insert(*signal, bounds.clone());
+    }
+    let constraint = &step.constraints[0];
+    let candidates = match step.rule.as_str() {
+        "integrity_domain" => vec![integrity_domain(constraint, step.signal, field)?],
+        "linear_bounds" => vec![derive_bounds(constraint, step.signal, &premises, field)?],
+        "binary_selector" => binary_selector(constraint, step.signal, &premises, field),
+        "one_hot_selector" => one_hot_selector(&step.constraints, step.signal, &premises, field),
+        "bit_decomposition" => {
+            let source = step.source.ok_or("missing the decomposed signal")?;
+            vec![bit_decomposition(constraint, source, step.signal, &premises, field)?]
+        }
+        rule => return Err(format!("unknown rule {}", rule)),
+    };
+    let previous = get_bounds(state, step.signal, field);
+    if candidates.iter().any(|derived| is_sound_conclusion(&step.bounds, derived, &previous)) {
+        Ok(())
+    } else {
+        Err(format!("the bounds [{}, {}] do not follow from the rule", step.bounds.0, step.bounds.1))
+    }
+}
+
+// Replays the derivation of the values of the signals from the inputs and returns the
+// subcomponents whose safety is used
+fn check_determinism_steps(
+    certificate: &TemplateCertificate,
+    field: &BigInt,
+    state: &Bounds,
+    constraints: &HashSet<String>,
+) -> Result<Vec<String>, String> {
+    let mut determined: HashSet<usize> = HashSet::new();
+    determined.insert(0);
+    let mut contracts = Vec::new();
+    for step in &certificate.determinism_steps {
+        let valid = match step.rule.as_str() {
+            "input" => certificate.inputs.contains(&step.signal),
+            "fixed" => state.get(&step.signal).is_some_and(|(min, max)| min == max),
+            "constraint" => step.constraint.as_ref().is_some_and(|c| {
+                constraints.contains(&c.key()) && determines_signal(c, step.signal, &determined)
+            }),
+            "bit_decomposition" => step.constraint.as_ref().is_some_and(|c| {
+                constraints.contains(&c.key())
+                    && step.sources.len() == 1
+                    && determined.contains(&step.sources[0])
+                    && is_unique_decomposition(c, step.sources[0], step.signal, state, field)
+            }),
+            "subcomponent" => {
+                let subcomponent = certificate.subcomponents.iter().find(|s| {
+                    s.outputs.contains(&step.signal)
+                        && s.inputs.iter().all(|i| determined.contains(i))
+                });
+                if let Some(subcomponent) = subcomponent {
+                    if !contracts.contains(&subcomponent.template) {
+                        contracts.push(subcomponent.template.clone());
+                    }
+                }
+                subcomponent.is_some()
+            }
+            _ => false,
+        };
+        if !valid {
+            return Err(format!(
+                "invalid step ({}) for {}",
+                step.rule,
+                certificate.signal_name(step.signal)
+            ));
+        }
+        determined.insert(step.signal);
+    }
+    match certificate.outputs.iter().find(|s| !determined.contains(s)) {
+        Some(output) => {
+            Err(format!("the value of {} is not determined", certificate.signal_name(*output)))
+        }
+        None => Ok(contracts),
+    }
+}
+
+// the signal is the only unknown signal of the constraint and it appears linearly
+fn determines_signal(constraint: &Constraint, signal: usize, determined: &HashSet<usize>) -> bool {
+    let c = non_zero(&constraint.c);
+    if !c.contains_key(&signal) {
+        return false;
+    }
+    let quadratic_determined = constraint.is_linear()
+        || non_zero(&constraint.a).keys().chain(non_zero(&constraint.b).keys()).all(|s| determined.contains(s));
+    quadratic_determined && c.keys().all(|s| *s == signal || determined.contains(s))
+}
+
+// x = k + sum w_i * b_i with binary b_i, distinct powers of two w_i and no overflow,
+// so the value of x determines the value of each b_i
+fn is_unique_decomposition(
+    constraint: &Constraint,
+    source: usize,
+    bit: usize,
+    state: &Bounds,
+    field: &BigInt,
+) -> bool {
+    let decomposition = match solve_decomposition(constraint, source, state, field) {
+        Some(decomposition) => decomposition,
+        None => return false,
+    };
+    let mut weights = HashSet::new();
+    decomposition.bits.iter().any(|(b, _)| *b == bit)
+        && decomposition.bits.iter().all(|(_, w)| is_power_of_two(w) && weights.insert(w.clone()))
+}
+
+struct Decomposition {
+    offset: BigInt,
+    bits: Vec<(usize, BigInt)>,
+}
+
+// solves the linear constraint for the source as source = offset + sum w_i * b_i,
+// checking that the b_i are binary and that the sum can not overflow the field
+fn solve_decomposition(
+    constraint: &Constraint,
+    source: usize,
+    bounds: &Bounds,
+    field: &BigInt,
+) -> Option<Decomposition> {
+    if !constraint.is_linear() {
+        return None;
+    }
+    let c = non_zero(&constraint.c);
+    let inverse = inverse(c.get(&source)?, field);
+    let mut offset = BigInt::from(0);
+    let mut bits = Vec::new();
+    let mut total = BigInt::from(0);
+    for (s, coef) in &c {
+        if *s == source {
+            continue;
+        }
+        let value = modulo(&(-(coef * &inverse)), field);
+        if *s == 0 {
+            offset = value;
+        } else {
+            if !is_binary(bounds, *s, field) {
+                return None;
+            }
+            total += &value;
+            bits.push((*s, value));
+        }
+    }
+    if &offset + &total < *field {
+        Some(Decomposition { offset, bits })
+    } else {
+        None
+    }
+}
+
+// (x - a) * (x - b) = 0 ==> x in [min(a, b), max(a, b)]
+fn integrity_domain(constraint: &Constraint, signal: usize, field: &BigInt) -> Result<Interval, String> {
+    if !non_zero(&constraint.c).is_empty() {
+        return Err("the constraint is not of the form (x - a) * (x - b) = 0".to_string());
+    }
+    let root_a = root(&non_zero(&constraint.a), signal, field)?;
+    let root_b = root(&non_zero(&constraint.b), signal, field)?;
+    Ok(hull(&(root_a.clone(), root_a), &(root_b.clone(), root_b)))
+}
+
+// root of c * x + k
+fn root(le: &LinearExpression, signal: usize, field: &BigInt) -> Result<BigInt, String> {
+    if le.keys().any(|s| *s != signal && *s != 0) {
+        return Err("the constraint is not of the form (x - a) * (x - b) = 0".to_string());
+    }
+    let coef = le.get(&signal).ok_or("the signal does not appear in the constraint")?;
+    let constant = le.get(&0).cloned().unwrap_or_else(|| BigInt::from(0));
+    Ok(modulo(&(-(constant * inverse(coef, field))), field))
+}
+
+// Bounds of the signal using the constraint A * B - C = 0 where the signal appears in C
+// with coefficient 1 or -1 and interval arithmetic over the representatives of the signals
+fn derive_bounds(
+    constraint: &Constraint,
+    signal: usize,
+    bounds: &Bounds,
+    field: &BigInt,
+) -> Result<Interval, String> {
+    let a = non_zero(&constraint.a);
+    let b = non_zero(&constraint.b);
+    let mut c = non_zero(&constraint.c);
+    let product = if constraint.is_linear() {
+        (BigInt::from(0), BigInt::from(0))
+    } else if a.contains_key(&signal) || b.contains_key(&signal) {
+        return Err("the signal appears in the quadratic part of the constraint".to_string());
+    } else {
+        interval_product(&linear_bounds(&a, bounds, field), &linear_bounds(&b, bounds, field))
+    };
+    let coef = c.remove(&signal).ok_or("the signal does not appear in the linear part")?;
+    let coef = signed(&coef, field);
+    let rest = linear_bounds(&c, bounds, field);
+    // coef * x = A * B - rest
+    let value = if coef == BigInt::from(1) {
+        (&product.0 - &rest.1, &product.1 - &rest.0)
+    } else if coef == BigInt::from(-1) {
+        (&rest.0 - &product.1, &rest.1 - &product.0)
+    } else {
+        return Err("the coefficient of the signal is not 1 or -1".to_string());
+    };
+    if floor_div(&value.0, field) != floor_div(&value.1, field) {
+        return Err("the value of the signal can take any value of the field".to_string());
+    }
+    Ok((modulo(&value.0, field), modulo(&value.1, field)))
+}
+
+// (a * s) * B - C = 0 with s binary: the union of the bounds of a * v * B - C = 0
+// for v = 0 and v = 1
+fn binary_selector(constraint: &Constraint, signal: usize, bounds: &Bounds, field: &BigInt) -> Vec<Interval> {
+    let a = non_zero(&constraint.a);
+    let b = non_zero(&constraint.b);
+    let mut candidates = Vec::new();
+    for (side, other) in [(&a, &b), (&b, &a)].iter() {
+        if side.len() != 1 {
+            continue;
+        }
+        let (selector, coef) = side.iter().next().unwrap();
+        if *selector == 0 || *selector == signal || !is_binary(bounds, *selector, field) {
+            continue;
+        }
+        let cases: Vec<Result<Interval, String>> = (0..2)
+            .map(|value| {
+                let case = fix_selector(constraint, &(coef * BigInt::from(value)), other, field);
+                derive_bounds(&case, signal, bounds, field)
+            })
+            .collect();
+        if let (Ok(zero), Ok(one)) = (&cases[0], &cases[1]) {
+            candidates.push(hull(zero, one));
+        }
+    }
+    candidates
+}
+
+// the linear constraint k * B - C = 0
+fn fix_selector(constraint: &Constraint, k: &BigInt, other: &LinearExpression, field: &BigInt) -> Constraint {
+    let mut c = non_zero(&constraint.c);
+    for (s, coef) in other {
+        let current = c.get(s).cloned().unwrap_or_else(|| BigInt::from(0));
+        c.insert(*s, modulo(&(current - k * coef), field));
+    }
+    Constraint { a: LinearExpression::new(), b: LinearExpression::new(), c: non_zero(&c) }
+}
+
+// x = k + sum w_i * s_i with sum s_i = 1 and binary s_i: the union of the bounds of
+// each of the possible selections
+fn one_hot_selector(constraints: &[Constraint], signal: usize, bounds: &Bounds, field: &BigInt) -> Vec<Interval> {
+    let mut candidates = Vec::new();
+    for group in &constraints[1..] {
+        let selectors = match one_hot_group(group, bounds, field) {
+            Some(selectors) => selectors,
+            None => continue,
+        };
+        let mut result: Option<Interval> = None;
+        let mut valid = true;
+        for selected in &selectors {
+            let mut case = bounds.clone();
+            for s in &selectors {
+                let value = BigInt::from(if s == selected { 1 } else { 0 });
+                case.insert(*s, (value.clone(), value));
+            }
+            match derive_bounds(&constraints[0], signal, &case, field) {
+                Ok(derived) => {
+                    result = Some(match result {
+                        Some(current) => hull(&current, &derived),
+                        None => derived,
+                    })
+                }
+                Err(_) => valid = false,
+            }
+        }
+        if let (true, Some(result)) = (valid, result) {
+            candidates.push(result);
+        }
+    }
+    candidates
+}
+
+// k - k * sum s_i = 0 with binary s_i, so exactly one of the s_i is 1
+fn one_hot_group(constraint: &Constraint, bounds: &Bounds, field: &BigInt) -> Option<Vec<usize>> {
+    if !constraint.is_linear() {
+        return None;
+    }
+    let c = non_zero(&constraint.c);
+    let constant = c.get(&0)?;
+    let coef_selectors = modulo(&(-constant), field);
+    let mut selectors = Vec::new();
+    for (s, coef) in &c {
+        if *s == 0 {
+            continue;
+        }
+        if *coef != coef_selectors || !is_binary(bounds, *s, field) {
+            return None;
+        }
+        selectors.push(*s);
+    }
+    if selectors.is_empty() || BigInt::from(selectors.len()) >= *field {
+        return None;
+    }
+    selectors.sort();
+    Some(selectors)
+}
+
+// x = k + sum w_i * b_i with binary b_i and no overflow: if w_i > max(x) - k then b_i = 0
+fn bit_decomposition(
+    constraint: &Constraint,
+    source: usize,
+    bit: usize,
+    bounds: &Bounds,
+    field: &BigInt,
+) -> Result<Interval, String> {
+    let decomposition = solve_decomposition(constraint, source, bounds, field)
+        .ok_or("the constraint is not a decomposition in binary signals")?;
+    let weight = decomposition
+        .bits
+        .iter()
+        .find(|(b, _)| *b == bit)
+        .map(|(_, w)| w.clone())
+        .ok_or("the signal is not one of the bits of the decomposition")?;
+    let (min, max) = get_bounds(bounds, source, field);
+    if min >= BigInt::from(0) && weight > &max - &decomposition.offset {
+        Ok((BigInt::from(0), BigInt::from(0)))
+    } else {
+        Err("the weight of the bit is not greater than the maximum value of the signal".to_string())
+    }
+}
+
+fn get_bounds(bounds: &Bounds, signal: usize, field: &BigInt) -> Interval {
+    if signal == 0 {
+        return (BigInt::from(1), BigInt::from(1));
+    }
+    match bounds.get(&signal) {
+        Some(interval) => interval.clone(),
+        None => (BigInt::from(0), field - BigInt::from(1)),
+    }
+}
+
+fn is_binary(bounds: &Bounds, signal: usize, field: &BigInt) -> bool {
+    let (min, max) = get_bounds(bounds, signal, field);
+    min >= BigInt::from(0) && max <= BigInt::from(1)
+}
+
+fn linear_bounds(le: &LinearExpression, bounds: &Bounds, field: &BigInt) -> Interval {
+    let mut lower = BigInt::from(0);
+    let mut upper = BigInt::from(0);
+    for (signal, coef) in le {
+        let coef = signed(coef, field);
+        let (min, max) = get_bounds(bounds, *signal, field);
+        if coef >= BigInt::from(0) {
+            lower += &coef * min;
+            upper += &coef * max;
+        } else {
+            lower += &coef * max;
+            upper += &coef * min;
+        }
+    }
+    (lower, upper)
+}
+
+fn interval_product(a: &Interval, b: &Interval) -> Interval {
+    let corners = [&a.0 * &b.0, &a.0 * &b.1, &a.1 * &b.0, &a.1 * &b.1];
+    let min = corners.iter().min().unwrap().clone();
+    let max = corners.iter().max().unwrap().clone();
+    (min, max)
+}
+
+fn hull(a: &Interval, b: &Interval) -> Interval {
+    (std::cmp::min(&a.0, &b.0).clone(), std::cmp::max(&a.1, &b.1).clone())
+}
+
+fn contains(outer: &Interval, inner: &Interval) -> bool {
+    outer.0 <= inner.0 && inner.1 <= outer.1
+}
+
+// the new bounds must contain all the values allowed by the rule and the previous bounds
+fn is_sound_conclusion(conclusion: &Interval, derived: &Interval, previous: &Interval) -> bool {
+    if previous.0 < BigInt::from(0) {
+        return contains(conclusion, derived);
+    }
+    let intersection = (std::cmp::max(&derived.0, &previous.0).clone(), std::cmp::min(&derived.1, &previous.1).clone());
+    intersection.0 > intersection.1 || contains(conclusion, &intersection)
+}
+
+// representative of the coefficient with the smallest absolute value
+fn signed(coef: &BigInt, field: &BigInt) -> BigInt {
+    if coef * BigInt::from(2) > *field {
+        coef - field
+    } else {
+        coef.clone()
+    }
+}
+
+fn modulo(value: &BigInt, field: &BigInt) -> BigInt {
+    let result = value % field;
+    if result < BigInt::from(0) {
+        result + field
+    } else {
+        result
+    }
+}
+
+fn floor_div(value: &BigInt, field: &BigInt) -> BigInt {
+    let quotient = value / field;
+    if value % field < BigInt::from(0) {
+        quotient - BigInt::from(1)
+    } else {
+        quotient
+    }
+}
+
+fn inverse(value: &BigInt, field: &BigInt) -> BigInt {
+    value.modpow(&(field - BigInt::from(2)), field)
+}
+
+fn is_power_of_two(value: &BigInt) -> bool {
+    let zero = BigInt::from(0);
+    value > &zero && (value & (value - BigInt::from(1))) == zero
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field() -> BigInt {
+        BigInt::from(97)
+    }
+
+    fn le(entries: &[(usize, i64)]) -> LinearExpression {
+        entries.iter().map(|(s, c)| (*s, modulo(&BigInt::from(*c), &field()))).collect()
+    }
+
+    fn interval(min: i64, max: i64) -> Interval {
+        (BigInt::from(min), BigInt::from(max))
+    }
+
+    #[test]
+    fn derive_bounds_of_linear_constraint() {
+        // x = a + 2 * b with a, b binary
+        let constraint = Constraint { a: le(&[]), b: le(&[]), c: le(&[(1, 1), (2, -1), (3, -2)]) };
+        let mut bounds = Bounds::new();
+        bounds.insert(2, interval(0, 1));
+        bounds.insert(3, interval(0, 1));
+        assert_eq!(derive_bounds(&constraint, 1, &bounds, &field()).unwrap(), interval(0, 3));
+    }
+
+    #[test]
+    fn derive_bounds_of_product_with_negative_factors() {
+        // x = (a - 2) * (b - 2) with a, b in [0, 4]
+        let constraint =
+            Constraint { a: le(&[(2, 1), (0, -2)]), b: le(&[(3, 1), (0, -2)]), c: le(&[(1, 1)]) };
+        let mut bounds = Bounds::new();
+        bounds.insert(2, interval(0, 4));
+        bounds.insert(3, interval(0, 4));
+        // the values in [-4, -1] wrap around the field
+        assert!(derive_bounds(&constraint, 1, &bounds, &field()).is_err());
+        assert_eq!(interval_product(&interval(-2, 2), &interval(-2, 2)), interval(-4, 4));
+    }
+
+    #[test]
+    fn binary_selector_keeps_correlated_signals() {
+        // (c0 - c1) * s = c0 - x with binary c0, c1 and s
+        let constraint =
+            Constraint { a: le(&[(2, 1), (3, -1)]), b: le(&[(4, 1)]), c: le(&[(1, -1), (2, 1)]) };
+        let mut bounds = Bounds::new();
+        for signal in 2..5 {
+            bounds.insert(signal, interval(0, 1));
+        }
+        assert!(derive_bounds(&constraint, 1, &bounds, &field()).is_err());
+        assert_eq!(binary_selector(&constraint, 1, &bounds, &field()), vec![interval(0, 1)]);
+    }
+
+    #[test]
+    fn integrity_domain_roots() {
+        // (x - 3) * (x - 5) = 0
+        let constraint = Constraint { a: le(&[(1, 1), (0, -3)]), b: le(&[(1, 1), (0, -5)]), c: le(&[]) };
+        assert_eq!(integrity_domain(&constraint, 1, &field()).unwrap(), interval(3, 5));
+    }
+
+    #[test]
+    fn bit_decomposition_prunes_high_bits() {
+        // x = b1 + 2 * b2 + 4 * b3 with x in [0, 3]
+        let constraint =
+            Constraint { a: le(&[]), b: le(&[]), c: le(&[(1, 1), (2, -1), (3, -2), (4, -4)]) };
+        let mut bounds = Bounds::new();
+        bounds.insert(1, interval(0, 3));
+        for bit in 2..5 {
+            bounds.insert(bit, interval(0, 1));
+        }
+        assert_eq!(bit_decomposition(&constraint, 1, 4, &bounds, &field()).unwrap(), interval(0, 0));
+        assert!(bit_decomposition(&constraint, 1, 3, &bounds, &field()).is_err());
+    }
+
+    #[test]
+    fn conclusion_must_contain_derived_values() {
+        assert!(is_sound_conclusion(&interval(0, 3), &interval(0, 5), &interval(0, 3)));
+        assert!(!is_sound_conclusion(&interval(0, 2), &interval(0, 5), &interval(0, 3)));
+    }
+}
@@ -0,0 +1,50 @@
+mod certificate;
+mod checker;
+
+use std::process;
+
+// Checks the certificates written by civer_circom --certificates without
+// trusting the deduction rules of CIVER
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 2 {
+        eprintln!("Usage: civer_checker <file_certificates.json>");
+        process::exit(2);
+    }
+    let text = match std::fs::read_to_string(&args[1]) {
+        Ok(text) => text,
+        Err(error) => {
+            eprintln!("Could not read {}: {}", args[1], error);
+            process::exit(2);
+        }
+    };
+    let certificates = match certificate::parse_certificates(&text) {
+        Ok(certificates) => certificates,
+        Err(error) => {
+            eprintln!("Invalid certificate file: {}", error);
+            process::exit(2);
+        }
+    };
+
+    let reports = checker::check_certificates(&certificates);
+    let mut valid = true;
+    for report in &reports {
+        println!("Template {}", report.template);
+        for property in &report.checked {
+            println!("    CHECKED: {}", property);
+        }
+        for fact in &report.trusted {
+            println!("    TRUSTED: {}", fact);
+        }
+        for error in &report.errors {
+            println!("    ERROR: {}", error);
+        }
+        valid &= report.is_valid();
+    }
+    if valid {
+        println!("All the certificates are valid");
+    } else {
+        println!("Some certificates are not valid");
+        process::exit(1);
+    }
+}
@@ -19,6 +19,7 @@ use super::execution_data::{ExecutedProgram, ExecutedTemplate, PreExecutedTempla
 use super::{
     ast::*, ArithmeticError, FileID, ProgramArchive, Report, ReportCode, ReportCollection
 };
+use circom_algebra::modular_arithmetic;
 use circom_algebra::num_bigint::BigInt;
 use std::collections::{HashMap, BTreeMap};
 use std::mem;
@@ -92,7 +93,8 @@ impl Default for FoldedValue {
 enum ExecutionError {
     NonQuadraticConstraint,
     FalseAssert,
-    ArraySizeTooBig
+    ArraySizeTooBig,
+    UnsupportedSpecificationCall,
 }
 
 enum ExecutionWarning {
@@ -264,8 +266,35 @@ fn execute_statement(
         Substitution { meta, var, access, op, rhe, .. } => {
             let access_information = treat_accessing(meta, access, program_archive, runtime, flags)?;
             let r_folded = execute_expression(rhe, program_archive, runtime, flags)?;
+            let possible_hints = if flags.completeness && actual_node.is_some() && *op != AssignOp::AssignVar {
+                if runtime.block_type == BlockType::Known {
+                    Option::Some(execute_expression_hint(rhe, program_archive, runtime, flags)?)
+                } else {
+                    // the value is only computed in some of the executions
+                    Option::Some(ExpressionSlice::new(&None))
+                }
+            } else {
+                Option::None
+            };
             let possible_constraint =
                 perform_assign(meta, var, *op, &access_information, r_folded, actual_node, runtime, program_archive, flags)?;
+            if let (Option::Some(node), Option::Some(hints), Option::Some(constrained)) = (actual_node.as_mut(), &possible_hints, &possible_constraint) {
+                for i in 0..AExpressionSlice::get_number_of_cells(&constrained.right){
+                    let access = treat_result_with_memory_error(
+                        AExpressionSlice::get_access_index(&constrained.right, i),
+                        meta,
+                        &mut runtime.runtime_errors,
+                        &runtime.call_trace,
+                    )?;
+                    let full_symbol = format!("{}{}", constrained.left, create_index_appendix(&access));
+                    let hint = if hints.is_single() {
+                        ExpressionSlice::access_value_by_index(hints, 0).ok().flatten()
+                    } else {
+                        ExpressionSlice::access_value_by_index(hints, i).ok().flatten()
+                    };
+                    node.add_hint(full_symbol, hint);
+                }
+            }
             if let Option::Some(node) = actual_node {
                 if *op == AssignOp::AssignConstraintSignal || (*op == AssignOp::AssignSignal && flags.inspect){
                     debug_assert!(possible_constraint.is_some());
@@ -306,7 +335,7 @@ fn execute_statement(
                                 let symbol = AExpr::Signal { symbol: full_symbol };
                                 let expr = AExpr::sub(&symbol, &value_right, &p);
                                 let ctr = AExpr::transform_expression_to_constraint_form(expr, &p).unwrap();
-                                node.add_constraint(ctr);
+                                node.add_constraint(ctr, meta);
                             }
                         } else if let AssignOp::AssignSignal = op {// needs fix, check case arrays
                             //debug_assert!(possible_constraint.is_some());
@@ -392,7 +421,7 @@ fn execute_statement(
                 )
                 .unwrap();
                 if let Option::Some(node) = actual_node {
-                    node.add_constraint(constraint_expression);
+                    node.add_constraint(constraint_expression, meta);
                 }    
             }
             Option::None
@@ -2156,6 +2185,17 @@ fn execute_prefix_op(
 //************************************************* Specifications execution *****************************************
 
 
+// checks if the semantics of the tag depends on its value
+pub fn tag_expression_has_value(expression: &crate::ast::Expression, name_signal: &String) -> bool{
+    use program_structure::ast::Expression::*;
+    match expression{
+        Variable { name, access, .. } => name != name_signal || !access.is_empty(),
+        InfixOp { lhe, rhe, .. } => tag_expression_has_value(lhe, name_signal) || tag_expression_has_value(rhe, name_signal),
+        PrefixOp { rhe, .. } => tag_expression_has_value(rhe, name_signal),
+        _ => false,
+    }
+}
+
 pub fn execute_tag_expression(
     expression: &crate::ast::Expression, 
     name_signal: &String,
@@ -2206,25 +2246,22 @@ fn execute_expression_specification(
             Ok(expression.clone())
         }
         Variable { meta, name, access, .. } => {
-            let possible_result = if ExecutionEnvironment::has_signal(&runtime.environment, name) {
-                execute_signal(meta, name, access, program_archive, runtime, flags)?
-            } else if ExecutionEnvironment::has_component(&runtime.environment, name) {
-                execute_component(meta, name, access, program_archive, runtime, flags)?
-            } else if ExecutionEnvironment::has_variable(&runtime.environment, name) {
-                execute_variable(meta, name, access, program_archive, runtime, flags)?
-            } else {
-                unreachable!()
-            };
-
-            match possible_result.spec_vars{
-                Some(spec_slice) =>{
-                    Ok(MemorySlice::unwrap_to_single(spec_slice).unwrap())
-                }
-                None => {
-                    Err(())
-                }
+            let spec_slice = execute_variable_specification(meta, name, access, program_archive, runtime, flags)?;
+            Ok(MemorySlice::unwrap_to_single(spec_slice).unwrap())
+        }
+        Call { meta, id, args } => {
+            let mut arguments = Vec::new();
+            for arg in args{
+                let argument = match arg{
+                    Variable { meta, name, access } => execute_variable_specification(meta, name, access, program_archive, runtime, flags)?,
+                    _ => ExpressionSlice::new(&Some(execute_expression_specification(arg, program_archive, runtime, flags)?)),
+                };
+                arguments.push(argument);
             }
-
+            let result = call_function_specification(id, arguments, program_archive, runtime.constants.get_p(), 0)
+                .and_then(single_value_specification)
+                .map_err(|_| ExecutionError::UnsupportedSpecificationCall);
+            treat_result_with_execution_error(result, meta, &mut runtime.runtime_errors, &runtime.call_trace)
         }
         InfixOp { meta, lhe, infix_op, rhe, .. } => {
             let l_value = execute_expression_specification(lhe, program_archive, runtime, flags)?;
@@ -2242,6 +2279,26 @@ fn execute_expression_specification(
     }
 }
 
+fn execute_variable_specification(
+    meta: &Meta,
+    name: &String,
+    access: &[Access],
+    program_archive: &ProgramArchive,
+    runtime: &mut RuntimeInformation,
+    flags: FlagsExecution
+) -> Result<ExpressionSlice, ()>{
+    let possible_result = if ExecutionEnvironment::has_signal(&runtime.environment, name) {
+        execute_signal(meta, name, access, program_archive, runtime, flags)?
+    } else if ExecutionEnvironment::has_component(&runtime.environment, name) {
+        execute_component(meta, name, access, program_archive, runtime, flags)?
+    } else if ExecutionEnvironment::has_variable(&runtime.environment, name) {
+        execute_variable(meta, name, access, program_archive, runtime, flags)?
+    } else {
+        unreachable!()
+    };
+    possible_result.spec_vars.ok_or(())
+}
+
 fn execute_infix_op_specification(
     meta: Meta, 
     infix: crate::ast::ExpressionInfixOpcode,
@@ -2301,7 +2358,7 @@ fn execute_infix_op_specification(
                         }
                     }
                     Lesser =>{
-                        if v_l <=v_r{
+                        if v_l < v_r{
                             BigInt::from(1)
                         } else{
                             BigInt::from(0)
@@ -2330,7 +2387,7 @@ fn execute_infix_op_specification(
                     }
                     BitOr => v_l | v_r,
                     BitAnd => v_l & v_r,
-                    BitXor => v_l * v_r,
+                    BitXor => v_l ^ v_r,
                     BoolOr => {
                         if v_l == BigInt::from(0) && v_r == BigInt::from(0){
                             BigInt::from(0)
@@ -2392,6 +2449,581 @@ fn execute_prefix_op_specification(
 }
 
 
+//************************************************* Reference functions in specifications *****************************************
+
+// The functions called in the specifications are executed symbolically: the loops are unrolled
+// using the values of the parameters of the template and the conditions that depend on the
+// signals become inline switches, merging the values assigned in both branches. As in circom,
+// the values are field elements: the arithmetic is reduced modulo p and the comparisons use the
+// signed representation of the values
+type SpecEnvironment = HashMap<String, ExpressionSlice>;
+
+// bounds the recursion of the functions whose base case depends on the signals
+const MAX_SPECIFICATION_CALL_DEPTH: usize = 100;
+// bounds the size (in nodes) of the expressions built by the functions
+const MAX_SPECIFICATION_SIZE: usize = 10_000;
+// bounds the iterations of each loop of the functions, the loops that do not finish before
+// are not encoded
+const MAX_SPECIFICATION_ITERATIONS: usize = 100_000;
+
+fn call_function_specification(
+    id: &str,
+    arguments: Vec<ExpressionSlice>,
+    program_archive: &ProgramArchive,
+    field: &BigInt,
+    depth: usize,
+) -> Result<ExpressionSlice, ()>{
+    if !program_archive.contains_function(id) || depth >= MAX_SPECIFICATION_CALL_DEPTH{
+        return Err(());
+    }
+    let function = program_archive.get_function_data(id);
+    let mut environment = SpecEnvironment::new();
+    for (name, argument) in function.get_name_of_params().iter().zip(arguments){
+        let (route, values) = argument.destruct();
+        let mut reduced_values = Vec::new();
+        for value in values{
+            // the signals are already in [0, p)
+            reduced_values.push(match value{
+                Some(value) if value.is_variable() => Some(value),
+                Some(value) => Some(reduce_specification(value, field)?),
+                None => None,
+            });
+        }
+        environment.insert(name.clone(), ExpressionSlice::new_array(route, reduced_values));
+    }
+    match execute_statement_function_specification(function.get_body(), &mut environment, program_archive, field, depth + 1)?{
+        Some(value) => Ok(value),
+        None => Err(()),
+    }
+}
+
+fn single_value_specification(slice: ExpressionSlice) -> Result<Expression, ()>{
+    if slice.is_single(){
+        MemorySlice::unwrap_to_single(slice).ok_or(())
+    } else{
+        Err(())
+    }
+}
+
+fn number_value_specification(expression: &Expression) -> Option<BigInt>{
+    match expression{
+        Expression::Number(_, value) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+fn number_specification(value: BigInt) -> Expression{
+    Expression::Number(Meta::new(0, 0), value)
+}
+
+fn infix_specification(lhe: Expression, infix_op: ExpressionInfixOpcode, rhe: Expression) -> Result<Expression, ()>{
+    bounded_specification(Expression::InfixOp { meta: Meta::new(0, 0), lhe: Box::new(lhe), infix_op, rhe: Box::new(rhe) })
+}
+
+fn switch_specification(cond: Expression, if_true: Expression, if_false: Expression) -> Result<Expression, ()>{
+    bounded_specification(Expression::InlineSwitchOp {
+        meta: Meta::new(0, 0),
+        cond: Box::new(cond),
+        if_true: Box::new(if_true),
+        if_false: Box::new(if_false),
+    })
+}
+
+fn bounded_specification(expression: Expression) -> Result<Expression, ()>{
+    if specification_size(&expression) > MAX_SPECIFICATION_SIZE{
+        Err(())
+    } else{
+        Ok(expression)
+    }
+}
+
+fn specification_size(expression: &Expression) -> usize{
+    use Expression::*;
+    match expression{
+        InfixOp { lhe, rhe, .. } => 1 + specification_size(lhe) + specification_size(rhe),
+        PrefixOp { rhe, .. } => 1 + specification_size(rhe),
+        InlineSwitchOp { cond, if_true, if_false, .. } => {
+            1 + specification_size(cond) + specification_size(if_true) + specification_size(if_false)
+        }
+        _ => 1,
+    }
+}
+
+// structural equality of the expressions built by the functions, used to merge only the values
+// that differ in the branches of a condition
+fn same_specification(left: &Expression, right: &Expression) -> bool{
+    use Expression::*;
+    match (left, right){
+        (Number(_, l), Number(_, r)) => l == r,
+        (Variable { name: l, access: l_access, .. }, Variable { name: r, access: r_access, .. }) => {
+            l == r && l_access.is_empty() && r_access.is_empty()
+        }
+        (InfixOp { lhe: l_lhe, infix_op: l_op, rhe: l_rhe, .. }, InfixOp { lhe: r_lhe, infix_op: r_op, rhe: r_rhe, .. }) => {
+            l_op == r_op && same_specification(l_lhe, r_lhe) && same_specification(l_rhe, r_rhe)
+        }
+        (PrefixOp { prefix_op: l_op, rhe: l_rhe, .. }, PrefixOp { prefix_op: r_op, rhe: r_rhe, .. }) => {
+            l_op == r_op && same_specification(l_rhe, r_rhe)
+        }
+        (InlineSwitchOp { cond: l_cond, if_true: l_true, if_false: l_false, .. }, InlineSwitchOp { cond: r_cond, if_true: r_true, if_false: r_false, .. }) => {
+            same_specification(l_cond, r_cond) && same_specification(l_true, r_true) && same_specification(l_false, r_false)
+        }
+        _ => false,
+    }
+}
+
+// the values that come from the specification of the template are integers
+fn reduce_specification(value: Expression, field: &BigInt) -> Result<Expression, ()>{
+    match number_value_specification(&value){
+        Some(value) => Ok(number_specification(((value % field) + field) % field)),
+        None => infix_specification(value, ExpressionInfixOpcode::Mod, number_specification(field.clone())),
+    }
+}
+
+// signed representation used by the comparisons of circom, the values above p/2 are negative
+fn signed_specification(value: Expression, field: &BigInt) -> Result<Expression, ()>{
+    let half = field / BigInt::from(2);
+    let is_negative = infix_specification(value.clone(), ExpressionInfixOpcode::Greater, number_specification(half))?;
+    let negative = infix_specification(value.clone(), ExpressionInfixOpcode::Sub, number_specification(field.clone()))?;
+    switch_specification(is_negative, negative, value)
+}
+
+// the operands of the boolean operations are compared with 0, so that they are not encoded as
+// the bitwise operations of the specifications of the templates
+fn boolean_specification(value: Expression) -> Result<Expression, ()>{
+    use ExpressionInfixOpcode::*;
+    match &value{
+        Expression::InfixOp { infix_op: Lesser | LesserEq | Greater | GreaterEq | Eq | NotEq | BoolAnd | BoolOr | BoolImplication, .. }
+        | Expression::PrefixOp { prefix_op: ExpressionPrefixOpcode::BoolNot, .. } => Ok(value),
+        _ => infix_specification(value, NotEq, number_specification(BigInt::from(0))),
+    }
+}
+
+fn is_mask_specification(value: &Expression) -> bool{
+    match number_value_specification(value){
+        Some(value) => value > BigInt::from(0) && (&value & (&value + 1)) == BigInt::from(0),
+        None => false,
+    }
+}
+
+// the operations that depend on the signals are only built if they can be encoded in the
+// solvers, the rest are rejected when the specification is executed
+fn execute_infix_op_function_specification(
+    infix_op: ExpressionInfixOpcode,
+    l_value: Expression,
+    r_value: Expression,
+    field: &BigInt,
+) -> Result<Expression, ()>{
+    use ExpressionInfixOpcode::*;
+    use modular_arithmetic as ma;
+    if let (Some(l), Some(r)) = (number_value_specification(&l_value), number_value_specification(&r_value)){
+        let value = match infix_op{
+            Mul => ma::mul(&l, &r, field),
+            Add => ma::add(&l, &r, field),
+            Sub => ma::sub(&l, &r, field),
+            Div => ma::div(&l, &r, field).map_err(|_| ())?,
+            Pow => ma::pow(&l, &r, field),
+            IntDiv => ma::idiv(&l, &r, field).map_err(|_| ())?,
+            Mod => ma::mod_op(&l, &r, field).map_err(|_| ())?,
+            ShiftL => ma::shift_l(&l, &r, field).map_err(|_| ())?,
+            ShiftR => ma::shift_r(&l, &r, field).map_err(|_| ())?,
+            LesserEq => ma::lesser_eq(&l, &r, field),
+            GreaterEq => ma::greater_eq(&l, &r, field),
+            Lesser => ma::lesser(&l, &r, field),
+            Greater => ma::greater(&l, &r, field),
+            Eq => ma::eq(&l, &r, field),
+            NotEq => ma::not_eq(&l, &r, field),
+            BoolOr => ma::bool_or(&l, &r, field),
+            BoolAnd => ma::bool_and(&l, &r, field),
+            BoolImplication => ma::bool_or(&ma::not(&l, field), &r, field),
+            BitOr => ma::bit_or(&l, &r, field),
+            BitAnd => ma::bit_and(&l, &r, field),
+            BitXor => ma::bit_xor(&l, &r, field),
+        };
+        return Ok(number_specification(value));
+    }
+    match infix_op{
+        Mul | Add | Sub => reduce_specification(infix_specification(l_value, infix_op, r_value)?, field),
+        // the shifts are only encoded when the number of bits is known and below p/2, as in
+        // circom the bits of the left shifts beyond the bit width of the field are discarded
+        ShiftL | ShiftR => match number_value_specification(&r_value){
+            Some(bits) if bits <= field / BigInt::from(2) => {
+                let shifted = infix_specification(l_value, infix_op, r_value)?;
+                if infix_op == ShiftL{
+                    let mask = (BigInt::from(1) << field.bits()) - BigInt::from(1);
+                    reduce_specification(infix_specification(shifted, BitAnd, number_specification(mask))?, field)
+                } else{
+                    Ok(shifted)
+                }
+            }
+            _ => Err(()),
+        },
+        BitAnd if is_mask_specification(&r_value) => infix_specification(l_value, infix_op, r_value),
+        BitAnd if is_mask_specification(&l_value) => infix_specification(r_value, infix_op, l_value),
+        IntDiv | Mod | Eq | NotEq => infix_specification(l_value, infix_op, r_value),
+        BoolOr | BoolAnd | BoolImplication => infix_specification(boolean_specification(l_value)?, infix_op, boolean_specification(r_value)?),
+        Lesser | LesserEq | Greater | GreaterEq => {
+            let signed = |value: Expression| match number_value_specification(&value){
+                Some(number) if number > field / BigInt::from(2) => Ok(number_specification(number - field)),
+                Some(_) => Ok(value),
+                None => signed_specification(value, field),
+            };
+            infix_specification(signed(l_value)?, infix_op, signed(r_value)?)
+        }
+        Div | Pow | BitOr | BitXor | BitAnd => Err(()),
+    }
+}
+
+fn execute_prefix_op_function_specification(
+    prefix_op: ExpressionPrefixOpcode,
+    value: Expression,
+    field: &BigInt,
+) -> Result<Expression, ()>{
+    use ExpressionPrefixOpcode::*;
+    match (number_value_specification(&value), prefix_op){
+        (Some(v), Sub) => Ok(number_specification(modular_arithmetic::prefix_sub(&v, field))),
+        (Some(v), BoolNot) => Ok(number_specification(modular_arithmetic::not(&v, field))),
+        (Some(v), Complement) => Ok(number_specification(modular_arithmetic::complement_256(&v, field))),
+        (None, Sub) => execute_infix_op_function_specification(ExpressionInfixOpcode::Sub, number_specification(BigInt::from(0)), value, field),
+        (None, BoolNot) => bounded_specification(Expression::PrefixOp { meta: Meta::new(0, 0), prefix_op, rhe: Box::new(boolean_specification(value)?) }),
+        (None, Complement) => Err(()),
+    }
+}
+
+fn indexes_specification(
+    access: &[Access],
+    environment: &SpecEnvironment,
+    program_archive: &ProgramArchive,
+    field: &BigInt,
+    depth: usize,
+) -> Result<Vec<SliceCapacity>, ()>{
+    use num_traits::ToPrimitive;
+    let mut indexes = Vec::new();
+    for acc in access{
+        match acc{
+            Access::ArrayAccess(index) => {
+                let value = single_value_specification(execute_expression_function_specification(index, environment, program_archive, field, depth)?)?;
+                indexes.push(number_value_specification(&value).and_then(|v| v.to_usize()).ok_or(())?);
+            }
+            Access::ComponentAccess(_) => return Err(()),
+        }
+    }
+    Ok(indexes)
+}
+
+// returns the value of the return statement reached, if any
+fn execute_statement_function_specification(
+    stmt: &Statement,
+    environment: &mut SpecEnvironment,
+    program_archive: &ProgramArchive,
+    field: &BigInt,
+    depth: usize,
+) -> Result<Option<ExpressionSlice>, ()>{
+    use Statement::*;
+    match stmt{
+        Block { stmts, .. } | InitializationBlock { initializations: stmts, .. } => {
+            for stmt in stmts{
+                if let Some(value) = execute_statement_function_specification(stmt, environment, program_archive, field, depth)?{
+                    return Ok(Some(value));
+                }
+            }
+            Ok(None)
+        }
+        Declaration { name, dimensions, .. } => {
+            let mut route = Vec::new();
+            for dimension in dimensions{
+                let value = single_value_specification(execute_expression_function_specification(dimension, environment, program_archive, field, depth)?)?;
+                route.push(number_value_specification(&value).and_then(|v| num_traits::ToPrimitive::to_usize(&v)).ok_or(())?);
+            }
+            environment.insert(name.clone(), ExpressionSlice::new_with_route(&route, &None));
+            Ok(None)
+        }
+        Substitution { var, access, rhe, .. } => {
+            let value = execute_expression_function_specification(rhe, environment, program_archive, field, depth)?;
+            let indexes = indexes_specification(access, environment, program_archive, field, depth)?;
+            let slice = environment.get_mut(var).ok_or(())?;
+            match ExpressionSlice::insert_values(slice, &indexes, &value, false){
+                Ok(_) | Err(MemoryError::MismatchedDimensionsWeak(..)) => Ok(None),
+                Err(_) => Err(()),
+            }
+        }
+        IfThenElse { cond, if_case, else_case, .. } => {
+            let condition = single_value_specification(execute_expression_function_specification(cond, environment, program_archive, field, depth)?)?;
+            match number_value_specification(&condition){
+                Some(value) if value != BigInt::from(0) => execute_statement_function_specification(if_case, environment, program_archive, field, depth),
+                Some(_) => match else_case{
+                    Some(else_case) => execute_statement_function_specification(else_case, environment, program_archive, field, depth),
+                    None => Ok(None),
+                },
+                None => {
+                    let mut else_environment = environment.clone();
+                    let if_value = execute_statement_function_specification(if_case, environment, program_archive, field, depth)?;
+                    let else_value = match else_case{
+                        Some(else_case) => execute_statement_function_specification(else_case, &mut else_environment, program_archive, field, depth)?,
+                        None => None,
+                    };
+                    match (if_value, else_value){
+                        (Some(if_value), Some(else_value)) => Ok(Some(merge_values_specification(&condition, &if_value, &else_value)?)),
+                        (None, None) => {
+                            for (name, value) in environment.iter_mut(){
+                                if let Some(else_value) = else_environment.get(name){
+                                    *value = merge_values_specification(&condition, value, else_value)?;
+                                }
+                            }
+                            Ok(None)
+                        }
+                        // returning in only one of the branches is not supported
+                        _ => Err(()),
+                    }
+                }
+            }
+        }
+        While { cond, stmt, .. } => {
+            for _ in 0..MAX_SPECIFICATION_ITERATIONS{
+                let condition = single_value_specification(execute_expression_function_specification(cond, environment, program_archive, field, depth)?)?;
+                match number_value_specification(&condition){
+                    Some(value) if value != BigInt::from(0) => {
+                        if let Some(value) = execute_statement_function_specification(stmt, environment, program_archive, field, depth)?{
+                            return Ok(Some(value));
+                        }
+                    }
+                    Some(_) => return Ok(None),
+                    None => return Err(()),
+                }
+            }
+            Err(())
+        }
+        Return { value, .. } => Ok(Some(execute_expression_function_specification(value, environment, program_archive, field, depth)?)),
+        LogCall { .. } | Assert { .. } => Ok(None),
+        _ => Err(()),
+    }
+}
+
+// only the positions whose values differ in both branches become inline switches
+fn merge_values_specification(condition: &Expression, if_value: &ExpressionSlice, else_value: &ExpressionSlice) -> Result<ExpressionSlice, ()>{
+    if if_value.route() != else_value.route(){
+        return Err(());
+    }
+    let (route, if_values) = if_value.clone().destruct();
+    let (_, else_values) = else_value.clone().destruct();
+    let mut values = Vec::new();
+    for (if_value, else_value) in if_values.into_iter().zip(else_values){
+        let value = match (if_value, else_value){
+            (Some(if_value), Some(else_value)) if same_specification(&if_value, &else_value) => Some(if_value),
+            (Some(if_value), Some(else_value)) => Some(switch_specification(condition.clone(), if_value, else_value)?),
+            _ => None,
+        };
+        values.push(value);
+    }
+    Ok(ExpressionSlice::new_array(route, values))
+}
+
+fn execute_expression_function_specification(
+    expression: &Expression,
+    environment: &SpecEnvironment,
+    program_archive: &ProgramArchive,
+    field: &BigInt,
+    depth: usize,
+) -> Result<ExpressionSlice, ()>{
+    use Expression::*;
+    let single = |expression: &Expression| -> Result<Expression, ()>{
+        single_value_specification(execute_expression_function_specification(expression, environment, program_archive, field, depth)?)
+    };
+    match expression{
+        Number(_, value) => Ok(ExpressionSlice::new(&Some(reduce_specification(number_specification(value.clone()), field)?))),
+        Variable { name, access, .. } => {
+            let indexes = indexes_specification(access, environment, program_archive, field, depth)?;
+            ExpressionSlice::access_values(environment.get(name).ok_or(())?, &indexes).map_err(|_| ())
+        }
+        InfixOp { lhe, infix_op, rhe, .. } => {
+            let value = execute_infix_op_function_specification(*infix_op, single(lhe)?, single(rhe)?, field)?;
+            Ok(ExpressionSlice::new(&Some(value)))
+        }
+        PrefixOp { prefix_op, rhe, .. } => {
+            let value = execute_prefix_op_function_specification(*prefix_op, single(rhe)?, field)?;
+            Ok(ExpressionSlice::new(&Some(value)))
+        }
+        InlineSwitchOp { cond, if_true, if_false, .. } => {
+            let condition = single(cond)?;
+            match number_value_specification(&condition){
+                Some(value) if value != BigInt::from(0) => execute_expression_function_specification(if_true, environment, program_archive, field, depth),
+                Some(_) => execute_expression_function_specification(if_false, environment, program_archive, field, depth),
+                None => {
+                    let (if_true, if_false) = (single(if_true)?, single(if_false)?);
+                    if same_specification(&if_true, &if_false){
+                        Ok(ExpressionSlice::new(&Some(if_true)))
+                    } else{
+                        Ok(ExpressionSlice::new(&Some(switch_specification(condition, if_true, if_false)?)))
+                    }
+                }
+            }
+        }
+        Call { id, args, .. } => {
+            let mut arguments = Vec::new();
+            for arg in args{
+                arguments.push(execute_expression_function_specification(arg, environment, program_archive, field, depth)?);
+            }
+            call_function_specification(id, arguments, program_archive, field, depth)
+        }
+        ArrayInLine { values, .. } => {
+            let mut route = Vec::new();
+            let mut cells = Vec::new();
+            for value in values{
+                let (inner_route, inner_cells) = execute_expression_function_specification(value, environment, program_archive, field, depth)?.destruct();
+                route = inner_route;
+                cells.extend(inner_cells);
+            }
+            route.insert(0, values.len());
+            Ok(ExpressionSlice::new_array(route, cells))
+        }
+        UniformArray { value, dimension, .. } => {
+            let size = number_value_specification(&single(dimension)?).and_then(|v| num_traits::ToPrimitive::to_usize(&v)).ok_or(())?;
+            let (mut route, inner_cells) = execute_expression_function_specification(value, environment, program_archive, field, depth)?.destruct();
+            let mut cells = Vec::new();
+            for _ in 0..size{
+                cells.extend(inner_cells.iter().cloned());
+            }
+            route.insert(0, size);
+            Ok(ExpressionSlice::new_array(route, cells))
+        }
+        _ => Err(()),
+    }
+}
+
+
+
+//************************************************* Witness hints *************************************************
+
+// Returns the expressions over the signals that the witness generator computes for the
+// right hand side of an assignment. The subexpressions that only involve known values
+// are folded, and the cells that cannot be represented (calls to functions, unknown
+// values of variables...) are None
+fn execute_expression_hint(
+    expr: &Expression,
+    program_archive: &ProgramArchive,
+    runtime: &mut RuntimeInformation,
+    flags: FlagsExecution
+) -> Result<ExpressionSlice, ()> {
+    use Expression::*;
+    let unknown = ExpressionSlice::new(&None);
+    let hint = match expr {
+        Number(meta, value) => ExpressionSlice::new(&Some(Number(meta.clone(), value.clone()))),
+        Variable { name, .. } => {
+            let folded = execute_expression(expr, program_archive, runtime, flags)?;
+            if ExecutionEnvironment::has_variable(&runtime.environment, name) {
+                let (route, values) = safe_unwrap_to_arithmetic_slice(folded, line!()).destruct();
+                let values = values
+                    .into_iter()
+                    .map(|value| match value {
+                        AExpr::Number { value } => Some(Number(Meta::new(0, 0), value)),
+                        _ => None,
+                    })
+                    .collect();
+                ExpressionSlice::new_array(route, values)
+            } else {
+                folded.spec_vars.unwrap_or(unknown)
+            }
+        }
+        InfixOp { meta, lhe, infix_op, rhe } => {
+            let l_value = single_hint(execute_expression_hint(lhe, program_archive, runtime, flags)?);
+            let r_value = single_hint(execute_expression_hint(rhe, program_archive, runtime, flags)?);
+            match (l_value, r_value) {
+                (Some(l_value), Some(r_value)) => {
+                    let folded = fold_infix_hint(*infix_op, &l_value, &r_value, runtime.constants.get_p());
+                    let value = folded.unwrap_or(InfixOp { meta: meta.clone(), lhe: Box::new(l_value), infix_op: *infix_op, rhe: Box::new(r_value) });
+                    ExpressionSlice::new(&Some(value))
+                }
+                _ => unknown,
+            }
+        }
+        PrefixOp { meta, prefix_op, rhe } => {
+            match single_hint(execute_expression_hint(rhe, program_archive, runtime, flags)?) {
+                Some(Number(_, value)) => {
+                    let field = runtime.constants.get_p();
+                    let folded = match prefix_op {
+                        ExpressionPrefixOpcode::BoolNot => modular_arithmetic::not(&value, field),
+                        ExpressionPrefixOpcode::Sub => modular_arithmetic::prefix_sub(&value, field),
+                        ExpressionPrefixOpcode::Complement => modular_arithmetic::complement_256(&value, field),
+                    };
+                    ExpressionSlice::new(&Some(Number(meta.clone(), folded)))
+                }
+                Some(value) => ExpressionSlice::new(&Some(PrefixOp { meta: meta.clone(), prefix_op: *prefix_op, rhe: Box::new(value) })),
+                None => unknown,
+            }
+        }
+        InlineSwitchOp { meta, cond, if_true, if_false } => {
+            match single_hint(execute_expression_hint(cond, program_archive, runtime, flags)?) {
+                Some(Number(_, value)) => {
+                    // only the selected branch is computed
+                    if modular_arithmetic::as_bool(&value, runtime.constants.get_p()) {
+                        execute_expression_hint(if_true, program_archive, runtime, flags)?
+                    } else {
+                        execute_expression_hint(if_false, program_archive, runtime, flags)?
+                    }
+                }
+                Some(cond_value) => {
+                    let true_value = single_hint(execute_expression_hint(if_true, program_archive, runtime, flags)?);
+                    let false_value = single_hint(execute_expression_hint(if_false, program_archive, runtime, flags)?);
+                    match (true_value, false_value) {
+                        (Some(true_value), Some(false_value)) => ExpressionSlice::new(&Some(InlineSwitchOp {
+                            meta: meta.clone(),
+                            cond: Box::new(cond_value),
+                            if_true: Box::new(true_value),
+                            if_false: Box::new(false_value),
+                        })),
+                        _ => unknown,
+                    }
+                }
+                None => unknown,
+            }
+        }
+        _ => unknown,
+    };
+    Result::Ok(hint)
+}
+
+fn single_hint(hint: ExpressionSlice) -> Option<Expression> {
+    if hint.is_single() {
+        MemorySlice::unwrap_to_single(hint)
+    } else {
+        None
+    }
+}
+
+// folds the operations between known values, the operations that produce an error
+// are kept since they are errors of the witness generation
+fn fold_infix_hint(infix_op: ExpressionInfixOpcode, l_value: &Expression, r_value: &Expression, field: &BigInt) -> Option<Expression> {
+    use ExpressionInfixOpcode::*;
+    use modular_arithmetic::*;
+    let (l_value, r_value) = match (l_value, r_value) {
+        (Expression::Number(_, l_value), Expression::Number(_, r_value)) => (l_value, r_value),
+        _ => return None,
+    };
+    let value = match infix_op {
+        Mul => mul(l_value, r_value, field),
+        Div => div(l_value, r_value, field).ok()?,
+        Add => add(l_value, r_value, field),
+        Sub => sub(l_value, r_value, field),
+        Pow => pow(l_value, r_value, field),
+        IntDiv => idiv(l_value, r_value, field).ok()?,
+        Mod if eq(r_value, &BigInt::from(0), field) == BigInt::from(1) => return None,
+        Mod => mod_op(l_value, r_value, field).ok()?,
+        ShiftL => shift_l(l_value, r_value, field).ok()?,
+        ShiftR => shift_r(l_value, r_value, field).ok()?,
+        LesserEq => lesser_eq(l_value, r_value, field),
+        GreaterEq => greater_eq(l_value, r_value, field),
+        Lesser => lesser(l_value, r_value, field),
+        Greater => greater(l_value, r_value, field),
+        Eq => eq(l_value, r_value, field),
+        NotEq => not_eq(l_value, r_value, field),
+        BoolOr => bool_or(l_value, r_value, field),
+        BoolAnd => bool_and(l_value, r_value, field),
+        BitOr => bit_or(l_value, r_value, field),
+        BitAnd => bit_and(l_value, r_value, field),
+        BitXor => bit_xor(l_value, r_value, field),
+        BoolImplication => return None,
+    };
+    Some(Expression::Number(Meta::new(0, 0), value))
+}
 
 //************************************************* Indexing support *************************************************
 
@@ -2855,6 +3487,10 @@ fn treat_result_with_execution_error<C>(
                     "The size of the array is expected to be a usize".to_string(),
                     ReportCode::RuntimeError,
                 ),
+                UnsupportedSpecificationCall => Report::error(
+                    "The function cannot be executed symbolically in the specification: its loops, array accesses and returns can only depend on the signals through inline conditions, the operations /, **, |, ^, ~ and the shifts by a signal cannot be applied to the signals, & needs a mask of the form 2^k - 1, and its recursion and the size of its result are bounded".to_string(),
+                    ReportCode::RuntimeError,
+                ),
             };
             add_report_to_runtime(report, meta, runtime_errors, call_trace);
             Result::Err(())
@@ -2924,4 +3560,118 @@ fn add_report_to_runtime(
     }
     report.add_note(trace);
     runtime_errors.push(report);
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use program_structure::file_definition::FileLibrary;
+
+    fn fold_specification(infix: ExpressionInfixOpcode, l: i64, r: i64) -> Expression {
+        let number = |v: i64| Expression::Number(Meta::new(0, 0), BigInt::from(v));
+        execute_infix_op_specification(Meta::new(0, 0), infix, number(l), number(r)).unwrap()
+    }
+
+    fn is_number(expression: &Expression, value: i64) -> bool {
+        matches!(expression, Expression::Number(_, v) if *v == BigInt::from(value))
+    }
+
+    #[test]
+    fn lesser_is_strict_in_specifications() {
+        assert!(is_number(&fold_specification(ExpressionInfixOpcode::Lesser, 3, 3), 0));
+        assert!(is_number(&fold_specification(ExpressionInfixOpcode::Lesser, 2, 3), 1));
+        assert!(is_number(&fold_specification(ExpressionInfixOpcode::LesserEq, 3, 3), 1));
+    }
+
+    #[test]
+    fn bit_xor_is_not_a_product_in_specifications() {
+        assert!(is_number(&fold_specification(ExpressionInfixOpcode::BitXor, 6, 3), 5));
+        assert!(is_number(&fold_specification(ExpressionInfixOpcode::BitXor, 5, 5), 0));
+    }
+
+    fn signal_specification(name: &str) -> Expression {
+        Expression::Variable { meta: Meta::new(0, 0), name: name.to_string(), access: Vec::new() }
+    }
+
+    fn fold_function(infix: ExpressionInfixOpcode, l: Expression, r: Expression) -> Result<Expression, ()> {
+        execute_infix_op_function_specification(infix, l, r, &BigInt::from(17))
+    }
+
+    #[test]
+    fn functions_are_evaluated_in_the_field() {
+        let n = |v: i64| number_specification(BigInt::from(v));
+        assert!(is_number(&fold_function(ExpressionInfixOpcode::Sub, n(0), n(1)).unwrap(), 16));
+        // 3 * 6 = 1 mod 17
+        assert!(is_number(&fold_function(ExpressionInfixOpcode::Div, n(1), n(3)).unwrap(), 6));
+        // 16 is -1 in the signed representation
+        assert!(is_number(&fold_function(ExpressionInfixOpcode::Lesser, n(16), n(0)).unwrap(), 1));
+        assert!(fold_function(ExpressionInfixOpcode::Div, n(1), n(0)).is_err());
+
+        let reduced = fold_function(ExpressionInfixOpcode::Sub, signal_specification("x"), n(1)).unwrap();
+        assert!(matches!(reduced, Expression::InfixOp { infix_op: ExpressionInfixOpcode::Mod, .. }));
+        let compared = fold_function(ExpressionInfixOpcode::Lesser, signal_specification("x"), n(3)).unwrap();
+        assert!(matches!(compared, Expression::InfixOp { lhe, .. } if lhe.is_switch()));
+    }
+
+    #[test]
+    fn operations_that_cannot_be_encoded_are_rejected() {
+        let x = || signal_specification("x");
+        let n = |v: i64| number_specification(BigInt::from(v));
+        for infix in [ExpressionInfixOpcode::Div, ExpressionInfixOpcode::Pow, ExpressionInfixOpcode::BitOr, ExpressionInfixOpcode::BitXor] {
+            assert!(fold_function(infix, x(), n(2)).is_err());
+        }
+        assert!(fold_function(ExpressionInfixOpcode::BitAnd, x(), n(6)).is_err());
+        assert!(fold_function(ExpressionInfixOpcode::BitAnd, n(7), x()).is_ok());
+        assert!(fold_function(ExpressionInfixOpcode::ShiftR, n(8), x()).is_err());
+        assert!(fold_function(ExpressionInfixOpcode::ShiftR, x(), n(2)).is_ok());
+        assert!(execute_prefix_op_function_specification(ExpressionPrefixOpcode::Complement, x(), &BigInt::from(17)).is_err());
+    }
+
+    #[test]
+    fn left_shifts_are_masked_to_the_bit_width_of_the_field() {
+        // as in circom, (x << 3) & 31 mod 17 for the 5 bits of 17
+        let shifted = fold_function(ExpressionInfixOpcode::ShiftL, signal_specification("x"), number_specification(BigInt::from(3))).unwrap();
+        let masked = match shifted {
+            Expression::InfixOp { lhe, infix_op: ExpressionInfixOpcode::Mod, rhe, .. } if is_number(&rhe, 17) => *lhe,
+            _ => panic!("the left shift is not reduced modulo p"),
+        };
+        assert!(matches!(masked, Expression::InfixOp { infix_op: ExpressionInfixOpcode::BitAnd, rhe, .. } if is_number(&rhe, 31)));
+        // 12 << 3 = 96 = 0b1100000, whose bits beyond the 5 of the field are discarded
+        assert!(is_number(&fold_function(ExpressionInfixOpcode::ShiftL, number_specification(BigInt::from(12)), number_specification(BigInt::from(3))).unwrap(), 0));
+    }
+
+    #[test]
+    fn loops_beyond_the_iteration_limit_are_rejected() {
+        let meta = || Meta::new(0, 0);
+        let program_archive = ProgramArchive::new(FileLibrary::new(), 0, (Vec::new(), number_specification(BigInt::from(0))), Vec::new(), false).ok().unwrap();
+        let endless = Statement::While {
+            meta: meta(),
+            cond: number_specification(BigInt::from(1)),
+            stmt: Box::new(Statement::Block { meta: meta(), stmts: Vec::new() }),
+        };
+        assert!(execute_statement_function_specification(&endless, &mut SpecEnvironment::new(), &program_archive, &BigInt::from(17), 0).is_err());
+    }
+
+    #[test]
+    fn only_the_values_that_differ_are_merged() {
+        let condition = signal_specification("c");
+        let if_value = ExpressionSlice::new_array(vec![2], vec![Some(signal_specification("x")), Some(number_specification(BigInt::from(1)))]);
+        let else_value = ExpressionSlice::new_array(vec![2], vec![Some(signal_specification("x")), Some(number_specification(BigInt::from(2)))]);
+        let (_, merged) = merge_values_specification(&condition, &if_value, &else_value).unwrap().destruct();
+        assert!(matches!(&merged[0], Some(Expression::Variable { .. })));
+        assert!(matches!(&merged[1], Some(value) if value.is_switch()));
+    }
+
+    #[test]
+    fn expressions_beyond_the_size_limit_are_rejected() {
+        // x * x doubles the size of the expression at each step
+        let mut value = signal_specification("x");
+        let mut steps = 0;
+        while let Ok(squared) = fold_function(ExpressionInfixOpcode::Mul, value.clone(), value.clone()) {
+            value = squared;
+            steps += 1;
+        }
+        assert!(steps < 20);
+        assert!(specification_size(&value) <= MAX_SPECIFICATION_SIZE);
+    }
+}
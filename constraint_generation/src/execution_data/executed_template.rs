@@ -1,9 +1,9 @@
 use super::type_definitions::*;
 use circom_algebra::algebra::ArithmeticExpression;
 use compiler::hir::very_concrete_program::*;
-use dag::DAG;
+use dag::{ConstraintLocation, DAG};
 use num_bigint::BigInt;
-use program_structure::ast::{SignalType, Statement, Expression, TypeSpecification};
+use program_structure::ast::{SignalType, Statement, Expression, TypeSpecification, Meta};
 use std::collections::{HashMap, HashSet, LinkedList};
 use crate::execution_data::AExpressionSlice;
 use crate::execution_data::TagInfo;
@@ -74,6 +74,8 @@ pub struct ExecutedTemplate {
     pub intermediates: SignalCollector,
     pub ordered_signals: Vec<String>,
     pub constraints: Vec<Constraint>,
+    pub constraint_locations: Vec<ConstraintLocation>,
+    pub hints: Vec<(String, Option<Expression>)>,
     pub components: ComponentCollector,
     pub number_of_components: usize,
     pub public_inputs: HashSet<String>,
@@ -125,6 +127,8 @@ impl ExecutedTemplate {
             intermediates: SignalCollector::new(),
             ordered_signals: Vec::new(),
             constraints: Vec::new(),
+            constraint_locations: Vec::new(),
+            hints: Vec::new(),
             components: ComponentCollector::new(),
             number_of_components: 0,
             connexions: Vec::new(),
@@ -200,8 +204,14 @@ impl ExecutedTemplate {
         self.number_of_components += dimensions.iter().fold(1, |p, c| p * (*c));
     }
 
-    pub fn add_constraint(&mut self, constraint: Constraint) {
+    pub fn add_constraint(&mut self, constraint: Constraint, meta: &Meta) {
         self.constraints.push(constraint);
+        self.constraint_locations.push(ConstraintLocation::new(meta));
+    }
+
+    // value computed by the witness generator for a signal assigned with <-- or <==
+    pub fn add_hint(&mut self, signal: String, hint: Option<Expression>) {
+        self.hints.push((signal, hint));
     }
 
     pub fn add_underscored_signal(&mut self, signal: &str) {
@@ -274,7 +284,12 @@ impl ExecutedTemplate {
             let state = State { name: name.clone(), dim: 0 };
             let config = SignalConfig { signal_type: 1, dimensions: dim, is_public: false };
             let specs = self.generate_specifications(&name, tag_specifications);
+            let tags = match self.signal_to_tags.get(name){
+                Some(tags) => tags.keys().cloned().collect(),
+                None => Vec::new(),
+            };
             postconditions_outputs.append(&mut generate_symbols(dag, state, &config, &specs));
+            add_signal_tags(dag, name, dim, &tags);
         }
         for (name, dim) in self.inputs() {
             if self.public_inputs.contains(name) {
@@ -328,16 +343,23 @@ impl ExecutedTemplate {
     }
     fn build_constraints(&self, dag: &mut DAG) {
         
-        for c in &self.constraints {
+        for (c, location) in self.constraints.iter().zip(self.constraint_locations.iter()) {
             let correspondence = dag.get_main().unwrap().correspondence();
             let cc = Constraint::apply_correspondence(c, correspondence);
-            dag.add_constraint(cc);
+            dag.add_constraint(cc, location.clone());
         }
         for s in &self.underscored_signals{
             let correspondence = dag.get_main().unwrap().correspondence();
             let new_s = correspondence.get(s).unwrap().clone();
             dag.add_underscored_signal(new_s);
         }
+        for (s, hint) in &self.hints{
+            let correspondence = dag.get_main().unwrap().correspondence();
+            if let Some(new_s) = correspondence.get(s).cloned(){
+                let new_hint = hint.as_ref().map(|h| h.apply_correspondence(correspondence));
+                dag.add_hint(new_s, new_hint);
+            }
+        }
     }
 
     fn build_specifications(&self, dag: &mut DAG) {
@@ -558,6 +580,18 @@ fn generate_symbols(
     updated_specs
 }
 
+// the tags of a signal are shared by all the positions of the array
+fn add_signal_tags(dag: &mut DAG, name: &String, dimensions: &[usize], tags: &[String]) {
+    match dimensions.split_first() {
+        None => dag.add_signal_tags(name, tags.to_vec()),
+        Some((size, rest)) => {
+            for index in 0..*size {
+                add_signal_tags(dag, &format!("{}[{}]", name, index), rest, tags);
+            }
+        }
+    }
+}
+
 fn as_big_int(exprs: Vec<ArithmeticExpression<String>>) -> Vec<BigInt> {
     let mut numbers = Vec::with_capacity(exprs.len());
     for e in exprs {
@@ -728,6 +762,12 @@ fn update_expression_instantation(
             let value = update_expression_instantation(rhe, new_signal_name);
             Expression::PrefixOp { meta: meta.clone(),  prefix_op: *prefix_op, rhe: Box::new(value) }
         }
+        InlineSwitchOp { meta, cond, if_true, if_false } => {
+            let cond = update_expression_instantation(cond, new_signal_name);
+            let if_true = update_expression_instantation(if_true, new_signal_name);
+            let if_false = update_expression_instantation(if_false, new_signal_name);
+            Expression::InlineSwitchOp { meta: meta.clone(), cond: Box::new(cond), if_true: Box::new(if_true), if_false: Box::new(if_false) }
+        }
 
         _ => {unreachable!("The rest of the expressions are not valid."); }
     }
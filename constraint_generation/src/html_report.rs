@@ -0,0 +1,227 @@
+use super::{verdict_name, StudiedNodes, TemplateDetails};
+use dag::{PossibleResult, TreeConstraints};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+const PROPERTIES: [(&str, &str); 3] = [("tags", "T"), ("postconditions", "P"), ("weak safety", "S")];
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; color: #222; }
+h1 { font-size: 1.5em; }
+h2 { font-size: 1.2em; margin-top: 2em; }
+details { margin-left: 1.5em; }
+details > summary { cursor: pointer; padding: 1px 0; }
+.node { margin-left: 1.5em; padding: 1px 0; }
+.badge { display: inline-block; min-width: 1.2em; text-align: center; border-radius: 3px; color: white; font-size: 0.8em; margin-right: 2px; }
+.verified { background: #2e7d32; }
+.failed { background: #c62828; }
+.unknown { background: #ef6c00; }
+.vacuous { background: #9e9d24; }
+.encoding-error { background: #6a1b9a; }
+.none { background: #9e9e9e; }
+.count { color: #666; font-size: 0.9em; }
+table { border-collapse: collapse; margin-top: 1em; }
+th, td { border: 1px solid #ccc; padding: 3px 8px; text-align: left; vertical-align: top; font-size: 0.9em; }
+th { background: #eee; }
+td ul { margin: 0; padding-left: 1.2em; }
+.toolbar { margin: 1em 0; }
+.toolbar input[type=text] { width: 20em; }
+";
+
+// filters the rows of the table and expands or collapses the tree of components
+const SCRIPT: &str = "
+function filterRows() {
+    var text = document.getElementById('filter').value.toLowerCase();
+    var wrong = document.getElementById('wrong').checked;
+    var rows = document.querySelectorAll('#templates tbody tr');
+    for (var i = 0; i < rows.length; i++) {
+        var row = rows[i];
+        var visible = row.dataset.name.toLowerCase().indexOf(text) >= 0 && (!wrong || row.dataset.verified === 'false');
+        row.style.display = visible ? '' : 'none';
+    }
+}
+function expandTree(open) {
+    var nodes = document.querySelectorAll('#tree details');
+    for (var i = 0; i < nodes.length; i++) {
+        nodes[i].open = open;
+    }
+}
+";
+
+// generates a self-contained HTML report including the tree of components of the circuit,
+// where each template instance is expanded only the first time it appears, and a table
+// with the results, number of constraints, time, rounds and counterexamples of each instance
+pub fn generate(
+    tree_constraints: &TreeConstraints,
+    studied_nodes: &StudiedNodes,
+    details: &HashMap<String, TemplateDetails>,
+    properties: [bool; 3],
+) -> String {
+    let mut components = HashMap::new();
+    let mut constraints = BTreeMap::new();
+    count_components(tree_constraints, &mut components, &mut constraints);
+    let ids: HashMap<&String, usize> = constraints.keys().enumerate().map(|(id, name)| (name, id)).collect();
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>CIVER report: {}</title>\n", escape(tree_constraints.pretty_template_name())));
+    html.push_str(&format!("<style>{}</style>\n<script>{}</script>\n</head>\n<body>\n", STYLE, SCRIPT));
+    html.push_str(&format!("<h1>CIVER verification report: {}</h1>\n", escape(tree_constraints.pretty_template_name())));
+
+    html.push_str("<h2>Summary</h2>\n<ul>\n");
+    html.push_str(&format!("<li>Template instances: {}, components: {}</li>\n", constraints.len(), components.values().sum::<usize>()));
+    for (i, (property, _)) in PROPERTIES.iter().enumerate() {
+        if !properties[i] {
+            continue;
+        }
+        let mut totals: BTreeMap<&str, usize> = BTreeMap::new();
+        for name in constraints.keys() {
+            *totals.entry(verdicts(studied_nodes, name, properties)[i]).or_default() += 1;
+        }
+        let totals: Vec<String> = totals.iter().map(|(verdict, n)| format!("{} {}", n, verdict)).collect();
+        html.push_str(&format!("<li>{}: {}</li>\n", property, totals.join(", ")));
+    }
+    html.push_str("</ul>\n<p>");
+    for (i, (property, letter)) in PROPERTIES.iter().enumerate() {
+        if properties[i] {
+            html.push_str(&format!("<span class=\"badge none\">{}</span> {} &nbsp; ", letter, property));
+        }
+    }
+    html.push_str("</p>\n");
+
+    html.push_str("<h2>Components</h2>\n<div class=\"toolbar\">");
+    html.push_str("<button onclick=\"expandTree(true)\">Expand all</button> <button onclick=\"expandTree(false)\">Collapse all</button></div>\n");
+    html.push_str("<div id=\"tree\">\n");
+    let mut rendered = HashSet::new();
+    render_node(tree_constraints, 1, studied_nodes, properties, &ids, &mut rendered, &mut html);
+    html.push_str("</div>\n");
+
+    html.push_str("<h2>Template instances</h2>\n<div class=\"toolbar\">");
+    html.push_str("<input type=\"text\" id=\"filter\" placeholder=\"Filter by name\" oninput=\"filterRows()\"> ");
+    html.push_str("<label><input type=\"checkbox\" id=\"wrong\" onchange=\"filterRows()\"> Only instances that were not verified</label></div>\n");
+    html.push_str("<table id=\"templates\">\n<thead><tr><th>Template</th>");
+    for (i, (property, _)) in PROPERTIES.iter().enumerate() {
+        if properties[i] {
+            html.push_str(&format!("<th>{}</th>", property));
+        }
+    }
+    html.push_str("<th>Constraints</th><th>Components</th><th>Time (s)</th><th>Rounds</th><th>Counterexamples</th></tr></thead>\n<tbody>\n");
+    for (name, n_constraints) in &constraints {
+        let results = verdicts(studied_nodes, name, properties);
+        let verified = results.iter().all(|verdict| *verdict == "VERIFIED" || *verdict == "-");
+        html.push_str(&format!("<tr id=\"t{}\" data-name=\"{}\" data-verified=\"{}\"><td>{}</td>", ids[name], escape(name), verified, escape(name)));
+        for (i, verdict) in results.iter().enumerate() {
+            if properties[i] {
+                html.push_str(&format!("<td><span class=\"badge {}\">&nbsp;</span> {}</td>", verdict_class(verdict), verdict));
+            }
+        }
+        html.push_str(&format!("<td>{}</td><td>{}</td>", n_constraints, components[name]));
+        match details.get(name) {
+            Some(details) => {
+                html.push_str(&format!("<td>{:.3}</td><td>{}</td><td>", details.stats.time, details.stats.rounds));
+                if !details.counterexamples.is_empty() {
+                    html.push_str("<ul>");
+                    for counterexample in &details.counterexamples {
+                        html.push_str(&format!("<li>{}</li>", escape(counterexample)));
+                    }
+                    html.push_str("</ul>");
+                }
+                html.push_str("</td>");
+            }
+            None => html.push_str("<td>-</td><td>-</td><td></td>"),
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+    html
+}
+
+fn count_components(
+    tree_constraints: &TreeConstraints,
+    components: &mut HashMap<String, usize>,
+    constraints: &mut BTreeMap<String, usize>,
+) {
+    *components.entry(tree_constraints.pretty_template_name().clone()).or_default() += 1;
+    constraints.insert(tree_constraints.pretty_template_name().clone(), tree_constraints.constraints().len());
+    for subcomponent in tree_constraints.subcomponents() {
+        count_components(subcomponent, components, constraints);
+    }
+}
+
+// renders the node and, the first time the instance appears, its subcomponents grouped
+// by instance, so that the size of the report depends on the number of instances
+fn render_node(
+    tree_constraints: &TreeConstraints,
+    copies: usize,
+    studied_nodes: &StudiedNodes,
+    properties: [bool; 3],
+    ids: &HashMap<&String, usize>,
+    rendered: &mut HashSet<String>,
+    html: &mut String,
+) {
+    let name = tree_constraints.pretty_template_name();
+    let mut label = String::new();
+    for (i, verdict) in verdicts(studied_nodes, name, properties).iter().enumerate() {
+        if properties[i] {
+            label.push_str(&format!("<span class=\"badge {}\" title=\"{}: {}\">{}</span>", verdict_class(verdict), PROPERTIES[i].0, verdict, PROPERTIES[i].1));
+        }
+    }
+    label.push_str(&format!(" <a href=\"#t{}\">{}</a>", ids[name], escape(name)));
+    if copies > 1 {
+        label.push_str(&format!(" <span class=\"count\">&times;{}</span>", copies));
+    }
+    label.push_str(&format!(" <span class=\"count\">({} constraints)</span>", tree_constraints.constraints().len()));
+
+    if tree_constraints.subcomponents().is_empty() {
+        html.push_str(&format!("<div class=\"node\">{}</div>\n", label));
+    } else if !rendered.insert(name.clone()) {
+        html.push_str(&format!("<div class=\"node\">{} <span class=\"count\">(expanded above)</span></div>\n", label));
+    } else {
+        let open = if rendered.len() == 1 { " open" } else { "" };
+        html.push_str(&format!("<details{}><summary>{}</summary>\n", open, label));
+        let mut groups: Vec<(&TreeConstraints, usize)> = Vec::new();
+        for subcomponent in tree_constraints.subcomponents() {
+            match groups.iter_mut().find(|(first, _)| first.pretty_template_name() == subcomponent.pretty_template_name()) {
+                Some((_, copies)) => *copies += 1,
+                None => groups.push((subcomponent, 1)),
+            }
+        }
+        for (subcomponent, copies) in groups {
+            render_node(subcomponent, copies, studied_nodes, properties, ids, rendered, html);
+        }
+        html.push_str("</details>\n");
+    }
+}
+
+// result of each property for the instance, "-" if it was not studied
+fn verdicts(studied_nodes: &StudiedNodes, name: &str, properties: [bool; 3]) -> [&'static str; 3] {
+    match studied_nodes.get(name) {
+        Some((_, (tags, postconditions, safety))) => {
+            let mut results = ["-"; 3];
+            for (i, result) in [tags, postconditions, safety].iter().enumerate() {
+                if properties[i] && **result != PossibleResult::NOSTUDIED {
+                    results[i] = verdict_name(result);
+                }
+            }
+            results
+        }
+        None => ["-"; 3],
+    }
+}
+
+fn verdict_class(verdict: &str) -> &'static str {
+    match verdict {
+        "VERIFIED" => "verified",
+        "FAILED" => "failed",
+        "UNKNOWN" => "unknown",
+        "VACUOUS" => "vacuous",
+        "ENCODING ERROR" => "encoding-error",
+        _ => "none",
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -5,29 +5,39 @@ mod compute_constants;
 mod environment_utils;
 mod execute;
 mod execution_data;
+mod html_report;
+mod progress;
+mod template_filter;
 
 use std::io::BufReader;
 use ansi_term::Colour;
-use circom_algebra::algebra::{ArithmeticError, ArithmeticExpression};
+use circom_algebra::algebra::{ArithmeticError, ArithmeticExpression, ExecutedInequation};
 use compiler::hir::very_concrete_program::VCP;
 use constraint_list::ConstraintList;
 use constraint_writers::ConstraintExporter;
 use dag::DAG;
 use dag::PossibleResult;
 use dag::TreeConstraints;
+use dag::TemplateCertificate;
+use dag::CheckOptions;
+use dag::TagSemantics;
+use dag::VerificationStats;
+use dag::{Assumption, AssumptionKind};
 use execution_data::executed_program::ExportResult;
 use execution_data::ExecutedProgram;
+use progress::Progress;
 use program_structure::ast::{self};
 use program_structure::error_code::ReportCode;
 use program_structure::error_definition::{Report, ReportCollection};
-use program_structure::file_definition::FileID;
+use program_structure::file_definition::{FileID, FileLibrary};
 use program_structure::program_archive::ProgramArchive;
+use program_structure::specification_data::SpecificationInfo;
 use std::rc::Rc;
 use num_bigint_dig::BigInt;
-use std::collections::{HashMap, BTreeMap};
+use std::collections::{HashMap, HashSet, BTreeMap, BTreeSet};
 use std::fs::File;
-use std::io::Write;
 use serde::{Serialize,Deserialize};
+pub use template_filter::TemplateFilter;
 
 
 
@@ -52,13 +62,179 @@ pub struct BuildConfig {
     pub initial_constraints_file: String,
     pub structure_file: String,
     pub apply_deduction_assigned: bool,
-    pub file_solved_templates: Option<String>
+    pub file_solved_templates: Option<String>,
+    pub print_bounds: bool,
+    pub bounds_file: String,
+    pub coverage: bool,
+    pub coverage_file: String,
+    pub assumptions: bool,
+    pub assumptions_file: String,
+    pub html: bool,
+    pub html_file: String,
+    pub dot: bool,
+    pub dot_signal_ranges: bool,
+    pub dot_file: String,
+    pub infer_postconditions: bool,
+    pub suggest_tags: bool,
+    pub postconditions_file: String,
+    pub certificates: bool,
+    pub certificates_file: String,
+    pub unsat_core: bool,
+    pub portfolio: bool,
+    pub template_filter: TemplateFilter,
+    pub budget: Option<u64>,
+    pub check_completeness: bool,
+    pub check_satisfiable: bool,
+    pub counterexamples: bool,
+    pub counterexamples_file: String,
+}
+
+// options of the verification performed by verify_program and verify_tree
+#[derive(Clone)]
+pub struct VerificationOptions {
+    pub prime: String,
+    pub verification_timeout: u64,
+    pub check_tags: bool,
+    pub check_postconditions: bool,
+    pub check_safety: bool,
+    pub add_tags_info: bool,
+    pub add_postconditions_info: bool,
+    pub apply_deduction_assigned: bool,
+    // checks weak safety running several configurations of the solver concurrently
+    pub portfolio: bool,
+    // templates to verify, the rest are NOSTUDIED
+    pub template_filter: TemplateFilter,
+    // results of the templates that are assumed as solved
+    pub solved_templates: HashMap<String, PossibleResult>,
+    pub unsat_core: bool,
+    pub certificates: bool,
+    // checks that the values computed by the witness generator satisfy the constraints
+    pub check_completeness: bool,
+    // checks that the constraints admit a witness for every valid input
+    pub check_satisfiable: bool,
+    // seconds after which the templates that are not started yet are not studied
+    pub budget: Option<u64>,
+}
+
+impl Default for VerificationOptions {
+    fn default() -> Self {
+        VerificationOptions {
+            prime: "bn128".to_string(),
+            verification_timeout: 5000,
+            check_tags: false,
+            check_postconditions: false,
+            check_safety: true,
+            add_tags_info: false,
+            add_postconditions_info: false,
+            apply_deduction_assigned: false,
+            portfolio: false,
+            template_filter: TemplateFilter::default(),
+            solved_templates: HashMap::new(),
+            unsat_core: false,
+            certificates: false,
+            check_completeness: false,
+            check_satisfiable: false,
+            budget: None,
+        }
+    }
+}
+
+impl VerificationOptions {
+    fn check_options(&self, verification_timeout: u64) -> CheckOptions {
+        CheckOptions{
+            verification_timeout,
+            check_tags: self.check_tags,
+            check_postconditions: self.check_postconditions,
+            check_safety: self.check_safety,
+            add_tags_info: self.add_tags_info,
+            add_postconditions_info: self.add_postconditions_info,
+            apply_deduction_assigned: self.apply_deduction_assigned,
+            record_certificates: self.certificates,
+            unsat_core: self.unsat_core,
+            portfolio: self.portfolio,
+        }
+    }
+
+    fn properties(&self) -> [bool; 3] {
+        [self.check_tags, self.check_postconditions, self.check_safety]
+    }
+}
+
+// how the results of the verification are reported, by default nothing is printed nor written
+#[derive(Clone, Default)]
+pub struct OutputOptions {
+    // prints the template being verified and handles the interruptions with Ctrl-C
+    pub progress: bool,
+    // file where the logs of each template are written as soon as it is verified
+    pub civer_file: Option<String>,
+    pub initial_constraints_file: Option<String>,
+    pub structure_file: Option<String>,
+    pub certificates_file: Option<String>,
+    pub html_file: Option<String>,
+    pub assumptions_file: Option<String>,
+    // extends the counterexamples of weak safety to witnesses of the whole circuit
+    pub counterexamples: bool,
+}
+
+type StudiedNodes = HashMap<String, ((usize, usize), (PossibleResult, PossibleResult, PossibleResult))>;
+
+// results of each property for a template, NOSTUDIED if the property was not checked
+#[derive(Clone, Debug)]
+pub struct TemplateVerdict {
+    pub template: String,
+    pub tags: PossibleResult,
+    pub postconditions: PossibleResult,
+    pub safety: PossibleResult,
+    pub stats: VerificationStats,
+    // unproved things the results depend on
+    pub assumptions: Vec<Assumption>,
+}
+
+// details of the verification of a template instance
+#[derive(Default)]
+struct TemplateDetails {
+    stats: VerificationStats,
+    counterexamples: Vec<String>, // messages of the counterexamples found
+    assumptions: Vec<Assumption>,
+}
+
+pub struct VerificationResult {
+    pub templates: Vec<TemplateVerdict>, // sorted by the name of the template
+    // assumptions of the circuit that are not proved by any template
+    pub ledger: Vec<Assumption>,
+    pub logs: Vec<String>,
+    pub reports: ReportCollection,
+    pub certificates: Vec<TemplateCertificate>,
+    // results of check_completeness and check_satisfiable for each template
+    pub completeness: BTreeMap<String, PossibleResult>,
+    pub satisfiability: BTreeMap<String, PossibleResult>,
+    // why the templates that are not started yet were not studied, if the verification was stopped
+    pub stop_reason: Option<&'static str>,
+    studied_nodes: StudiedNodes,
+    details: HashMap<String, TemplateDetails>,
+}
+
+// results collected while verifying the templates of a tree of constraints
+#[derive(Default)]
+struct TreeVerification {
+    studied_nodes: StudiedNodes,
+    details: HashMap<String, TemplateDetails>,
+    certificates: Vec<TemplateCertificate>,
+    reports: ReportCollection,
+    logs: Vec<String>,
+}
+
+impl VerificationResult {
+    pub fn get_template(&self, template: &str) -> Option<&TemplateVerdict> {
+        self.templates.iter().find(|verdict| verdict.template == template)
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
 pub struct FlagsExecution{
     pub verbose: bool,
     pub inspect: bool,
+    pub completeness: bool,
 }
 
 pub type ConstraintWriter = Box<dyn ConstraintExporter>;
@@ -85,6 +261,57 @@ pub struct NodeInfo{
 
 }
 
+#[derive(Deserialize, Serialize, Debug)]
+pub struct SignalBoundsInfo {
+    pub signal: String,
+    pub min: String,
+    pub max: String,
+    pub is_binary: bool,
+    pub bits: Option<usize>, // number of bits needed to represent the signal, None if it may be negative
+    pub fits_64_bits: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct OutputCoverageInfo {
+    pub signal: String,
+    pub tags: Vec<String>,
+    pub postconditions: bool,
+    pub tag_specifications: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct TemplateCoverageInfo {
+    pub constraints: usize,
+    pub only_weak_safety: bool, // the template has no pre/postconditions nor tag specifications
+    pub covered_outputs: usize, // outputs appearing in a postcondition or tag specification
+    pub outputs: Vec<OutputCoverageInfo>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct AssumptionInfo {
+    pub kind: String,
+    pub template: String,
+    pub description: String,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct AssumptionsInfo {
+    pub templates: BTreeMap<String, Vec<AssumptionInfo>>, // assumptions used by each template
+    pub ledger: Vec<AssumptionInfo>, // assumptions not proved by any template
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct CoverageInfo {
+    pub templates: BTreeMap<String, TemplateCoverageInfo>,
+    pub only_weak_safety: Vec<String>,
+    pub not_instantiated: Vec<String>,
+    pub outputs: usize,
+    pub covered_outputs: usize,
+    pub constraints: usize, // constraints of every component of the circuit
+    pub specified_constraints: usize, // constraints of the components with specifications
+    pub percentage_specified_constraints: f64,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct StructureInfo {
     pub timing: TimingInfo,
@@ -96,9 +323,13 @@ pub struct StructureInfo {
 
 pub fn build_circuit(program: ProgramArchive, config: BuildConfig) -> BuildResponse {
     let files = program.file_library.clone();
+    let tag_specifications = program.specifications.clone();
+    let template_names = program.get_template_names().clone();
     let flags = FlagsExecution{
         verbose: config.flag_verbose,
         inspect: config.inspect_constraints,
+        // the witness hints also serve to prove the satisfiability of the constraints
+        completeness: config.check_completeness || config.check_satisfiable,
     };
     let (exe, warnings) = instantiation(&program, flags, &config.prime).map_err(|r| {
         Report::print_reports(&r, &files);
@@ -110,39 +341,562 @@ pub fn build_circuit(program: ProgramArchive, config: BuildConfig) -> BuildRespo
     if config.inspect_constraints {
         Report::print_reports(&warnings, &files);
     }
+    if config.dot {
+        if dag.generate_dot_output(&config.dot_file, config.dot_signal_ranges).is_ok() {
+            println!("{} {}", Colour::Green.paint("Component graph written in:"), config.dot_file);
+        } else {
+            eprintln!("{}", Colour::Red.paint("Could not write the component graph in the given path"));
+        }
+    }
 
-    let always_check = true;
-    if config.check_tags ||config.check_postconditions || config.check_safety || always_check{
-        if !config.civer{
-            eprintln!("{}", Colour::Yellow.paint("Not including tag specifications: in case you want to add extra tag specifications, use the flag --civer followed by the name of the file including the specifications (example: --civer tags.circom)"));
-        }
-        let tree_constraints = dag.map_to_constraint_tree();
-        check_tags(
-            tree_constraints,
-            &config.prime, 
-            config.verification_timeout, 
-            config.check_tags, 
-            config.check_postconditions, 
-            config.check_safety, 
-            config.add_tags_info, 
-            config.add_postconditions_info,
-            config.apply_deduction_assigned,
-            &config.civer_file,
-            &config.initial_constraints_file,
-            &config.structure_file,
-            config.file_solved_templates.clone()
-        );
-        
+    if !config.civer{
+        eprintln!("{}", Colour::Yellow.paint("Not including tag specifications: in case you want to add extra tag specifications, use the flag --civer followed by the name of the file including the specifications (example: --civer tags.circom)"));
+    }
+    let tree_constraints = dag.map_to_constraint_tree();
+    if config.print_bounds{
+        print_bounds(&tree_constraints, &config.prime, &config.bounds_file);
+    }
+    if config.coverage{
+        print_coverage(&tree_constraints, &template_names, &config.coverage_file);
+    }
+    // the inference of specifications shares the budget and the interruptions with the verification
+    if config.infer_postconditions || config.suggest_tags{
+        progress::handle_interruptions();
+    }
+    let progress = Progress::new(0, config.budget, false, None);
+    if config.infer_postconditions{
+        infer_postconditions(&tree_constraints, &config.prime, config.verification_timeout, &progress, &config.postconditions_file);
+    }
+    if config.suggest_tags{
+        suggest_tags(&tree_constraints, &tag_specifications, &config.prime, config.verification_timeout, &progress, &files);
+    }
+    let options = VerificationOptions{ budget: progress.remaining_budget(), ..circuit_verification_options(&config) };
+    let counterexamples = check_tags(tree_constraints, &options, &output_options(&config), &files);
+    if progress::interrupted(){
+        eprintln!("{}", Colour::Red.paint("Verification interrupted, the partial results are written and the circuit is not compiled"));
+        return Result::Err(());
     }
     if config.flag_f {
         sync_dag_and_vcp(&mut vcp, &mut dag);
+        write_counterexamples(&counterexamples, &vcp, &config.prime, &config.counterexamples_file);
         Result::Ok((Box::new(dag), vcp))
     } else {
         let list = simplification_process(&mut vcp, dag, &config);
+        write_counterexamples(&counterexamples, &vcp, &config.prime, &config.counterexamples_file);
         Result::Ok((Box::new(list), vcp))
     }
 }
 
+// proves that the main components of both programs produce the same outputs for the
+// same inputs, the logs of the verification are written in the given file
+pub fn check_equivalence(program: ProgramArchive, other: ProgramArchive, prime: &String,
+    verification_timeout: u64, flag_verbose: bool, name: &String
+) -> Result<(), ()> {
+    use program_structure::constants::UsefulConstants;
+    let flags = FlagsExecution{
+        verbose: flag_verbose,
+        inspect: false,
+        completeness: false,
+    };
+    let mut trees = Vec::new();
+    for program in [program, other]{
+        let files = program.file_library.clone();
+        let (exe, warnings) = instantiation(&program, flags, prime).map_err(|r| {
+            Report::print_reports(&r, &files);
+        })?;
+        Report::print_reports(&warnings, &files);
+        let (dag, _, _) = export(exe, program, flags).map_err(|r| {
+            Report::print_reports(&r, &files);
+        })?;
+        trees.push(dag.map_to_constraint_tree());
+    }
+    let field = UsefulConstants::new(prime).get_p().clone();
+    let (result, logs) = trees[0].check_equivalence(&trees[1], &field, verification_timeout);
+    if std::fs::write(name, logs.concat()).is_err(){
+        eprintln!("{}", Colour::Red.paint("Could not write the logs of the verification in the given path"));
+    }
+
+    println!("--------------------------------------------");
+    println!("--------------------------------------------");
+    println!("------- CIVER EQUIVALENCE RESULTS ----------");
+    println!("--------------------------------------------");
+    println!("--------------------------------------------\n");
+    match result{
+        PossibleResult::VERIFIED => println!("-> Both circuits produce the same outputs for every valid input :)"),
+        PossibleResult::FAILED => println!("-> The circuits are not equivalent, the counterexample is included in {}", name),
+        _ => println!("-> CIVER could not verify the equivalence of the circuits"),
+    }
+    println!("\n--------------------------------------------");
+    println!("--------------------------------------------\n");
+    Result::Ok(())
+}
+
+// verifies the circuit over each of the given primes and prints, for each template and
+// property, the result obtained in every field, the logs are written in the civer file
+pub fn verify_primes(program: ProgramArchive, primes: &[String], config: &BuildConfig) -> Result<(), ()> {
+    let files = program.file_library.clone();
+    let flags = FlagsExecution{
+        verbose: config.flag_verbose,
+        inspect: false,
+        completeness: false,
+    };
+    let options = verification_options(config, &config.prime);
+    let mut results = PrimesMatrix::new();
+    // the primes whose instantiation failed, the rest of the primes are still verified
+    let mut failed = vec![false; primes.len()];
+    let mut logs = Vec::new();
+    for (position, prime) in primes.iter().enumerate(){
+        println!("{} {}", Colour::Green.paint("Verifying the circuit over"), prime);
+        let dag = match instantiation(&program, flags, prime){
+            Result::Ok((exe, warnings)) => {
+                Report::print_reports(&warnings, &files);
+                export(exe, program.clone(), flags).map(|(dag, _, _)| dag)
+            }
+            Result::Err(reports) => Result::Err(reports),
+        };
+        let dag = match dag{
+            Result::Ok(dag) => dag,
+            Result::Err(reports) => {
+                Report::print_reports(&reports, &files);
+                logs.push(format!("------- PRIME {} -------\n\nThe circuit could not be instantiated\n\n", prime));
+                failed[position] = true;
+                continue;
+            }
+        };
+        let mut result = verify_tree(&dag.map_to_constraint_tree(), &verification_options(config, prime), &OutputOptions::default(), &files);
+        Report::print_reports(&result.reports, &files);
+        logs.push(format!("------- PRIME {} -------\n\n", prime));
+        logs.append(&mut result.logs);
+        add_prime_results(&mut results, position, primes.len(), &result.templates, &options);
+    }
+    if std::fs::write(&config.civer_file, logs.concat()).is_err(){
+        eprintln!("{}", Colour::Red.paint("Could not write the logs of the verification in the given path"));
+    }
+
+    println!("--------------------------------------------");
+    println!("--------------------------------------------");
+    println!("------- CIVER MULTI-PRIME RESULTS ----------");
+    println!("--------------------------------------------");
+    println!("--------------------------------------------\n");
+    let width = results.keys().map(|(template, property)| template.len() + property.len() + 3).max().unwrap_or(0);
+    let mut header = format!("  {:width$}", "", width = width);
+    for prime in primes{
+        header.push_str(&format!(" | {:14}", prime));
+    }
+    println!("{}", header);
+    let mut different = Vec::new();
+    for ((template, property), verdicts) in &results{
+        let differs = depends_on_prime(verdicts);
+        let mut row = format!("{} {:width$}", if differs {"*"} else {" "}, format!("{} ({})", template, property), width = width);
+        for (verdict, failed) in verdicts.iter().zip(&failed){
+            row.push_str(&format!(" | {:14}", prime_verdict(*verdict, *failed)));
+        }
+        if differs{
+            println!("{}", Colour::Red.paint(row));
+            if !different.contains(template){
+                different.push(template.clone());
+            }
+        } else{
+            println!("{}", row);
+        }
+    }
+    println!();
+    if different.is_empty() && failed.contains(&true){
+        println!("-> All templates obtain the same results for every instantiated prime");
+    } else if different.is_empty(){
+        println!("-> All templates obtain the same results for every prime :)");
+    } else{
+        println!("-> Templates whose results depend on the prime: ");
+        for template in &different{
+            println!("    - {}, ", template);
+        }
+    }
+    if failed.contains(&true){
+        println!("-> Primes over which the circuit could not be instantiated: ");
+        for (prime, _) in primes.iter().zip(&failed).filter(|(_, failed)| **failed){
+            println!("    - {}, ", prime);
+        }
+    }
+    println!("\n--------------------------------------------");
+    println!("--------------------------------------------\n");
+    if failed.contains(&true){
+        Result::Err(())
+    } else{
+        Result::Ok(())
+    }
+}
+
+// results of each template and property for every prime, None when the template is not
+// instantiated for the prime
+type PrimesMatrix = BTreeMap<(String, &'static str), Vec<Option<&'static str>>>;
+
+// adds the results obtained for the prime in the given position
+fn add_prime_results(results: &mut PrimesMatrix, position: usize, number_primes: usize, templates: &[TemplateVerdict], options: &VerificationOptions){
+    for verdict in templates{
+        for (property, result) in verdict_results(verdict, options){
+            let verdicts = results.entry((verdict.template.clone(), property)).or_insert_with(|| vec![None; number_primes]);
+            verdicts[position] = Some(verdict_name(result));
+        }
+    }
+}
+
+// cell of the matrix for the prime, ERROR when the circuit could not be instantiated over it
+fn prime_verdict(verdict: Option<&'static str>, failed: bool) -> &'static str{
+    match verdict{
+        Some(verdict) => verdict,
+        None if failed => "ERROR",
+        None => "-",
+    }
+}
+
+// a template that is not instantiated for some prime is not a difference
+fn depends_on_prime(verdicts: &[Option<&str>]) -> bool{
+    let first = verdicts.iter().flatten().next();
+    verdicts.iter().flatten().any(|verdict| Some(verdict) != first)
+}
+
+// name of the result in the reports, following the classification of check_tags
+fn verdict_name(result: &PossibleResult) -> &'static str{
+    match result{
+        PossibleResult::FAILED => "FAILED",
+        PossibleResult::SPURIOUS => "ENCODING ERROR",
+        PossibleResult::VACUOUS => "VACUOUS",
+        PossibleResult::UNKNOWN | PossibleResult::TOO_BIG => "UNKNOWN",
+        PossibleResult::NOSTUDIED => "NOT STUDIED",
+        PossibleResult::VERIFIED | PossibleResult::NOTHING => "VERIFIED",
+    }
+}
+
+// instantiates the main component of the program and verifies each of its templates,
+// without simplifying the constraints or generating code. Nothing is printed or written,
+// the errors and warnings of the instantiation are returned as reports
+pub fn verify_program(program: &ProgramArchive, options: &VerificationOptions) -> Result<VerificationResult, ReportCollection> {
+    let flags = FlagsExecution{
+        verbose: false,
+        inspect: false,
+        completeness: false,
+    };
+    let (exe, mut warnings) = execute::constraint_execution(program, flags, &options.prime)?;
+    let (dag, _, mut export_warnings) = exe.export(program.clone(), flags)?;
+    warnings.append(&mut export_warnings);
+    let mut result = verify_tree(&dag.map_to_constraint_tree(), options, &OutputOptions::default(), &program.file_library);
+    warnings.append(&mut result.reports);
+    result.reports = warnings;
+    Result::Ok(result)
+}
+
+// verifies each of the templates of the tree of constraints, the output options say whether
+// the progress is printed and where the logs of each template are written once it is verified
+pub fn verify_tree(tree_constraints: &TreeConstraints, options: &VerificationOptions, output: &OutputOptions, files: &FileLibrary) -> VerificationResult {
+    use program_structure::constants::UsefulConstants;
+    let field = UsefulConstants::new(&options.prime).get_p().clone();
+    let mut log_file = output.civer_file.as_ref().and_then(|name| match File::create(name){
+        Ok(file) => Some(file),
+        Err(_) => {
+            eprintln!("{}", Colour::Red.paint("Could not write the logs of the verification in the given path"));
+            None
+        }
+    });
+    let mut to_check = BTreeSet::new();
+    templates_to_check(tree_constraints, &options.solved_templates, &options.template_filter, &mut to_check);
+    if output.progress{
+        progress::handle_interruptions();
+    }
+    let mut progress = Progress::new(to_check.len(), options.budget, output.progress, log_file.as_mut());
+    let mut verification = TreeVerification::default();
+    check_tags_node(tree_constraints, &field, options, &mut progress, files, &mut verification);
+
+    let mut completeness = BTreeMap::new();
+    let mut per_template_logs = Vec::new();
+    let reports = &mut verification.reports;
+    if options.check_completeness{
+        check_each_template(tree_constraints, &options.template_filter, &progress, &mut completeness, &mut per_template_logs,
+            &mut |node| node.check_completeness(&field, progress.timeout(options.verification_timeout), files, reports)
+        );
+    }
+    let mut satisfiability = BTreeMap::new();
+    if options.check_satisfiable{
+        check_each_template(tree_constraints, &options.template_filter, &progress, &mut satisfiability, &mut per_template_logs,
+            &mut |node| node.check_satisfiability(&field, progress.timeout(options.verification_timeout), files, reports)
+        );
+    }
+    progress.write_logs(&per_template_logs);
+    verification.logs.append(&mut per_template_logs);
+    let stop_reason = progress.stop_reason();
+
+    let TreeVerification{ studied_nodes, details, certificates, reports, logs } = verification;
+    let ledger = assumptions_ledger(tree_constraints.pretty_template_name(), &studied_nodes, &details, options.properties());
+    let mut templates: Vec<TemplateVerdict> = studied_nodes.iter()
+        .map(|(template, (_, (tags, postconditions, safety)))| {
+            let details = details.get(template);
+            TemplateVerdict{
+                stats: details.map(|details| details.stats.clone()).unwrap_or_default(),
+                assumptions: details.map(|details| details.assumptions.clone()).unwrap_or_default(),
+                template: template.clone(),
+                tags: if options.check_tags {tags.clone()} else {PossibleResult::NOSTUDIED},
+                postconditions: if options.check_postconditions {postconditions.clone()} else {PossibleResult::NOSTUDIED},
+                safety: if options.check_safety {safety.clone()} else {PossibleResult::NOSTUDIED},
+            }
+        })
+        .collect();
+    templates.sort_by(|a, b| a.template.cmp(&b.template));
+    VerificationResult{ templates, ledger, logs, reports, certificates, completeness, satisfiability, stop_reason, studied_nodes, details }
+}
+
+// options of the verification of the circuit, where the properties are the ones given by the user
+fn circuit_verification_options(config: &BuildConfig) -> VerificationOptions {
+    let mut solved_templates = HashMap::new();
+    if let Some(file) = &config.file_solved_templates{
+        read_studied_nodes(file.clone(), &mut solved_templates);
+    }
+    VerificationOptions{
+        prime: config.prime.clone(),
+        verification_timeout: config.verification_timeout,
+        check_tags: config.check_tags,
+        check_postconditions: config.check_postconditions,
+        check_safety: config.check_safety,
+        add_tags_info: config.add_tags_info,
+        add_postconditions_info: config.add_postconditions_info,
+        apply_deduction_assigned: config.apply_deduction_assigned,
+        portfolio: config.portfolio,
+        template_filter: config.template_filter.clone(),
+        solved_templates,
+        unsat_core: config.unsat_core,
+        certificates: config.certificates,
+        check_completeness: config.check_completeness,
+        check_satisfiable: config.check_satisfiable,
+        budget: config.budget,
+    }
+}
+
+fn output_options(config: &BuildConfig) -> OutputOptions {
+    let requested = |flag: bool, name: &String| if flag {Some(name.clone())} else {None};
+    OutputOptions{
+        progress: true,
+        civer_file: Some(config.civer_file.clone()),
+        initial_constraints_file: Some(config.initial_constraints_file.clone()),
+        structure_file: Some(config.structure_file.clone()),
+        certificates_file: requested(config.certificates, &config.certificates_file),
+        html_file: requested(config.html, &config.html_file),
+        assumptions_file: requested(config.assumptions, &config.assumptions_file),
+        counterexamples: config.counterexamples,
+    }
+}
+
+// options of the verifications performed with --primes and --verify_template,
+// weak safety is studied when no property is given
+fn verification_options(config: &BuildConfig, prime: &str) -> VerificationOptions {
+    VerificationOptions{
+        prime: prime.to_string(),
+        verification_timeout: config.verification_timeout,
+        check_tags: config.check_tags,
+        check_postconditions: config.check_postconditions,
+        check_safety: config.check_safety || (!config.check_tags && !config.check_postconditions),
+        add_tags_info: config.add_tags_info,
+        add_postconditions_info: config.add_postconditions_info,
+        apply_deduction_assigned: config.apply_deduction_assigned,
+        portfolio: config.portfolio,
+        template_filter: config.template_filter.clone(),
+        ..VerificationOptions::default()
+    }
+}
+
+// results of the properties studied with the given options
+fn verdict_results<'a>(verdict: &'a TemplateVerdict, options: &VerificationOptions) -> Vec<(&'static str, &'a PossibleResult)> {
+    vec![
+        ("tags", options.check_tags, &verdict.tags),
+        ("postconditions", options.check_postconditions, &verdict.postconditions),
+        ("weak safety", options.check_safety, &verdict.safety),
+    ].into_iter()
+        .filter(|(_, checked, _)| *checked)
+        .map(|(property, _, result)| (property, result))
+        .collect()
+}
+
+fn studied_properties(options: &VerificationOptions) -> Vec<&'static str> {
+    let verdict = TemplateVerdict{
+        template: String::new(),
+        tags: PossibleResult::NOSTUDIED,
+        postconditions: PossibleResult::NOSTUDIED,
+        safety: PossibleResult::NOSTUDIED,
+        stats: VerificationStats::default(),
+        assumptions: Vec::new(),
+    };
+    verdict_results(&verdict, options).into_iter().map(|(property, _)| property).collect()
+}
+
+// bounds the number of instances verified by verify_template, all of them are built in advance
+pub const MAX_TEMPLATE_INSTANCES: usize = 10_000;
+
+// checks that the values of every parameter of the template are given and returns the
+// combinations of values to verify, the main component of the program is replaced by
+// the instantiation of the template with the first of them
+pub fn template_parameters(program: &mut ProgramArchive, template: &str, params: &[(String, Vec<i64>)]) -> Result<Vec<Vec<i64>>, ()> {
+    if !program.contains_template(template){
+        eprintln!("{}", Colour::Red.paint(format!("The template {} does not exist", template)));
+        return Result::Err(());
+    }
+    let names = program.get_template_data(template).get_name_of_params().clone();
+    if let Some((unknown, _)) = params.iter().find(|(name, _)| !names.contains(name)){
+        eprintln!("{}", Colour::Red.paint(format!("The template {} has no parameter {}", template, unknown)));
+        return Result::Err(());
+    }
+    let mut values_of_params = Vec::new();
+    for name in &names{
+        match params.iter().find(|(param, _)| param == name){
+            Some((_, values)) => values_of_params.push(values),
+            None => {
+                eprintln!("{}", Colour::Red.paint(format!("Missing the values of the parameter {} (example: --params {}=1..8)", name, name)));
+                return Result::Err(());
+            }
+        }
+    }
+    if number_of_instances(&values_of_params).filter(|instances| *instances <= MAX_TEMPLATE_INSTANCES).is_none(){
+        eprintln!("{}", Colour::Red.paint(format!("Too many combinations of the values of the parameters, at most {} instances of the template are verified", MAX_TEMPLATE_INSTANCES)));
+        return Result::Err(());
+    }
+    let mut tuples = vec![Vec::new()];
+    for values in values_of_params{
+        tuples = tuples.into_iter()
+            .flat_map(|tuple| values.iter().map(move |value| [tuple.clone(), vec![*value]].concat()))
+            .collect();
+    }
+    set_template_instantiation(program, template, &tuples[0]);
+    Result::Ok(tuples)
+}
+
+// number of combinations of the values of the parameters, None if it overflows
+fn number_of_instances(values_of_params: &[&Vec<i64>]) -> Option<usize> {
+    values_of_params.iter().try_fold(1usize, |instances, values| instances.checked_mul(values.len()))
+}
+
+fn set_template_instantiation(program: &mut ProgramArchive, template: &str, values: &[i64]) {
+    let args = values.iter()
+        .map(|value| ast::Expression::Number(ast::Meta::new(0, 0), BigInt::from(*value)))
+        .collect();
+    program.set_main_component((Vec::new(), ast::Expression::Call{ meta: ast::Meta::new(0, 0), id: template.to_string(), args }));
+}
+
+// verifies the instantiations of the template for each of the given values of its
+// parameters, highlighting the values whose verification fails or times out
+pub fn verify_template(mut program: ProgramArchive, template: &str, tuples: &[Vec<i64>], config: &BuildConfig) -> Result<(), ()> {
+    let files = program.file_library.clone();
+    let options = verification_options(config, &config.prime);
+    let properties = studied_properties(&options);
+    let names = program.get_template_data(template).get_name_of_params().clone();
+    let mut rows = Vec::new();
+    // results of every instance of each template found in the instantiations
+    let mut instances: BTreeMap<String, BTreeMap<String, Vec<&str>>> = BTreeMap::new();
+    let mut logs = Vec::new();
+    for values in tuples{
+        let parameters: Vec<String> = names.iter().zip(values).map(|(name, value)| format!("{}={}", name, value)).collect();
+        let parameters = parameters.join(", ");
+        println!("{} {}({})", Colour::Green.paint("Verifying"), template, parameters);
+        set_template_instantiation(&mut program, template, values);
+        logs.push(format!("------- {}({}) -------\n\n", template, parameters));
+        match verify_program(&program, &options){
+            Result::Ok(mut result) => {
+                Report::print_reports(&result.reports, &files);
+                logs.append(&mut result.logs);
+                // each property of the instantiation is reported with the worst result
+                // among the templates it contains that are selected by the filters
+                let mut row = vec!["NOT STUDIED"; properties.len()];
+                let mut wrong = Vec::new();
+                for template_verdict in &result.templates{
+                    let verdicts: Vec<&str> = verdict_results(template_verdict, &options).into_iter()
+                        .map(|(_, result)| verdict_name(result))
+                        .collect();
+                    if verdicts.iter().all(|verdict| *verdict == "NOT STUDIED"){
+                        continue;
+                    }
+                    for (worst, verdict) in row.iter_mut().zip(&verdicts){
+                        if verdict_severity(verdict) > verdict_severity(worst){
+                            *worst = verdict;
+                        }
+                    }
+                    if verdicts.iter().any(|verdict| *verdict != "VERIFIED"){
+                        wrong.push(template_verdict.template.clone());
+                    }
+                    let base = template_verdict.template.split('(').next().unwrap().to_string();
+                    instances.entry(base).or_default().insert(template_verdict.template.clone(), verdicts);
+                }
+                rows.push((parameters, row, wrong));
+            }
+            Result::Err(reports) => {
+                Report::print_reports(&reports, &files);
+                rows.push((parameters, vec!["ERROR"; properties.len()], Vec::new()));
+            }
+        }
+    }
+    if std::fs::write(&config.civer_file, logs.concat()).is_err(){
+        eprintln!("{}", Colour::Red.paint("Could not write the logs of the verification in the given path"));
+    }
+
+    println!("--------------------------------------------");
+    println!("--------------------------------------------");
+    println!("------- CIVER TEMPLATE RESULTS -------------");
+    println!("--------------------------------------------");
+    println!("--------------------------------------------\n");
+    if !options.template_filter.is_empty(){
+        println!("-> Verification filtered by {}, the rest of the templates are not studied\n", options.template_filter.description());
+    }
+    let width = rows.iter().map(|(parameters, _, _)| parameters.len()).max().unwrap_or(0);
+    let mut header = format!("  {:width$}", "", width = width);
+    for property in &properties{
+        header.push_str(&format!(" | {:14}", property));
+    }
+    println!("{}", header);
+    let mut highlighted = Vec::new();
+    for (parameters, row, wrong) in &rows{
+        let mut line = format!("{:width$}", parameters, width = width);
+        for verdict in row{
+            line.push_str(&format!(" | {:14}", verdict));
+        }
+        if row.iter().all(|verdict| *verdict == "VERIFIED"){
+            println!("  {}", line);
+        } else{
+            println!("{}", Colour::Red.paint(format!("* {}", line)));
+            highlighted.push((parameters, row, wrong));
+        }
+    }
+    println!();
+    println!("-> Results of each template: ");
+    for (base, results) in &instances{
+        let verified = results.values().filter(|verdicts| verdicts.iter().all(|verdict| *verdict == "VERIFIED")).count();
+        println!("    - {}: {} of {} instances verified", base, verified, results.len());
+    }
+    println!();
+    if highlighted.is_empty(){
+        println!("-> All the instantiations of {} were verified :)", template);
+    } else{
+        println!("-> Parameter values that CIVER could not verify: ");
+        for (parameters, row, wrong) in &highlighted{
+            if row.contains(&"ERROR"){
+                println!("    - {} (instantiation error), ", parameters);
+            } else if wrong.is_empty(){
+                println!("    - {} (no template selected by the filters), ", parameters);
+            } else{
+                println!("    - {} ({}), ", parameters, wrong.join(", "));
+            }
+        }
+    }
+    println!("\n--------------------------------------------");
+    println!("--------------------------------------------\n");
+    Result::Ok(())
+}
+
+// order of the results when reporting the worst of several ones
+fn verdict_severity(verdict: &str) -> usize{
+    match verdict{
+        "NOT STUDIED" => 0,
+        "VERIFIED" => 1,
+        "VACUOUS" => 2,
+        "UNKNOWN" => 3,
+        "ENCODING ERROR" => 4,
+        _ => 5,
+    }
+}
+
 type InstantiationResponse = Result<(ExecutedProgram, ReportCollection), ReportCollection>;
 fn instantiation(program: &ProgramArchive, flags: FlagsExecution, prime: &String) -> InstantiationResponse {
     let execution_result = execute::constraint_execution(&program, flags, prime);
@@ -163,26 +917,15 @@ fn export(exe: ExecutedProgram, program: ProgramArchive, flags: FlagsExecution)
     exported
 }
 
-fn check_tags(tree_constraints: TreeConstraints, prime: &String,
-        verification_timeout: u64, check_tags: bool, check_postconditions: bool,
-        check_safety: bool, add_tags_info: bool,add_postconditions_info: bool,
-        apply_deduction_assigned: bool, name: &String, name_initial: &String,
-        name_structure: &String, file_studied_nodes: Option<String>
-    )
+// verifies the circuit and prints the results of each property, writing the outputs
+// requested by the user
+fn check_tags(tree_constraints: TreeConstraints, options: &VerificationOptions, output: &OutputOptions, files: &FileLibrary) -> Vec<SafetyCounterexample>
     {
     use program_structure::constants::UsefulConstants;
 
-    let mut studied_nodes: HashMap<String, ((usize, usize), (PossibleResult, PossibleResult, PossibleResult))> = HashMap::new();
-    
-
-    let mut previously_studied_nodes = HashMap::new();
-    // Read the structure
-    if file_studied_nodes.is_some(){
-        read_studied_nodes(file_studied_nodes.unwrap(), &mut previously_studied_nodes);
-    }
-
-    let constants = UsefulConstants::new(prime);
-    let field = constants.get_p().clone();
+    let field = UsefulConstants::new(&options.prime).get_p().clone();
+    let (check_tags, check_postconditions, check_safety) = (options.check_tags, options.check_postconditions, options.check_safety);
+    let template_filter = &options.template_filter;
     
     let mut tags_verified = Vec::new();
     let mut post_verified = Vec::new();
@@ -193,19 +936,39 @@ fn check_tags(tree_constraints: TreeConstraints, prime: &String,
     let mut safety_verified = Vec::new();
     let mut safety_failed = Vec::new();
     let mut safety_timeout = Vec::new();
-
-    
-    let result_create = File::create(name);
-    let mut cfile = if result_create.is_ok(){
-        result_create.unwrap()
-    } else{
-        unreachable!("Should not enter here")
-    };
-    let logs = check_tags_node(&tree_constraints, &mut studied_nodes, &field,
-        verification_timeout, check_tags, check_postconditions,
-        check_safety, add_tags_info, add_postconditions_info,
-        apply_deduction_assigned, &previously_studied_nodes
-    );
+    let mut tags_encoding_error = Vec::new();
+    let mut post_encoding_error = Vec::new();
+    let mut safety_encoding_error = Vec::new();
+    let mut tags_vacuous = Vec::new();
+    let mut post_vacuous = Vec::new();
+    let mut safety_vacuous = Vec::new();
+
+    let result = verify_tree(&tree_constraints, options, output, files);
+    let studied_nodes = &result.studied_nodes;
+    let details = &result.details;
+    // the templates that are not verified because of the filters or the interruption
+    let filtered = templates_with_assumption(details, AssumptionKind::FilteredTemplate);
+    let not_studied = templates_with_assumption(details, AssumptionKind::NotStudiedTemplate);
+    if !template_filter.is_empty() && filtered.len() == studied_nodes.len(){
+        eprintln!("{}", Colour::Yellow.paint(format!("No template matches the filters {}", template_filter.description())));
+    }
+    Report::print_reports(&result.reports, files);
+    if let (true, Some(name_certificates)) = (options.certificates, &output.certificates_file){
+        let written = std::fs::write(name_certificates, dag::certificates_to_json(&field, &result.certificates));
+        if written.is_ok(){
+            println!("{} {}", Colour::Green.paint("Certificates written in:"), name_certificates);
+        } else{
+            eprintln!("{}", Colour::Red.paint("Could not write the certificates in the given path"));
+        }
+    }
+    if let Some(name_html) = &output.html_file{
+        let written = std::fs::write(name_html, html_report::generate(&tree_constraints, studied_nodes, details, options.properties()));
+        if written.is_ok(){
+            println!("{} {}", Colour::Green.paint("HTML report written in:"), name_html);
+        } else{
+            eprintln!("{}", Colour::Red.paint("Could not write the HTML report in the given path"));
+        }
+    }
 
     let mut number_constraints = HashMap::new();
     let mut number_components = HashMap::new();
@@ -217,10 +980,12 @@ fn check_tags(tree_constraints: TreeConstraints, prime: &String,
 
     let mut init_c = 0;
     count_constraints_node(&tree_constraints, &mut number_constraints, &mut number_components, &mut init_constraint_to_node, &mut init_c);
-    std::fs::write(
-        name_initial,
-        serde_json::to_string_pretty(&init_constraint_to_node).unwrap(),
-    );
+    if let Some(name_initial) = &output.initial_constraints_file{
+        std::fs::write(
+            name_initial,
+            serde_json::to_string_pretty(&init_constraint_to_node).unwrap(),
+        );
+    }
 
 
     let mut init_c = 0;
@@ -244,10 +1009,12 @@ fn check_tags(tree_constraints: TreeConstraints, prime: &String,
         equivalency_structural: equiv_to_vec
     };
      
-    std::fs::write(
-        name_structure,
-        serde_json::to_string_pretty(&structure).unwrap(),
-    );
+    if let Some(name_structure) = &output.structure_file{
+        std::fs::write(
+            name_structure,
+            serde_json::to_string_pretty(&structure).unwrap(),
+        );
+    }
 
     let mut total_cons  = 0;
     let mut total_verified = 0;
@@ -256,16 +1023,14 @@ fn check_tags(tree_constraints: TreeConstraints, prime: &String,
     let mut total_comps_verified = 0;
 
     if check_safety{
-        (total_cons, total_verified) = compute_percentage_verified(&studied_nodes, &number_constraints);
-        (total_comps, total_comps_verified) = compute_percentage_verified(&studied_nodes, &number_components);
+        (total_cons, total_verified) = compute_percentage_verified(studied_nodes, &number_constraints);
+        (total_comps, total_comps_verified) = compute_percentage_verified(studied_nodes, &number_components);
     }
 
-    for l in logs {
-        let _result =  cfile.write_all(l.as_bytes());
-    }
-    let _result = cfile.flush();
-
-    for (component, (_, (result_tags, result_post, result_safety))) in &studied_nodes{
+    for (component, (_, (result_tags, result_post, result_safety))) in studied_nodes{
+        if filtered.contains(component) || not_studied.contains(component){
+            continue;
+        }
         //print!("Component {}: ", component);
         if check_tags{
             match result_tags{
@@ -273,6 +1038,12 @@ fn check_tags(tree_constraints: TreeConstraints, prime: &String,
                 	//println!("TAGS VERIFICATION FAILED || ");
                 	tags_failed.push(component);
                 }
+                PossibleResult::SPURIOUS => {
+                	tags_encoding_error.push(component);
+                }
+                PossibleResult::VACUOUS => {
+                	tags_vacuous.push(component);
+                }
                 	
                 PossibleResult::UNKNOWN => {
                 	//println!("TAGS VERIFICATION UNKNOWN  || ");
@@ -295,6 +1066,12 @@ fn check_tags(tree_constraints: TreeConstraints, prime: &String,
                 	//println!("POSTCONDITIONS VERIFICATION FAILED || ");
                 	post_failed.push(component);
                 },
+                PossibleResult::SPURIOUS => {
+                	post_encoding_error.push(component);
+                },
+                PossibleResult::VACUOUS => {
+                	post_vacuous.push(component);
+                },
                 PossibleResult::UNKNOWN => {
                 	//println!("POSTCONDITIONS VERIFICATION UNKNOWN  || ");
                 	post_timeout.push(component);
@@ -315,6 +1092,12 @@ fn check_tags(tree_constraints: TreeConstraints, prime: &String,
                     safety_failed.push(component);
                     //println!("WEAK SAFETY VERIFICATION FAILED || ");
                 },
+                PossibleResult::SPURIOUS => {
+                    safety_encoding_error.push(component);
+                },
+                PossibleResult::VACUOUS => {
+                    safety_vacuous.push(component);
+                },
                 PossibleResult::UNKNOWN => {
                     safety_timeout.push(component);
                     //println!("WEAK SAFETY VERIFICATION UNKNOWN  || ");
@@ -340,8 +1123,31 @@ fn check_tags(tree_constraints: TreeConstraints, prime: &String,
     println!("--------------------------------------------");
     println!("--------------------------------------------\n");
 
+    if !template_filter.is_empty(){
+        println!("-> Verification filtered by {}", template_filter.description());
+        if !filtered.is_empty(){
+            println!("Components not verified, their contracts are assumed in the components that use them: ");
+            for c in &filtered{
+                println!("    - {}, ", c);
+            }
+        }
+        println!("  * Number of selected templates: {}", studied_nodes.len() - filtered.len());
+        println!("  * Number of filtered templates: {}", filtered.len());
+        println!("\n");
+    }
+
+    if !not_studied.is_empty(){
+        println!("-> CIVER could not study all components, {}", result.stop_reason.unwrap_or("the verification was stopped"));
+        println!("Components not studied, their contracts are assumed in the components that use them: ");
+        for c in &not_studied{
+            println!("    - {}, ", c);
+        }
+        println!("  * Number of not studied templates: {}", not_studied.len());
+        println!("\n");
+    }
+
     if check_tags{
-        if tags_failed.is_empty() && tags_timeout.is_empty(){
+        if not_studied.is_empty() && tags_failed.is_empty() && tags_timeout.is_empty() && tags_encoding_error.is_empty() && tags_vacuous.is_empty(){
         	println!("-> All tags were verified :)");
         } else{
         	println!("-> CIVER could not verify all postconditions");
@@ -357,15 +1163,29 @@ fn check_tags(tree_constraints: TreeConstraints, prime: &String,
         			println!("    - {}, ", c);
         		}
         	}
+        	if !tags_encoding_error.is_empty(){
+        		println!("Components whose counterexamples (tags) do not satisfy the constraints, possible encoding error: ");
+        		for c in &tags_encoding_error{
+        			println!("    - {}, ", c);
+        		}
+        	}
+        	if !tags_vacuous.is_empty(){
+        		println!("Components whose proofs (tags) hold vacuously because of contradictory assumptions: ");
+        		for c in &tags_vacuous{
+        			println!("    - {}, ", c);
+        		}
+        	}
         }
 
         println!("  * Number of verified components (tags): {}", tags_verified.len());
         println!("  * Number of failed components (tags): {}", tags_failed.len());
         println!("  * Number of timeout components (tags): {}", tags_timeout.len());
+        println!("  * Number of encoding errors (tags): {}", tags_encoding_error.len());
+        println!("  * Number of vacuous components (tags): {}", tags_vacuous.len());
         println!("\n");
     } 
     if check_postconditions{
-        if post_failed.is_empty() && post_timeout.is_empty(){
+        if not_studied.is_empty() && post_failed.is_empty() && post_timeout.is_empty() && post_encoding_error.is_empty() && post_vacuous.is_empty(){
         	println!("-> All postconditions were verified :)");
         } else{
         	println!("-> CIVER could not verify all postconditions");
@@ -381,16 +1201,30 @@ fn check_tags(tree_constraints: TreeConstraints, prime: &String,
         			println!("    - {}, ", c);
         		}
         	}
+        	if !post_encoding_error.is_empty(){
+        		println!("Components whose counterexamples (postconditions) do not satisfy the constraints, possible encoding error: ");
+        		for c in &post_encoding_error{
+        			println!("    - {}, ", c);
+        		}
+        	}
+        	if !post_vacuous.is_empty(){
+        		println!("Components whose proofs (postconditions) hold vacuously because of contradictory assumptions: ");
+        		for c in &post_vacuous{
+        			println!("    - {}, ", c);
+        		}
+        	}
         }
 
         println!("  * Number of verified components (postconditions): {}", post_verified.len());
         println!("  * Number of failed components (postconditions): {}", post_failed.len());
         println!("  * Number of timeout components (postconditions): {}", post_timeout.len());
+        println!("  * Number of encoding errors (postconditions): {}", post_encoding_error.len());
+        println!("  * Number of vacuous components (postconditions): {}", post_vacuous.len());
         println!("\n");
     }
 
     if check_safety{
-        if safety_failed.is_empty() && safety_timeout.is_empty(){
+        if not_studied.is_empty() && safety_failed.is_empty() && safety_timeout.is_empty() && safety_encoding_error.is_empty() && safety_vacuous.is_empty(){
         	println!("-> All components satisfy weak safety :)");
         } else{
         	println!("-> CIVER could not verify weak safety of all components");
@@ -406,10 +1240,24 @@ fn check_tags(tree_constraints: TreeConstraints, prime: &String,
         			println!("    - {}, ", c);
         		}
         	}
+        	if !safety_encoding_error.is_empty(){
+        		println!("Components whose counterexamples (weak-safety) do not satisfy the constraints, possible encoding error: ");
+        		for c in &safety_encoding_error{
+        			println!("    - {}, ", c);
+        		}
+        	}
+        	if !safety_vacuous.is_empty(){
+        		println!("Components whose proofs (weak-safety) hold vacuously because of contradictory assumptions: ");
+        		for c in &safety_vacuous{
+        			println!("    - {}, ", c);
+        		}
+        	}
         }
         println!("  * Number of verified components (weak-safety): {}", safety_verified.len());
         println!("  * Number of failed components (weak-safety): {}", safety_failed.len());
         println!("  * Number of timeout components (weak-safety): {}", safety_timeout.len());
+        println!("  * Number of encoding errors (weak-safety): {}", safety_encoding_error.len());
+        println!("  * Number of vacuous components (weak-safety): {}", safety_vacuous.len());
         println!("  * Percentage of verified constraints: {} - ({} / {})", (total_verified as f64 / total_cons as f64) * 100.0, total_verified, total_cons);
         println!("  * Percentage of verified components: {} - ({} / {})", (total_comps_verified as f64 / total_comps as f64) * 100.0, total_comps_verified, total_comps);
 
@@ -419,9 +1267,521 @@ fn check_tags(tree_constraints: TreeConstraints, prime: &String,
 
     }
 
+    if options.portfolio && check_safety{
+        print_portfolio_winners(details);
+    }
+
+    if let Some(name_assumptions) = &output.assumptions_file{
+        print_assumptions(&tree_constraints, studied_nodes, details, options.properties(), name_assumptions);
+    }
+
+    if options.check_completeness{
+        let completeness_results = &result.completeness;
+        let incomplete: Vec<&String> = completeness_results.iter()
+            .filter(|(_, result)| **result == PossibleResult::FAILED)
+            .map(|(component, _)| component)
+            .collect();
+        let unknown: Vec<&String> = completeness_results.iter()
+            .filter(|(_, result)| **result == PossibleResult::UNKNOWN)
+            .map(|(component, _)| component)
+            .collect();
+        let not_studied = completeness_results.values().filter(|result| **result == PossibleResult::NOSTUDIED).count();
+        // the selected templates that were not started before the verification was stopped
+        let stopped: Vec<&String> = completeness_results.iter()
+            .filter(|(component, result)| **result == PossibleResult::NOSTUDIED && template_filter.selects(component))
+            .map(|(component, _)| component)
+            .collect();
+        if incomplete.is_empty() && unknown.is_empty() && stopped.is_empty(){
+        	println!("-> All components are complete :)");
+        } else{
+        	println!("-> CIVER could not verify the completeness of all components");
+        	if !incomplete.is_empty(){
+        		println!("Components whose witness generator does not satisfy their constraints: ");
+        		for c in &incomplete{
+        			println!("    - {}, ", c);
+        		}
+        	}
+        	if !unknown.is_empty(){
+        		println!("Components timeout or unknown when checking completeness: ");
+        		for c in &unknown{
+        			println!("    - {}, ", c);
+        		}
+        	}
+        	if !stopped.is_empty(){
+        		println!("Components not studied (completeness), {}: ", result.stop_reason.unwrap_or("the verification was stopped"));
+        		for c in &stopped{
+        			println!("    - {}, ", c);
+        		}
+        	}
+        }
+        println!("  * Number of verified components (completeness): {}", completeness_results.len() - incomplete.len() - unknown.len() - not_studied);
+        println!("  * Number of failed components (completeness): {}", incomplete.len());
+        println!("  * Number of unknown components (completeness): {}", unknown.len());
+        println!("  * Number of not studied components (completeness): {}", not_studied);
+        println!("\n");
+    }
+
+    if options.check_satisfiable{
+        let satisfiability_results = &result.satisfiability;
+        let unsatisfiable: Vec<&String> = satisfiability_results.iter()
+            .filter(|(_, result)| **result == PossibleResult::FAILED)
+            .map(|(component, _)| component)
+            .collect();
+        let unknown: Vec<&String> = satisfiability_results.iter()
+            .filter(|(_, result)| **result == PossibleResult::UNKNOWN)
+            .map(|(component, _)| component)
+            .collect();
+        let not_studied = satisfiability_results.values().filter(|result| **result == PossibleResult::NOSTUDIED).count();
+        // the selected templates that were not started before the verification was stopped
+        let stopped: Vec<&String> = satisfiability_results.iter()
+            .filter(|(component, result)| **result == PossibleResult::NOSTUDIED && template_filter.selects(component))
+            .map(|(component, _)| component)
+            .collect();
+        if unsatisfiable.is_empty() && unknown.is_empty() && stopped.is_empty(){
+        	println!("-> The constraints of all components are satisfiable :)");
+        } else{
+        	println!("-> CIVER could not verify the satisfiability of the constraints of all components");
+        	if !unsatisfiable.is_empty(){
+        		println!("Components whose constraints admit no witness for some valid inputs: ");
+        		for c in &unsatisfiable{
+        			println!("    - {}, ", c);
+        		}
+        	}
+        	if !unknown.is_empty(){
+        		println!("Components timeout when checking satisfiability: ");
+        		for c in &unknown{
+        			println!("    - {}, ", c);
+        		}
+        	}
+        	if !stopped.is_empty(){
+        		println!("Components not studied (satisfiability), {}: ", result.stop_reason.unwrap_or("the verification was stopped"));
+        		for c in &stopped{
+        			println!("    - {}, ", c);
+        		}
+        	}
+        }
+        println!("  * Number of verified components (satisfiability): {}", satisfiability_results.len() - unsatisfiable.len() - unknown.len() - not_studied);
+        println!("  * Number of failed components (satisfiability): {}", unsatisfiable.len());
+        println!("  * Number of timeout components (satisfiability): {}", unknown.len());
+        println!("  * Number of not studied components (satisfiability): {}", not_studied);
+        println!("\n");
+    }
+
     println!("--------------------------------------------");
     println!("--------------------------------------------\n");
 
+    let mut counterexamples = Vec::new();
+    if output.counterexamples && !safety_failed.is_empty(){
+        let mut instances = Vec::new();
+        failed_safety_instances(&tree_constraints, studied_nodes, &mut HashSet::new(), &mut instances);
+        let names = tree_constraints.signal_names();
+        let inputs: Vec<(String, usize)> = (0..tree_constraints.number_inputs())
+            .map(|s| s + tree_constraints.initial_signal() + tree_constraints.number_outputs())
+            .map(|s| (names.get(&s).cloned().unwrap_or_else(|| s.to_string()), s))
+            .collect();
+        for instance in instances{
+            match tree_constraints.safety_witnesses(instance, &field, options.verification_timeout){
+                Some(witnesses) => counterexamples.push(SafetyCounterexample{
+                    template: instance.pretty_template_name().clone(),
+                    inputs: inputs.clone(),
+                    witnesses,
+                }),
+                None => eprintln!("{}", Colour::Yellow.paint(format!("Could not extend the counterexample of weak safety of {} to a witness of the whole circuit", instance.pretty_template_name()))),
+            }
+        }
+    }
+    counterexamples
+}
+
+// two witnesses of the circuit with the same inputs that show that a template does
+// not satisfy weak safety, indexed by the signals of the circuit
+struct SafetyCounterexample{
+    template: String,
+    inputs: Vec<(String, usize)>,
+    witnesses: (Vec<BigInt>, Vec<BigInt>),
+}
+
+// first instance of each template that does not satisfy weak safety
+fn failed_safety_instances<'a>(
+    tree_constraints: &'a TreeConstraints,
+    studied_nodes: &HashMap<String, ((usize, usize), (PossibleResult, PossibleResult, PossibleResult))>,
+    visited: &mut HashSet<String>,
+    instances: &mut Vec<&'a TreeConstraints>,
+){
+    if !visited.insert(tree_constraints.pretty_template_name().clone()){
+        return;
+    }
+    for subcomponent in tree_constraints.subcomponents(){
+        failed_safety_instances(subcomponent, studied_nodes, visited, instances);
+    }
+    if let Some((_, (_, _, PossibleResult::FAILED))) = studied_nodes.get(tree_constraints.pretty_template_name()){
+        instances.push(tree_constraints);
+    }
+}
+
+// writes the inputs of each counterexample and its two witnesses, using the wires of the r1cs
+// values of the inputs in the format of input.json, reduced to [0, p), where the values of
+// the arrays are flattened following the order of their indexes
+fn inputs_json(inputs: &[(String, usize)], witness: &[BigInt], field: &BigInt) -> serde_json::Map<String, serde_json::Value>{
+    let mut json = serde_json::Map::new();
+    let mut arrays: BTreeMap<&str, Vec<(Vec<usize>, String)>> = BTreeMap::new();
+    for (input, s) in inputs{
+        let value = (((&witness[*s] % field) + field) % field).to_string();
+        match input.split_once('['){
+            Some((base, indexes)) => {
+                let indexes = indexes.trim_end_matches(']').split("][")
+                    .map(|index| index.parse().unwrap_or(0))
+                    .collect();
+                arrays.entry(base).or_default().push((indexes, value));
+            }
+            None => {
+                json.insert(input.clone(), serde_json::Value::String(value));
+            }
+        }
+    }
+    for (base, mut values) in arrays{
+        values.sort();
+        let values = values.into_iter().map(|(_, value)| serde_json::Value::String(value)).collect();
+        json.insert(base.to_string(), serde_json::Value::Array(values));
+    }
+    json
+}
+
+fn write_counterexamples(counterexamples: &[SafetyCounterexample], vcp: &VCP, prime: &String, name: &String){
+    use program_structure::constants::UsefulConstants;
+    use constraint_writers::wtns_writer::write_wtns;
+
+    let constants = UsefulConstants::new(prime);
+    let field = constants.get_p().clone();
+    for (k, counterexample) in counterexamples.iter().enumerate(){
+        let (first, second) = &counterexample.witnesses;
+        let inputs = inputs_json(&counterexample.inputs, first, &field);
+        let file_inputs = format!("{}_{}_input.json", name, k);
+        let file_first = format!("{}_{}_1.wtns", name, k);
+        let file_second = format!("{}_{}_2.wtns", name, k);
+        let first: Vec<BigInt> = vcp.witness_list.iter().map(|s| first[*s].clone()).collect();
+        let second: Vec<BigInt> = vcp.witness_list.iter().map(|s| second[*s].clone()).collect();
+        let result = std::fs::write(&file_inputs, serde_json::to_string_pretty(&inputs).unwrap())
+            .and_then(|_| write_wtns(&file_first, &field, &first))
+            .and_then(|_| write_wtns(&file_second, &field, &second));
+        if result.is_ok(){
+            println!("{} {}: {}, {}, {}", Colour::Green.paint("Counterexample of weak safety of"), counterexample.template, file_inputs, file_first, file_second);
+        } else{
+            eprintln!("{}", Colour::Red.paint("Could not write the counterexamples in the given path"));
+        }
+    }
+}
+
+// applies the check to each template once, its subcomponents are checked first. The
+// templates that are not selected by the filters or that are not started before the
+// verification is stopped are NOSTUDIED
+fn check_each_template(
+    tree_constraints: &TreeConstraints,
+    template_filter: &TemplateFilter,
+    progress: &Progress,
+    results: &mut BTreeMap<String, PossibleResult>,
+    logs: &mut Vec<String>,
+    check: &mut dyn FnMut(&TreeConstraints) -> (PossibleResult, Vec<String>),
+){
+    let template = tree_constraints.pretty_template_name();
+    if results.contains_key(template){
+        return;
+    }
+    for subcomponent in tree_constraints.subcomponents(){
+        check_each_template(subcomponent, template_filter, progress, results, logs, check);
+    }
+    let not_studied = if !template_filter.selects(template){
+        Some(format!("of the filters {}", template_filter.description()))
+    } else{
+        progress.stop_reason().map(|reason| reason.to_string())
+    };
+    if let Some(reason) = not_studied{
+        logs.push(format!("Template {} not checked because {}\n\n", template, reason));
+        results.insert(template.clone(), PossibleResult::NOSTUDIED);
+        return;
+    }
+    let (result, mut new_logs) = check(tree_constraints);
+    logs.append(&mut new_logs);
+    results.insert(template.clone(), result);
+}
+
+fn print_bounds(tree_constraints: &TreeConstraints, prime: &String, name: &String){
+    use program_structure::constants::UsefulConstants;
+
+    let constants = UsefulConstants::new(prime);
+    let field = constants.get_p().clone();
+
+    let mut bounds_info = BTreeMap::new();
+    bounds_node(tree_constraints, &field, &mut bounds_info);
+
+    let result = std::fs::write(
+        name,
+        serde_json::to_string_pretty(&bounds_info).unwrap(),
+    );
+    if result.is_ok(){
+        println!("{} {}", Colour::Green.paint("Bounds written in:"), name);
+    } else{
+        eprintln!("{}", Colour::Red.paint("Could not write the bounds in the given path"));
+    }
+}
+
+fn bounds_node(
+    tree_constraints: &TreeConstraints,
+    field: &BigInt,
+    bounds_info: &mut BTreeMap<String, Vec<SignalBoundsInfo>>,
+){
+    if bounds_info.contains_key(tree_constraints.pretty_template_name()){
+        return;
+    }
+    for subcomponent in tree_constraints.subcomponents(){
+        bounds_node(subcomponent, field, bounds_info);
+    }
+
+    let deductions = tree_constraints.deduce_bounds(field);
+    let mut signals_info = Vec::new();
+    for i in 0..tree_constraints.number_signals(){
+        let signal = tree_constraints.initial_signal() + i;
+        let name = match tree_constraints.signal_names().get(&signal){
+            Some(name) => name.clone(),
+            None => signal.to_string(),
+        };
+        signals_info.push(signal_bounds_info(name, deductions.get(&signal), field));
+    }
+    bounds_info.insert(tree_constraints.pretty_template_name().clone(), signals_info);
+}
+
+fn print_coverage(tree_constraints: &TreeConstraints, template_names: &HashSet<String>, name: &String){
+    let mut coverage = CoverageInfo{
+        templates: BTreeMap::new(),
+        only_weak_safety: Vec::new(),
+        not_instantiated: Vec::new(),
+        outputs: 0,
+        covered_outputs: 0,
+        constraints: 0,
+        specified_constraints: 0,
+        percentage_specified_constraints: 100.0,
+    };
+    let mut instantiated = HashSet::new();
+    coverage_node(tree_constraints, &mut coverage, &mut instantiated);
+    for info in coverage.templates.values(){
+        coverage.outputs += info.outputs.len();
+        coverage.covered_outputs += info.covered_outputs;
+    }
+    coverage.only_weak_safety = coverage.templates.iter()
+        .filter(|(_, info)| info.only_weak_safety)
+        .map(|(template, _)| template.clone())
+        .collect();
+    coverage.not_instantiated = template_names.iter()
+        .filter(|template| !instantiated.contains(*template))
+        .cloned()
+        .collect();
+    coverage.not_instantiated.sort();
+    if coverage.constraints > 0{
+        coverage.percentage_specified_constraints = 100.0 * coverage.specified_constraints as f64 / coverage.constraints as f64;
+    }
+
+    println!("{}", Colour::Green.paint("CIVER specification coverage:"));
+    println!("  * Outputs covered by postconditions or tag specifications: {} of {}", coverage.covered_outputs, coverage.outputs);
+    println!("  * Constraints of components with specifications: {} of {} ({:.2}%)", 
+        coverage.specified_constraints, coverage.constraints, coverage.percentage_specified_constraints);
+    if !coverage.only_weak_safety.is_empty(){
+        println!("  * Templates only checked for weak safety: ");
+        for template in &coverage.only_weak_safety{
+            println!("    - {}, ", template);
+        }
+    }
+    if !coverage.not_instantiated.is_empty(){
+        println!("  * Templates that are never instantiated: ");
+        for template in &coverage.not_instantiated{
+            println!("    - {}, ", template);
+        }
+    }
+    let result = std::fs::write(
+        name,
+        serde_json::to_string_pretty(&coverage).unwrap(),
+    );
+    if result.is_ok(){
+        println!("{} {}", Colour::Green.paint("Coverage written in:"), name);
+    } else{
+        eprintln!("{}", Colour::Red.paint("Could not write the coverage in the given path"));
+    }
+}
+
+// the constraints are counted once for each component, the outputs once for each instance
+fn coverage_node(
+    tree_constraints: &TreeConstraints,
+    coverage: &mut CoverageInfo,
+    instantiated: &mut HashSet<String>,
+){
+    for subcomponent in tree_constraints.subcomponents(){
+        coverage_node(subcomponent, coverage, instantiated);
+    }
+    let has_specifications = tree_constraints.has_specifications();
+    coverage.constraints += tree_constraints.constraints().len();
+    if has_specifications{
+        coverage.specified_constraints += tree_constraints.constraints().len();
+    }
+    let template = tree_constraints.pretty_template_name().split('(').next().unwrap();
+    instantiated.insert(template.to_string());
+    if coverage.templates.contains_key(tree_constraints.pretty_template_name()){
+        return;
+    }
+    let outputs: Vec<OutputCoverageInfo> = tree_constraints.output_coverage().into_iter()
+        .map(|output| OutputCoverageInfo{
+            signal: output.signal,
+            tags: output.tags,
+            postconditions: output.postconditions,
+            tag_specifications: output.tag_specifications,
+        })
+        .collect();
+    let info = TemplateCoverageInfo{
+        constraints: tree_constraints.constraints().len(),
+        only_weak_safety: !has_specifications,
+        covered_outputs: outputs.iter().filter(|output| output.postconditions || output.tag_specifications).count(),
+        outputs,
+    };
+    coverage.templates.insert(tree_constraints.pretty_template_name().clone(), info);
+}
+
+fn infer_postconditions(tree_constraints: &TreeConstraints, prime: &String, verification_timeout: u64, progress: &Progress, name: &String){
+    use program_structure::constants::UsefulConstants;
+
+    let constants = UsefulConstants::new(prime);
+    let field = constants.get_p().clone();
+
+    let mut inferred = BTreeMap::new();
+    postconditions_node(tree_constraints, &field, verification_timeout, progress, &mut inferred);
+
+    // one block per template instance, ready to be pasted in the body of the template
+    let mut content = String::new();
+    for (template, postconditions) in &inferred{
+        content.push_str(&format!("// {}\n", template));
+        let postconditions = match postconditions{
+            Ok(postconditions) => postconditions,
+            Err(reason) => {
+                content.push_str(&format!("// not studied because {}\n\n", reason));
+                continue;
+            }
+        };
+        for postcondition in postconditions{
+            content.push_str(&format!("spec_postcondition {};\n", postcondition));
+        }
+        content.push('\n');
+    }
+    let result = std::fs::write(name, content);
+    if result.is_ok(){
+        println!("{} {}", Colour::Green.paint("Inferred postconditions written in:"), name);
+    } else{
+        eprintln!("{}", Colour::Red.paint("Could not write the inferred postconditions in the given path"));
+    }
+}
+
+// the postconditions of the templates that are not started before the verification is
+// stopped are the reason why they are not studied
+fn postconditions_node(
+    tree_constraints: &TreeConstraints,
+    field: &BigInt,
+    verification_timeout: u64,
+    progress: &Progress,
+    inferred: &mut BTreeMap<String, Result<Vec<String>, &'static str>>,
+){
+    if inferred.contains_key(tree_constraints.pretty_template_name()){
+        return;
+    }
+    for subcomponent in tree_constraints.subcomponents(){
+        postconditions_node(subcomponent, field, verification_timeout, progress, inferred);
+    }
+    let postconditions = match progress.stop_reason(){
+        Some(reason) => Err(reason),
+        None => Ok(tree_constraints.infer_postconditions(field, progress.timeout(verification_timeout))),
+    };
+    inferred.insert(tree_constraints.pretty_template_name().clone(), postconditions);
+}
+
+fn suggest_tags(tree_constraints: &TreeConstraints, tag_specifications: &SpecificationInfo, prime: &String, verification_timeout: u64, progress: &Progress, files: &FileLibrary){
+    use program_structure::constants::UsefulConstants;
+    use execute::{execute_tag_expression, tag_expression_has_value};
+
+    if tag_specifications.is_empty(){
+        eprintln!("{}", Colour::Yellow.paint("No tag specifications to suggest tags: use the flag --civer followed by the name of the file including the specifications"));
+        return;
+    }
+    let constants = UsefulConstants::new(prime);
+    let field = constants.get_p().clone();
+
+    let mut semantics = Vec::new();
+    for (tag, specification) in tag_specifications{
+        let signal = specification.get_signal();
+        let condition = specification.get_condition();
+        semantics.push(TagSemantics{
+            tag: tag.clone(),
+            has_value: tag_expression_has_value(condition, signal),
+            instantiate: Box::new(move |s, value|{
+                let correspondence = HashMap::from([(signal.clone(), s)]);
+                execute_tag_expression(condition, signal, &value).apply_correspondence(&correspondence)
+            }),
+        });
+    }
+    semantics.sort_by(|l, r| l.tag.cmp(&r.tag));
+
+    let mut reports = Vec::new();
+    let mut studied = BTreeMap::new();
+    suggest_tags_node(tree_constraints, &field, verification_timeout, progress, &semantics, &mut studied, &mut reports);
+    let not_studied: Vec<&String> = studied.iter().filter(|(_, studied)| !**studied).map(|(template, _)| template).collect();
+    if reports.is_empty(){
+        println!("{}", Colour::Green.paint("No new tags were found for the outputs of the templates"));
+    }
+    Report::print_reports(&reports, files);
+    if let (Some(reason), false) = (progress.stop_reason(), not_studied.is_empty()){
+        let not_studied: Vec<&str> = not_studied.iter().map(|template| template.as_str()).collect();
+        eprintln!("{}", Colour::Yellow.paint(format!("Tags not suggested for {} because {}", not_studied.join(", "), reason)));
+    }
+}
+
+// whether each template is studied, the ones that are not started before the verification
+// is stopped are not
+fn suggest_tags_node(
+    tree_constraints: &TreeConstraints,
+    field: &BigInt,
+    verification_timeout: u64,
+    progress: &Progress,
+    semantics: &[TagSemantics],
+    studied: &mut BTreeMap<String, bool>,
+    reports: &mut ReportCollection,
+){
+    if studied.contains_key(tree_constraints.pretty_template_name()){
+        return;
+    }
+    for subcomponent in tree_constraints.subcomponents(){
+        suggest_tags_node(subcomponent, field, verification_timeout, progress, semantics, studied, reports);
+    }
+    let stopped = progress.stop_reason().is_some();
+    if !stopped{
+        tree_constraints.suggest_tags(field, progress.timeout(verification_timeout), semantics, reports);
+    }
+    studied.insert(tree_constraints.pretty_template_name().clone(), !stopped);
+}
+
+// The signals without deduced bounds are reported using the complete field
+fn signal_bounds_info(signal: String, bounds: Option<&ExecutedInequation<usize>>, field: &BigInt) -> SignalBoundsInfo{
+    let (min, max) = match bounds{
+        Some(ineq) => (ineq.min.clone(), ineq.max.clone()),
+        None => (BigInt::from(0), field - BigInt::from(1)),
+    };
+    let zero = BigInt::from(0);
+    let bits = if min >= zero{
+        Some(max.bits())
+    } else{
+        None
+    };
+    SignalBoundsInfo{
+        signal,
+        is_binary: min >= zero && max <= BigInt::from(1),
+        fits_64_bits: bits.is_some_and(|b| b <= 64),
+        bits,
+        min: min.to_string(),
+        max: max.to_string(),
+    }
 }
 
 fn count_constraints_node(
@@ -517,21 +1877,22 @@ fn build_structure_nodes(
 
 fn check_tags_node(
     tree_constraints: &TreeConstraints, 
-    studied_nodes: &mut HashMap<String, ((usize, usize), (PossibleResult, PossibleResult, PossibleResult))>, 
-    field:&BigInt,
-    verification_timeout: u64, 
-    check_tags: bool, 
-    check_postconditions: bool, 
-    check_safety: bool, 
-    add_tags_info: bool, 
-    add_postconditions_info: bool,
-    apply_deduction_assigned: bool,
-    previously_studied_nodes: &HashMap<String, PossibleResult>
-) -> Vec<String>{
-    if previously_studied_nodes.contains_key(tree_constraints.pretty_template_name()){
-        let previous_result = previously_studied_nodes.get(tree_constraints.pretty_template_name()).unwrap();
-        studied_nodes.insert(
-            tree_constraints.pretty_template_name().clone(),
+    field: &BigInt,
+    options: &VerificationOptions,
+    progress: &mut Progress,
+    files: &FileLibrary,
+    verification: &mut TreeVerification,
+){
+    let template = tree_constraints.pretty_template_name();
+    if let Some(previous_result) = options.solved_templates.get(template){
+        let assumption = Assumption{
+            kind: AssumptionKind::SolvedTemplate,
+            template: template.clone(),
+            description: format!("weak safety result {} given in the file of solved templates", verdict_name(previous_result)),
+        };
+        verification.details.insert(template.clone(), TemplateDetails{ assumptions: vec![assumption], ..TemplateDetails::default() });
+        verification.studied_nodes.insert(
+            template.clone(),
             (
                 (0,0),
                 (
@@ -541,42 +1902,181 @@ fn check_tags_node(
                 )
             )
         );
-        Vec::new()
-    } else{
-        if !studied_nodes.contains_key(tree_constraints.pretty_template_name()){
-            let mut number_tags_postconditions = tree_constraints.get_no_tags_postconditions();
-            let mut number_postconditions = tree_constraints.get_no_postconditions();
-            let mut logs = Vec::new();
-            for subcomponent in tree_constraints.subcomponents(){
-                logs.append(&mut check_tags_node(subcomponent, studied_nodes, field,
-                    verification_timeout, check_tags, check_postconditions, 
-                    check_safety, add_tags_info, add_postconditions_info,
-                    apply_deduction_assigned, previously_studied_nodes
-                ));
-                number_tags_postconditions += studied_nodes.get(subcomponent.pretty_template_name()).unwrap().0.0;
-                number_postconditions += studied_nodes.get(subcomponent.pretty_template_name()).unwrap().0.1;
+    } else if !verification.studied_nodes.contains_key(template){
+        let mut number_tags_postconditions = tree_constraints.get_no_tags_postconditions();
+        let mut number_postconditions = tree_constraints.get_no_postconditions();
+        for subcomponent in tree_constraints.subcomponents(){
+            check_tags_node(subcomponent, field, options, progress, files, verification);
+            number_tags_postconditions += verification.studied_nodes.get(subcomponent.pretty_template_name()).unwrap().0.0;
+            number_postconditions += verification.studied_nodes.get(subcomponent.pretty_template_name()).unwrap().0.1;
+
+        }
+        // the subcomponents are studied even if the template is filtered out
+        let not_studied = if !options.template_filter.selects(template){
+            Some((AssumptionKind::FilteredTemplate, format!("of the filters {}", options.template_filter.description())))
+        } else{
+            progress.stop_reason().map(|reason| (AssumptionKind::NotStudiedTemplate, reason.to_string()))
+        };
+        if let Some((kind, reason)) = not_studied{
+            let assumption = Assumption{
+                kind,
+                template: template.clone(),
+                description: format!("not verified because {}", reason),
+            };
+            verification.details.insert(template.clone(), TemplateDetails{ assumptions: vec![assumption], ..TemplateDetails::default() });
+            let new_logs = vec![format!("Template {} not verified because {}\n\n", template, reason)];
+            progress.write_logs(&new_logs);
+            verification.logs.extend(new_logs);
+            verification.studied_nodes.insert(
+                template.clone(),
+                (
+                    (number_tags_postconditions, number_postconditions),
+                    (PossibleResult::NOSTUDIED, PossibleResult::NOSTUDIED, PossibleResult::NOSTUDIED)
+                )
+            );
+            return;
+        }
+        progress.start_template(template);
+
+        let first_report = verification.reports.len();
+        let check_options = options.check_options(progress.timeout(options.verification_timeout));
+        let mut results = tree_constraints.check_tags(field, &check_options, files, &mut verification.reports);
+        let counterexamples = verification.reports[first_report..].iter()
+            .filter(|report| matches!(report.get_code(), ReportCode::CIVERCounterexample))
+            .map(|report| report.get_message().clone())
+            .collect();
+        let assumptions = tree_constraints.assumptions(options.check_tags, options.check_postconditions, options.check_safety, results.stats.rounds);
+        verification.details.insert(template.clone(), TemplateDetails{ stats: results.stats, counterexamples, assumptions });
+        if let Some(certificate) = results.certificate{
+            verification.certificates.push(certificate);
+        }
+        results.logs.push("\n\n".to_string());
+        progress.finish_template(&results.logs);
+        verification.logs.append(&mut results.logs);
+        let result_component = (results.tags, results.postconditions, results.safety);
+        verification.studied_nodes.insert(template.clone(), ((number_tags_postconditions, number_postconditions), result_component));
+    }
+}
+
+// template instances that are checked by check_tags_node, without the solved and filtered ones
+fn templates_to_check(
+    tree_constraints: &TreeConstraints,
+    previously_studied_nodes: &HashMap<String, PossibleResult>,
+    template_filter: &TemplateFilter,
+    to_check: &mut BTreeSet<String>,
+){
+    let template = tree_constraints.pretty_template_name();
+    if previously_studied_nodes.contains_key(template){
+        return;
+    }
+    if template_filter.selects(template){
+        to_check.insert(template.clone());
+    }
+    for subcomponent in tree_constraints.subcomponents(){
+        templates_to_check(subcomponent, previously_studied_nodes, template_filter, to_check);
+    }
+}
+
+fn templates_with_assumption(details: &HashMap<String, TemplateDetails>, kind: AssumptionKind) -> BTreeSet<&String>{
+    details.iter()
+        .filter(|(_, details)| details.assumptions.iter().any(|assumption| assumption.kind == kind))
+        .map(|(template, _)| template)
+        .collect()
+}
 
+// assumptions that are not proved by the verification of any template: the facts, the
+// preconditions, the solved, custom, filtered and not studied templates and the contracts
+// of the subcomponents whose result is not VERIFIED. The preconditions of the subcomponents
+// are not checked in the components that use them, while the tags of their inputs are
+// checked in the components that produce them
+fn assumptions_ledger(
+    main: &str,
+    studied_nodes: &HashMap<String, ((usize, usize), (PossibleResult, PossibleResult, PossibleResult))>,
+    details: &HashMap<String, TemplateDetails>,
+    properties: [bool; 3],
+) -> Vec<Assumption>{
+    let verified = |template: &String, property: usize| match studied_nodes.get(template){
+        Some((_, (tags, postconditions, safety))) => *[tags, postconditions, safety][property] == PossibleResult::VERIFIED,
+        None => false,
+    };
+    let mut ledger = BTreeSet::new();
+    for template in details.values(){
+        for assumption in &template.assumptions{
+            let proved = match assumption.kind{
+                AssumptionKind::Fact | AssumptionKind::SolvedTemplate | AssumptionKind::CustomTemplate
+                    | AssumptionKind::FilteredTemplate | AssumptionKind::NotStudiedTemplate
+                    | AssumptionKind::Precondition => false,
+                AssumptionKind::TagPrecondition => properties[0] && assumption.template != main,
+                AssumptionKind::TagsContract => verified(&assumption.template, 0),
+                AssumptionKind::PostconditionsContract => verified(&assumption.template, 1),
+                AssumptionKind::SafetyContract => verified(&assumption.template, 2),
+            };
+            if !proved{
+                ledger.insert(assumption.clone());
             }
+        }
+    }
+    ledger.into_iter().collect()
+}
 
-            let (result_tags, result_post, result_safety, mut new_logs) = tree_constraints.check_tags(
-                field,
-                verification_timeout,
-                check_tags,
-                check_postconditions, 
-                check_safety, 
-                add_tags_info, 
-                add_postconditions_info,
-                apply_deduction_assigned
-            );
-            logs.append(&mut new_logs);
-            logs.push("\n\n".to_string());
-            let result_component = (result_tags, result_post, result_safety);
-            studied_nodes.insert(tree_constraints.pretty_template_name().clone(), ((number_tags_postconditions, number_postconditions), result_component));
-            logs
-        } else{
-            Vec::new()
+fn assumption_info(assumption: &Assumption) -> AssumptionInfo{
+    AssumptionInfo{
+        kind: assumption.kind.name().to_string(),
+        template: assumption.template.clone(),
+        description: assumption.description.clone(),
+    }
+}
+
+fn print_assumptions(
+    tree_constraints: &TreeConstraints,
+    studied_nodes: &HashMap<String, ((usize, usize), (PossibleResult, PossibleResult, PossibleResult))>,
+    details: &HashMap<String, TemplateDetails>,
+    properties: [bool; 3],
+    name: &str,
+){
+    let ledger = assumptions_ledger(tree_constraints.pretty_template_name(), studied_nodes, details, properties);
+    if ledger.is_empty(){
+        println!("-> The results do not depend on unproved assumptions :)");
+    } else{
+        println!("-> Assumptions not proved by the verification of any component: ");
+        for assumption in &ledger{
+            println!("    - [{}] {}: {}", assumption.kind.name(), assumption.template, assumption.description);
         }
     }
+    println!("  * Number of unproved assumptions: {}", ledger.len());
+    println!("\n");
+
+    let info = AssumptionsInfo{
+        templates: details.iter()
+            .map(|(template, details)| (template.clone(), details.assumptions.iter().map(assumption_info).collect()))
+            .collect(),
+        ledger: ledger.iter().map(assumption_info).collect(),
+    };
+    let result = std::fs::write(
+        name,
+        serde_json::to_string_pretty(&info).unwrap(),
+    );
+    if result.is_ok(){
+        println!("{} {}", Colour::Green.paint("Assumptions written in:"), name);
+    } else{
+        eprintln!("{}", Colour::Red.paint("Could not write the assumptions in the given path"));
+    }
+}
+
+// number of weak safety queries answered first by each configuration of the portfolio
+fn print_portfolio_winners(details: &HashMap<String, TemplateDetails>){
+    let mut winners: BTreeMap<&String, usize> = BTreeMap::new();
+    for template in details.values(){
+        for winner in &template.stats.portfolio_winners{
+            *winners.entry(winner).or_default() += 1;
+        }
+    }
+    println!("-> Configurations of the portfolio that answered first: ");
+    for configuration in dag::default_portfolio(){
+        println!("    - {}: {}", configuration.name, winners.get(&configuration.name).unwrap_or(&0));
+    }
+    println!("  * Number of queries answered by the portfolio: {}", winners.values().sum::<usize>());
+    println!("\n");
 }
 
 fn compute_percentage_verified(
@@ -587,6 +2087,10 @@ fn compute_percentage_verified(
     let mut verified_cons = 0;
     for (name, n_cons) in number_constraints{
         let (_, (_, _, result)) = studied_nodes.get(name).unwrap();
+        // the templates filtered out of the verification
+        if *result == PossibleResult::NOSTUDIED{
+            continue;
+        }
         total_cons += n_cons;
         if *result == PossibleResult::VERIFIED{
             verified_cons += n_cons;
@@ -640,4 +2144,121 @@ pub fn read_studied_nodes(path: String, previously_studied_nodes: &mut HashMap<S
         );
     }
 
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn inputs_json_sorts_the_arrays_by_index() {
+        let field = BigInt::from(257);
+        let witness: Vec<BigInt> = [1, 7, 10, 11, 12, 13, -1].iter().map(|v| BigInt::from(*v)).collect();
+        let inputs = vec![
+            ("in[10]".to_string(), 3),
+            ("in[2]".to_string(), 2),
+            ("m[1][0]".to_string(), 5),
+            ("m[0][1]".to_string(), 4),
+            ("sel".to_string(), 6),
+            ("in[0]".to_string(), 1),
+        ];
+        let json = serde_json::Value::Object(inputs_json(&inputs, &witness, &field));
+        assert_eq!(json, serde_json::json!({"in": ["7", "10", "11"], "m": ["12", "13"], "sel": "256"}));
+    }
+
+    fn verdict(template: &str, postconditions: PossibleResult, safety: PossibleResult) -> TemplateVerdict {
+        TemplateVerdict{
+            template: template.to_string(),
+            tags: PossibleResult::NOSTUDIED,
+            postconditions,
+            safety,
+            stats: VerificationStats::default(),
+            assumptions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn primes_matrix_has_a_row_per_template_and_studied_property() {
+        let options = VerificationOptions{ check_postconditions: true, check_safety: true, ..VerificationOptions::default() };
+        let mut results = PrimesMatrix::new();
+        add_prime_results(&mut results, 0, 2, &[verdict("A", PossibleResult::VERIFIED, PossibleResult::FAILED)], &options);
+        add_prime_results(&mut results, 1, 2, &[verdict("A", PossibleResult::TOO_BIG, PossibleResult::SPURIOUS)], &options);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[&("A".to_string(), "postconditions")], vec![Some("VERIFIED"), Some("UNKNOWN")]);
+        assert_eq!(results[&("A".to_string(), "weak safety")], vec![Some("FAILED"), Some("ENCODING ERROR")]);
+    }
+
+    #[test]
+    fn filtered_templates_are_not_studied_in_the_primes_matrix() {
+        let options = VerificationOptions{ check_safety: true, ..VerificationOptions::default() };
+        let mut results = PrimesMatrix::new();
+        for position in 0..2 {
+            add_prime_results(&mut results, position, 2, &[verdict("A", PossibleResult::NOSTUDIED, PossibleResult::NOSTUDIED)], &options);
+        }
+        let a = &results[&("A".to_string(), "weak safety")];
+        assert_eq!(a, &vec![Some("NOT STUDIED"), Some("NOT STUDIED")]);
+        assert!(!depends_on_prime(a));
+        assert_eq!(verdict_name(&PossibleResult::NOTHING), "VERIFIED");
+        assert!(verdict_severity("NOT STUDIED") < verdict_severity("VERIFIED"));
+    }
+
+    #[test]
+    fn templates_missing_for_a_prime_do_not_depend_on_it() {
+        let options = VerificationOptions{ check_safety: true, ..VerificationOptions::default() };
+        let mut results = PrimesMatrix::new();
+        let first = [verdict("A", PossibleResult::NOSTUDIED, PossibleResult::VERIFIED), verdict("B", PossibleResult::NOSTUDIED, PossibleResult::VERIFIED)];
+        let second = [verdict("B", PossibleResult::NOSTUDIED, PossibleResult::FAILED)];
+        let third = [verdict("A", PossibleResult::NOSTUDIED, PossibleResult::VERIFIED), verdict("B", PossibleResult::NOSTUDIED, PossibleResult::VERIFIED)];
+        for (position, templates) in [&first[..], &second[..], &third[..]].iter().enumerate() {
+            add_prime_results(&mut results, position, 3, templates, &options);
+        }
+        let a = &results[&("A".to_string(), "weak safety")];
+        assert_eq!(a, &vec![Some("VERIFIED"), None, Some("VERIFIED")]);
+        assert!(!depends_on_prime(a));
+        assert!(depends_on_prime(&results[&("B".to_string(), "weak safety")]));
+        assert!(!depends_on_prime(&[None, None]));
+    }
+
+    #[test]
+    fn instances_are_the_combinations_of_the_values() {
+        let (n, k) = (vec![1, 2, 3], vec![8, 16]);
+        assert_eq!(number_of_instances(&[&n, &k]), Some(6));
+        assert_eq!(number_of_instances(&[]), Some(1));
+        let many: Vec<i64> = (0..1 << 16).collect();
+        assert_eq!(number_of_instances(&[&many, &many]), Some(1 << 32));
+        assert_eq!(number_of_instances(&[&many, &many, &many, &many]), None);
+    }
+
+    #[test]
+    fn preconditions_of_the_subcomponents_are_unproved_assumptions() {
+        let assumption = |kind, template: &str| Assumption{ kind, template: template.to_string(), description: String::new() };
+        let assumptions = vec![
+            assumption(AssumptionKind::Precondition, "Main()"),
+            assumption(AssumptionKind::Precondition, "A()"),
+            assumption(AssumptionKind::TagPrecondition, "Main()"),
+            assumption(AssumptionKind::TagPrecondition, "A()"),
+            assumption(AssumptionKind::SafetyContract, "A()"),
+        ];
+        let details = HashMap::from([("Main()".to_string(), TemplateDetails{ stats: VerificationStats::default(), counterexamples: Vec::new(), assumptions })]);
+        let safe = (PossibleResult::NOSTUDIED, PossibleResult::NOSTUDIED, PossibleResult::VERIFIED);
+        let studied_nodes = HashMap::from([("A()".to_string(), ((0, 0), safe.clone())), ("Main()".to_string(), ((0, 0), safe))]);
+        let ledger = assumptions_ledger("Main()", &studied_nodes, &details, [true, false, true]);
+        assert_eq!(ledger, vec![
+            assumption(AssumptionKind::Precondition, "A()"),
+            assumption(AssumptionKind::Precondition, "Main()"),
+            assumption(AssumptionKind::TagPrecondition, "Main()"),
+        ]);
+    }
+
+    #[test]
+    fn primes_that_cannot_be_instantiated_are_errors() {
+        let options = VerificationOptions{ check_safety: true, ..VerificationOptions::default() };
+        let mut results = PrimesMatrix::new();
+        // the second prime could not be instantiated
+        add_prime_results(&mut results, 0, 2, &[verdict("A", PossibleResult::NOSTUDIED, PossibleResult::VERIFIED)], &options);
+        let a = &results[&("A".to_string(), "weak safety")];
+        assert_eq!(prime_verdict(a[0], false), "VERIFIED");
+        assert_eq!(prime_verdict(a[1], true), "ERROR");
+        assert_eq!(prime_verdict(a[1], false), "-");
+        assert!(!depends_on_prime(a));
+    }
+}
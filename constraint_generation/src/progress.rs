@@ -0,0 +1,164 @@
+use ansi_term::Colour;
+use std::fs::File;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+use std::time::{Duration, Instant};
+
+// set when the user presses Ctrl-C, the templates that are not started yet are not studied
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+static HANDLER: Once = Once::new();
+
+// the first Ctrl-C stops the verification after the current template so that the partial
+// results are written, the second one exits immediately. The handler is only set once
+pub fn handle_interruptions() {
+    HANDLER.call_once(|| {
+        let result = ctrlc::set_handler(|| {
+            if INTERRUPTED.swap(true, Ordering::SeqCst) {
+                std::process::exit(130);
+            }
+            eprintln!("{}", Colour::Yellow.paint("Interrupted: the partial results are written after the current template, press Ctrl-C again to exit immediately"));
+        });
+        if result.is_err() {
+            eprintln!("{}", Colour::Yellow.paint("Could not handle the interruptions, the partial results are lost if the verification is interrupted"));
+        }
+    });
+}
+
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+// progress of the verification of the templates of a circuit, the logs of each template are
+// written in the log file as soon as it is verified
+pub struct Progress<'a> {
+    total: usize,
+    done: usize,
+    start: Instant,
+    budget: Option<Duration>,
+    print: bool,
+    log_file: Option<&'a mut File>,
+}
+
+impl<'a> Progress<'a> {
+    pub fn new(total: usize, budget: Option<u64>, print: bool, log_file: Option<&'a mut File>) -> Progress<'a> {
+        Progress {
+            total,
+            done: 0,
+            start: Instant::now(),
+            budget: budget.map(Duration::from_secs),
+            print,
+            log_file,
+        }
+    }
+
+    // reason why the templates that are not started yet are not studied
+    pub fn stop_reason(&self) -> Option<&'static str> {
+        if INTERRUPTED.load(Ordering::SeqCst) {
+            Some("the verification was interrupted")
+        } else if self.budget.is_some_and(|budget| self.start.elapsed() >= budget) {
+            Some("the time budget of the verification ran out")
+        } else {
+            None
+        }
+    }
+
+    // timeout of the queries of the next template, that cannot exceed the remaining budget
+    pub fn timeout(&self, verification_timeout: u64) -> u64 {
+        match self.budget {
+            Some(budget) => {
+                let remaining = budget.saturating_sub(self.start.elapsed()).as_millis() as u64;
+                verification_timeout.min(remaining.max(1))
+            }
+            None => verification_timeout,
+        }
+    }
+
+    // seconds of the budget that are left, rounded up, to share it with a later stage of the verification
+    pub fn remaining_budget(&self) -> Option<u64> {
+        self.budget.map(|budget| {
+            let remaining = budget.saturating_sub(self.start.elapsed());
+            remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0)
+        })
+    }
+
+    pub fn start_template(&self, template: &str) {
+        if self.print {
+            eprintln!(
+                "{} {} (elapsed {:.1}s)",
+                Colour::Blue.paint(format!("[{}/{}] Checking", self.done + 1, self.total)),
+                template,
+                self.start.elapsed().as_secs_f64()
+            );
+        }
+    }
+
+    pub fn finish_template(&mut self, logs: &[String]) {
+        self.done += 1;
+        self.write_logs(logs);
+    }
+
+    pub fn write_logs(&mut self, logs: &[String]) {
+        if let Some(file) = &mut self.log_file {
+            for l in logs {
+                let _result = file.write_all(l.as_bytes());
+            }
+            let _result = file.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // progress whose verification started the given seconds ago
+    fn started(budget: Option<u64>, elapsed: u64) -> Progress<'static> {
+        let mut progress = Progress::new(2, budget, false, None);
+        progress.start = Instant::now().checked_sub(Duration::from_secs(elapsed)).unwrap();
+        progress
+    }
+
+    #[test]
+    fn timeout_without_budget() {
+        let progress = started(None, 100);
+        assert_eq!(progress.timeout(5000), 5000);
+        assert_eq!(progress.stop_reason(), None);
+    }
+
+    #[test]
+    fn timeout_within_the_remaining_budget() {
+        let progress = started(Some(10), 4);
+        assert_eq!(progress.timeout(5000), 5000);
+        let remaining = progress.timeout(60000);
+        assert!(remaining > 5000 && remaining <= 6000);
+        assert_eq!(progress.stop_reason(), None);
+    }
+
+    #[test]
+    fn timeout_after_the_budget_ran_out() {
+        // the budget is exceeded without overflowing and the queries get the minimum timeout
+        let progress = started(Some(10), 20);
+        assert_eq!(progress.timeout(5000), 1);
+        assert_eq!(progress.stop_reason(), Some("the time budget of the verification ran out"));
+        let progress = started(Some(0), 0);
+        assert_eq!(progress.timeout(5000), 1);
+        assert!(progress.stop_reason().is_some());
+    }
+
+    #[test]
+    fn remaining_budget() {
+        assert_eq!(started(None, 5).remaining_budget(), None);
+        assert_eq!(started(Some(10), 4).remaining_budget(), Some(6));
+        assert_eq!(started(Some(10), 20).remaining_budget(), Some(0));
+    }
+
+    #[test]
+    fn finished_templates() {
+        let mut progress = started(None, 0);
+        progress.finish_template(&["logs".to_string()]);
+        progress.finish_template(&[]);
+        assert_eq!(progress.done, 2);
+    }
+}
@@ -0,0 +1,103 @@
+// patterns over the names of the template instances (for example Num2Bits(254)) that
+// select the ones to verify, where * matches any sequence of characters and ? any single
+// character. The instances that are not selected are not verified, but their contracts
+// are still assumed in the components that use them
+#[derive(Clone, Debug, Default)]
+pub struct TemplateFilter {
+    // if not empty, only the instances matching any of these patterns are verified
+    pub only: Vec<String>,
+    // the instances matching any of these patterns are not verified
+    pub exclude: Vec<String>,
+}
+
+impl TemplateFilter {
+    pub fn is_empty(&self) -> bool {
+        self.only.is_empty() && self.exclude.is_empty()
+    }
+
+    pub fn selects(&self, template: &str) -> bool {
+        let only = self.only.is_empty() || self.only.iter().any(|pattern| matches(pattern, template));
+        only && !self.exclude.iter().any(|pattern| matches(pattern, template))
+    }
+
+    pub fn description(&self) -> String {
+        let mut filters = Vec::new();
+        for pattern in &self.only {
+            filters.push(format!("--civer_only {}", pattern));
+        }
+        for pattern in &self.exclude {
+            filters.push(format!("--civer_exclude {}", pattern));
+        }
+        filters.join(", ")
+    }
+}
+
+fn matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    // position of the last * and of the text when it was reached, to backtrack
+    let mut star = None;
+    let (mut p, mut t) = (0, 0);
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn star_at_the_start_middle_and_end() {
+        assert!(matches("*Bits(254)", "Num2Bits(254)"));
+        assert!(matches("Num*(254)", "Num2Bits(254)"));
+        assert!(matches("Num2Bits*", "Num2Bits(254)"));
+        assert!(matches("*", "Num2Bits(254)"));
+        assert!(matches("*", ""));
+        assert!(matches("**Bits*", "Num2Bits(254)"));
+        assert!(matches("*2*(*)", "Num2Bits(254)"));
+        assert!(!matches("*Bits", "Num2Bits(254)"));
+        assert!(!matches("Bits*", "Num2Bits(254)"));
+        assert!(!matches("Num*Num", "Num2Bits(254)"));
+    }
+
+    #[test]
+    fn question_mark_matches_one_character() {
+        assert!(matches("Num?Bits(254)", "Num2Bits(254)"));
+        assert!(matches("Num2Bits(???)", "Num2Bits(254)"));
+        assert!(!matches("Num2Bits(??)", "Num2Bits(254)"));
+        assert!(!matches("Num2Bits(????)", "Num2Bits(254)"));
+        assert!(!matches("?", ""));
+    }
+
+    #[test]
+    fn parentheses_are_literal() {
+        assert!(matches("Num2Bits(254)", "Num2Bits(254)"));
+        assert!(matches("LessThan()", "LessThan()"));
+        assert!(!matches("Num2Bits(254)", "Num2Bits(25)"));
+        assert!(!matches("Num2Bits", "Num2Bits(254)"));
+        assert!(!matches("LessThan()", "LessThan(8)"));
+    }
+
+    #[test]
+    fn only_and_exclude() {
+        let filter = TemplateFilter { only: vec!["Num2Bits*".to_string()], exclude: vec!["*(254)".to_string()] };
+        assert!(filter.selects("Num2Bits(8)"));
+        assert!(!filter.selects("Num2Bits(254)"));
+        assert!(!filter.selects("LessThan(8)"));
+        assert!(TemplateFilter::default().selects("LessThan(8)"));
+    }
+}
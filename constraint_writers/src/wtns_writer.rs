@@ -0,0 +1,101 @@
+use circom_algebra::num_bigint::BigInt;
+use std::fs::File;
+use std::io::{BufWriter, Result, Write};
+
+// Binary witness format used by snarkjs
+const MAGIC: &[u8] = b"wtns";
+const VERSION: u32 = 2;
+const SECTIONS: u32 = 2;
+const HEADER_TYPE: u32 = 1;
+const WITNESS_TYPE: u32 = 2;
+
+// the value is reduced to [0, p) since only its magnitude is written
+fn bigint_as_bytes(number: &BigInt, field: &BigInt, with_bytes: usize) -> Vec<u8> {
+    let reduced = ((number % field) + field) % field;
+    let (_, mut value) = reduced.to_bytes_le();
+    while value.len() < with_bytes {
+        value.push(0);
+    }
+    value
+}
+
+// values contains the value of each wire of the r1cs, the first one is the constant 1
+pub fn wtns_bytes(field: &BigInt, values: &[BigInt]) -> Vec<u8> {
+    let field_size = field.bits().div_ceil(64) * 8;
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.extend_from_slice(&SECTIONS.to_le_bytes());
+
+    bytes.extend_from_slice(&HEADER_TYPE.to_le_bytes());
+    bytes.extend_from_slice(&((4 + field_size + 4) as u64).to_le_bytes());
+    bytes.extend_from_slice(&(field_size as u32).to_le_bytes());
+    let (_, field_bytes) = field.to_bytes_le();
+    bytes.extend_from_slice(&field_bytes);
+    bytes.resize(bytes.len() + field_size - field_bytes.len(), 0);
+    bytes.extend_from_slice(&(values.len() as u32).to_le_bytes());
+
+    bytes.extend_from_slice(&WITNESS_TYPE.to_le_bytes());
+    bytes.extend_from_slice(&((field_size * values.len()) as u64).to_le_bytes());
+    for value in values {
+        bytes.extend_from_slice(&bigint_as_bytes(value, field, field_size));
+    }
+    bytes
+}
+
+pub fn write_wtns(output_file: &str, field: &BigInt, values: &[BigInt]) -> Result<()> {
+    let mut writer = File::create(output_file).map(BufWriter::new)?;
+    writer.write_all(&wtns_bytes(field, values))?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::convert::TryInto;
+    const BN128: &str = "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+
+    fn read_u32(bytes: &[u8], position: usize) -> u32 {
+        u32::from_le_bytes(bytes[position..position + 4].try_into().unwrap())
+    }
+
+    fn read_u64(bytes: &[u8], position: usize) -> u64 {
+        u64::from_le_bytes(bytes[position..position + 8].try_into().unwrap())
+    }
+
+    #[test]
+    fn wtns_layout_matches_snarkjs() {
+        let field: BigInt = BN128.parse().unwrap();
+        let values = vec![BigInt::from(1), BigInt::from(5), BigInt::from(258)];
+        let bytes = wtns_bytes(&field, &values);
+
+        assert_eq!(&bytes[0..4], b"wtns");
+        assert_eq!(read_u32(&bytes, 4), 2);
+        assert_eq!(read_u32(&bytes, 8), 2);
+        // header: n8, prime and number of witnesses
+        assert_eq!(read_u32(&bytes, 12), 1);
+        assert_eq!(read_u64(&bytes, 16), 4 + 32 + 4);
+        assert_eq!(read_u32(&bytes, 24), 32);
+        assert_eq!(BigInt::from_bytes_le(circom_algebra::num_bigint::Sign::Plus, &bytes[28..60]), field);
+        assert_eq!(read_u32(&bytes, 60), 3);
+        // witness section
+        assert_eq!(read_u32(&bytes, 64), 2);
+        assert_eq!(read_u64(&bytes, 68), 3 * 32);
+        assert_eq!(bytes.len(), 76 + 3 * 32);
+        assert_eq!(&bytes[76 + 32..76 + 34], &[5, 0]);
+        assert_eq!(&bytes[76 + 64..76 + 66], &[2, 1]);
+    }
+
+    #[test]
+    fn wtns_values_are_reduced_to_the_field() {
+        let field = BigInt::from(257);
+        let values = vec![BigInt::from(-1), BigInt::from(258), BigInt::from(256)];
+        let bytes = wtns_bytes(&field, &values);
+        // 257 needs 9 bits, so each element takes 8 bytes
+        assert_eq!(read_u32(&bytes, 24), 8);
+        let witness = &bytes[bytes.len() - 24..];
+        assert_eq!(&witness[0..8], &[0, 1, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(&witness[8..16], &[1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(&witness[16..24], &[0, 1, 0, 0, 0, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,107 @@
+use super::TreeConstraints;
+use crate::certificates::spec_expression_to_string_with_names;
+use program_structure::ast::Expression;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AssumptionKind {
+    Fact,
+    Precondition,
+    TagPrecondition,
+    TagsContract,
+    PostconditionsContract,
+    SafetyContract,
+    SolvedTemplate,
+    CustomTemplate,
+    FilteredTemplate,
+    NotStudiedTemplate,
+}
+
+impl AssumptionKind {
+    pub fn name(&self) -> &'static str {
+        use AssumptionKind::*;
+        match self {
+            Fact => "fact",
+            Precondition => "precondition",
+            TagPrecondition => "tag precondition",
+            TagsContract => "tags contract",
+            PostconditionsContract => "postconditions contract",
+            SafetyContract => "weak safety contract",
+            SolvedTemplate => "solved template",
+            CustomTemplate => "custom template",
+            FilteredTemplate => "filtered template",
+            NotStudiedTemplate => "not studied template",
+        }
+    }
+}
+
+// something the verdict of a template depends on without being proved when checking it,
+// template is the template instance that states it (the subcomponent for the contracts)
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Assumption {
+    pub kind: AssumptionKind,
+    pub template: String,
+    pub description: String,
+}
+
+impl TreeConstraints {
+    // assumptions used to check the template, including the contracts of its subcomponents
+    // and of the descendants added in each round of the verification
+    pub fn assumptions(&self, check_tags: bool, check_postconditions: bool, check_safety: bool, rounds: usize) -> Vec<Assumption> {
+        if self.is_custom {
+            return vec![self.assumption(AssumptionKind::CustomTemplate, "its constraints are not known, all its properties are assumed".to_string())];
+        }
+        let mut assumptions = Vec::new();
+        for fact in &self.facts {
+            assumptions.push(self.assumption(AssumptionKind::Fact, self.named_expression(fact)));
+        }
+        for precondition in self.preconditions.iter().chain(self.preconditions_intermediates.iter()) {
+            assumptions.push(self.assumption(AssumptionKind::Precondition, self.named_expression(precondition)));
+        }
+        for precondition in &self.tags_preconditions {
+            assumptions.push(self.assumption(AssumptionKind::TagPrecondition, self.named_expression(precondition)));
+        }
+
+        // the contracts of the children of the components whose constraints were added
+        let mut level: Vec<&TreeConstraints> = self.subcomponents.iter().collect();
+        for _ in 0..=rounds {
+            let mut next_level = Vec::new();
+            for subtree in level {
+                if check_safety {
+                    assumptions.push(subtree.assumption(AssumptionKind::SafetyContract, "equal inputs determine equal outputs".to_string()));
+                }
+                if check_postconditions && subtree.generate_implications().is_some() {
+                    let description = subtree.contract_description(&subtree.postconditions_outputs);
+                    assumptions.push(subtree.assumption(AssumptionKind::PostconditionsContract, description));
+                }
+                if check_tags && subtree.generate_tags_implications().is_some() {
+                    let description = subtree.contract_description(&subtree.tags_postconditions_outputs);
+                    assumptions.push(subtree.assumption(AssumptionKind::TagsContract, description));
+                }
+                next_level.extend(subtree.subcomponents.iter());
+            }
+            level = next_level;
+        }
+        assumptions.sort();
+        assumptions.dedup();
+        assumptions
+    }
+
+    fn assumption(&self, kind: AssumptionKind, description: String) -> Assumption {
+        Assumption { kind, template: self.pretty_template_name.clone(), description }
+    }
+
+    fn named_expression(&self, expr: &Expression) -> String {
+        spec_expression_to_string_with_names(expr, &self.signal_names)
+    }
+
+    fn contract_description(&self, postconditions: &[Expression]) -> String {
+        let mut left: Vec<String> = self.preconditions.iter().chain(self.tags_preconditions.iter())
+            .map(|precondition| self.named_expression(precondition))
+            .collect();
+        if left.is_empty() {
+            left.push("true".to_string());
+        }
+        let right: Vec<String> = postconditions.iter().map(|postcondition| self.named_expression(postcondition)).collect();
+        format!("{} ==> {}", left.join(" && "), right.join(" && "))
+    }
+}
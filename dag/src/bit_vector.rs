@@ -0,0 +1,191 @@
+use super::tags_checking::Signal2Bounds;
+use circom_algebra::algebra::Constraint;
+use circom_algebra::num_traits::Signed;
+use num_bigint_dig::BigInt;
+use std::collections::HashMap;
+use z3::ast::{Ast, Bool, Int, BV};
+use z3::Context;
+
+// maximum number of bits of the bounds of a signal to use the bit-vector encoding
+const MAX_SIGNAL_BITS: usize = 64;
+// maximum width of the bit-vectors, the multiplications of wider ones are too expensive
+const MAX_WIDTH: usize = 256;
+
+// width of the bit-vectors needed to encode the constraints over the signed representatives
+// of the signals without overflows. It only exists if every signal has bounds of at most
+// MAX_SIGNAL_BITS bits and no constraint can reach a multiple of p different from 0, so that
+// each constraint A * B = C holds over the integers and is encoded without quotients
+pub fn encoding_width<'a>(
+    constraints: &[Constraint<usize>],
+    signals: impl Iterator<Item = &'a usize>,
+    deductions: &Signal2Bounds,
+    field: &BigInt,
+) -> Option<u32> {
+    let limit = BigInt::from(1) << MAX_SIGNAL_BITS;
+    let mut maximum = BigInt::from(1);
+    for s in signals {
+        let value = signal_magnitude(*s, deductions)?;
+        if value >= limit {
+            return None;
+        }
+        if value > maximum {
+            maximum = value;
+        }
+    }
+    for c in constraints {
+        let value = magnitude(c.a(), deductions, field)? * magnitude(c.b(), deductions, field)?
+            + magnitude(c.c(), deductions, field)?;
+        if &value >= field {
+            return None;
+        }
+        if value > maximum {
+            maximum = value;
+        }
+    }
+    // an extra bit for the sign
+    let width = maximum.bits() + 1;
+    if width <= MAX_WIDTH {
+        Some(width as u32)
+    } else {
+        None
+    }
+}
+
+// bound of the absolute value of the signed representative of the signal
+fn signal_magnitude(signal: usize, deductions: &Signal2Bounds) -> Option<BigInt> {
+    let bounds = deductions.get(&signal)?;
+    Some(std::cmp::max(bounds.min.abs(), bounds.max.abs()))
+}
+
+// bound of the absolute value of the linear expression and of any of its partial sums
+fn magnitude(le: &HashMap<usize, BigInt>, deductions: &Signal2Bounds, field: &BigInt) -> Option<BigInt> {
+    let mut result = BigInt::from(0);
+    for (signal, coef) in le {
+        let coef = to_signed(coef, field).abs();
+        if *signal == Constraint::<usize>::constant_coefficient() {
+            result += coef;
+        } else {
+            result += coef * signal_magnitude(*signal, deductions)?;
+        }
+    }
+    Some(result)
+}
+
+fn to_signed(value: &BigInt, field: &BigInt) -> BigInt {
+    if value > &(field / BigInt::from(2)) {
+        value - field
+    } else {
+        value.clone()
+    }
+}
+
+// value in the field of the signed representative of a signal
+pub fn to_field(value: &BigInt, field: &BigInt) -> BigInt {
+    if value < &BigInt::from(0) {
+        value + field
+    } else {
+        value.clone()
+    }
+}
+
+pub fn constant<'a>(ctx: &'a Context, value: &BigInt, width: u32) -> BV<'a> {
+    BV::from_int(&Int::from_str(ctx, &value.to_string()).unwrap(), width)
+}
+
+// the signed representative of the signal is between the bounds deduced for it
+pub fn bounds_condition<'a>(ctx: &'a Context, signal: &BV<'a>, min: &BigInt, max: &BigInt, width: u32) -> Bool<'a> {
+    signal.bvsge(&constant(ctx, min, width)) & signal.bvsle(&constant(ctx, max, width))
+}
+
+fn linear_expression<'a>(
+    ctx: &'a Context,
+    le: &HashMap<usize, BigInt>,
+    signals_to_smt_symbols: &HashMap<usize, BV<'a>>,
+    field: &BigInt,
+    width: u32,
+) -> BV<'a> {
+    let mut result = BV::from_i64(ctx, 0, width);
+    for (signal, coef) in le {
+        let coef = constant(ctx, &to_signed(coef, field), width);
+        if *signal == Constraint::<usize>::constant_coefficient() {
+            result = result.bvadd(&coef);
+        } else {
+            result = result.bvadd(&signals_to_smt_symbols.get(signal).unwrap().bvmul(&coef));
+        }
+    }
+    result
+}
+
+pub fn constraint_condition<'a>(
+    ctx: &'a Context,
+    constraint: &Constraint<usize>,
+    signals_to_smt_symbols: &HashMap<usize, BV<'a>>,
+    field: &BigInt,
+    width: u32,
+) -> Bool<'a> {
+    let a = linear_expression(ctx, constraint.a(), signals_to_smt_symbols, field, width);
+    let b = linear_expression(ctx, constraint.b(), signals_to_smt_symbols, field, width);
+    let c = linear_expression(ctx, constraint.c(), signals_to_smt_symbols, field, width);
+    a.bvmul(&b)._eq(&c)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use circom_algebra::algebra::ExecutedInequation;
+
+    fn bounds(signals: &[(usize, BigInt, BigInt)]) -> Signal2Bounds {
+        signals.iter().map(|(signal, min, max)| (*signal, ExecutedInequation { signal: *signal, min: min.clone(), max: max.clone() })).collect()
+    }
+
+    // coefficient * s_1 * s_2 = s_3
+    fn product(coefficient: BigInt) -> Constraint<usize> {
+        Constraint::new(
+            HashMap::from([(1, coefficient)]),
+            HashMap::from([(2, BigInt::from(1))]),
+            HashMap::from([(3, BigInt::from(1))]),
+        )
+    }
+
+    fn width(constraint: Constraint<usize>, deductions: &Signal2Bounds, field: &BigInt) -> Option<u32> {
+        encoding_width(&[constraint], [1, 2, 3].iter(), deductions, field)
+    }
+
+    #[test]
+    fn constraints_below_the_field() {
+        let field = BigInt::from(101);
+        let small = bounds(&[(1, 0.into(), 9.into()), (2, 0.into(), 10.into()), (3, 0.into(), 10.into())]);
+        // 9 * 10 + 10 = 100 < 101, plus the sign bit
+        assert_eq!(width(product(1.into()), &small, &field), Some(8));
+        // -1 is encoded using its signed representative
+        assert_eq!(width(product(100.into()), &small, &field), Some(8));
+        let large = bounds(&[(1, 0.into(), 9.into()), (2, (-10).into(), 0.into()), (3, 0.into(), 11.into())]);
+        // 9 * 10 + 11 = 101 may be a multiple of the field different from 0
+        assert_eq!(width(product(1.into()), &large, &field), None);
+    }
+
+    #[test]
+    fn signals_of_at_most_64_bits() {
+        let field = BigInt::from(1) << 300;
+        let limit = BigInt::from(1) << 64;
+        let one = BigInt::from(1);
+        let fitting = bounds(&[(1, 0.into(), &limit - &one), (2, 0.into(), 1.into()), (3, 0.into(), 1.into())]);
+        // (2^64 - 1) * 1 + 1 = 2^64 needs 65 bits and the sign bit
+        assert_eq!(width(product(1.into()), &fitting, &field), Some(66));
+        let negative = bounds(&[(1, -(&limit - &one), 0.into()), (2, 0.into(), 1.into()), (3, 0.into(), 1.into())]);
+        assert_eq!(width(product(1.into()), &negative, &field), Some(66));
+        let too_big = bounds(&[(1, 0.into(), limit), (2, 0.into(), 1.into()), (3, 0.into(), 1.into())]);
+        assert_eq!(width(product(1.into()), &too_big, &field), None);
+        let missing = bounds(&[(1, 0.into(), 1.into()), (2, 0.into(), 1.into())]);
+        assert_eq!(width(product(1.into()), &missing, &field), None);
+    }
+
+    #[test]
+    fn width_of_at_most_256_bits() {
+        let field = BigInt::from(1) << 300;
+        let binary = bounds(&[(1, 0.into(), 1.into()), (2, 0.into(), 1.into()), (3, 0.into(), 0.into())]);
+        // 2^254 needs 255 bits and the sign bit
+        assert_eq!(width(product(BigInt::from(1) << 254), &binary, &field), Some(256));
+        assert_eq!(width(product(BigInt::from(1) << 255), &binary, &field), None);
+    }
+}
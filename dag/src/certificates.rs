@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use circom_algebra::algebra::Constraint;
+use circom_algebra::num_bigint::BigInt;
+use json::JsonValue;
+use program_structure::ast::{Expression, ExpressionInfixOpcode, ExpressionPrefixOpcode};
+use crate::json_porting::transform_constraint_to_json;
+use crate::{ExecutedImplication, SafetyImplication};
+
+// Certificates record why the properties of a template hold, so they can be
+// re-validated without trusting the deduction rules:
+//   - bound_steps: each bound deduced, with the rule, the constraints used and
+//     the bounds of the signals (premises) at the moment the rule was applied
+//   - determinism_steps: the derivation used to prove weak safety without the solver
+//   - smt_proofs: the constraints, contracts, preconditions and deduced bounds in the
+//     unsat core of each SMT proof
+// together with the constraints of the template (and of the children added to the
+// verification) and the safety contracts of the subcomponents used in the proofs
+pub struct BoundStep {
+    pub rule: String,
+    pub signal: usize,
+    pub min: BigInt,
+    pub max: BigInt,
+    pub constraints: Vec<Constraint<usize>>,
+    pub premises: Vec<(usize, BigInt, BigInt)>,
+    pub source: Option<usize>,
+}
+
+pub struct DeterminismStep {
+    pub rule: String,
+    pub signal: usize,
+    pub constraint: Option<Constraint<usize>>,
+    pub sources: Vec<usize>,
+}
+
+#[derive(Clone)]
+pub struct SmtProof {
+    pub property: String,
+    pub core_constraints: Vec<Constraint<usize>>,
+    pub core_contracts: Vec<String>,
+    pub core_assumptions: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct TemplateCertificate {
+    pub template_name: String,
+    pub inputs: Vec<usize>,
+    pub outputs: Vec<usize>,
+    pub signal_names: HashMap<usize, String>,
+    pub constraints: Vec<Constraint<usize>>,
+    pub subcomponents: Vec<(String, SafetyImplication)>,
+    pub results: Vec<(String, String)>,
+    pub bound_steps: Vec<BoundStep>,
+    pub determinism_steps: Vec<DeterminismStep>,
+    pub smt_proofs: Vec<SmtProof>,
+}
+
+impl TemplateCertificate {
+    pub fn new(template_name: &str, inputs: Vec<usize>, outputs: Vec<usize>) -> TemplateCertificate {
+        TemplateCertificate { template_name: template_name.to_string(), inputs, outputs, ..TemplateCertificate::default() }
+    }
+
+    pub fn add_result(&mut self, property: &str, result: String){
+        self.results.push((property.to_string(), result));
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        let mut names = json::object! {};
+        let mut order: Vec<&usize> = self.signal_names.keys().collect();
+        order.sort();
+        for s in order{
+            names[s.to_string()] = self.signal_names.get(s).unwrap().as_str().into();
+        }
+        let mut results = json::object! {};
+        for (property, result) in &self.results{
+            results[property.as_str()] = result.as_str().into();
+        }
+        let subcomponents: Vec<JsonValue> = self.subcomponents.iter()
+            .map(|(name, (inputs, outputs))| json::object! {
+                "template": name.as_str(),
+                "inputs": inputs.clone(),
+                "outputs": outputs.clone(),
+            })
+            .collect();
+        json::object! {
+            "template": self.template_name.as_str(),
+            "results": results,
+            "inputs": self.inputs.clone(),
+            "outputs": self.outputs.clone(),
+            "signal_names": names,
+            "constraints": self.constraints.iter().map(transform_constraint_to_json).collect::<Vec<_>>(),
+            "subcomponents": subcomponents,
+            "bound_steps": self.bound_steps.iter().map(|s| s.to_json()).collect::<Vec<_>>(),
+            "determinism_steps": self.determinism_steps.iter().map(|s| s.to_json()).collect::<Vec<_>>(),
+            "smt_proofs": self.smt_proofs.iter().map(|s| s.to_json()).collect::<Vec<_>>(),
+        }
+    }
+}
+
+impl BoundStep {
+    fn to_json(&self) -> JsonValue {
+        let premises: Vec<JsonValue> = self.premises.iter()
+            .map(|(s, min, max)| json::array![*s, min.to_string(), max.to_string()])
+            .collect();
+        let mut step = json::object! {
+            "rule": self.rule.as_str(),
+            "signal": self.signal,
+            "min": self.min.to_string(),
+            "max": self.max.to_string(),
+            "constraints": self.constraints.iter().map(transform_constraint_to_json).collect::<Vec<_>>(),
+            "premises": premises,
+        };
+        if let Some(source) = self.source{
+            step["source"] = source.into();
+        }
+        step
+    }
+}
+
+impl DeterminismStep {
+    fn to_json(&self) -> JsonValue {
+        let mut step = json::object! {
+            "rule": self.rule.as_str(),
+            "signal": self.signal,
+            "sources": self.sources.clone(),
+        };
+        if let Some(constraint) = &self.constraint{
+            step["constraint"] = transform_constraint_to_json(constraint);
+        }
+        step
+    }
+}
+
+impl SmtProof {
+    fn to_json(&self) -> JsonValue {
+        json::object! {
+            "property": self.property.as_str(),
+            "core_constraints": self.core_constraints.iter().map(transform_constraint_to_json).collect::<Vec<_>>(),
+            "core_contracts": self.core_contracts.iter().map(|c| c.as_str()).collect::<Vec<_>>(),
+            "core_assumptions": self.core_assumptions.iter().map(|c| c.as_str()).collect::<Vec<_>>(),
+        }
+    }
+}
+
+pub fn certificates_to_json(field: &BigInt, certificates: &[TemplateCertificate]) -> String {
+    let value = json::object! {
+        "field": field.to_string(),
+        "templates": certificates.iter().map(|c| c.to_json()).collect::<Vec<_>>(),
+    };
+    json::stringify_pretty(value, 2)
+}
+
+pub fn implication_to_string(implication: &ExecutedImplication) -> String {
+    let left: Vec<String> = implication.left.iter().map(spec_expression_to_string).collect();
+    let right: Vec<String> = implication.right.iter().map(spec_expression_to_string).collect();
+    let left = if left.is_empty(){ "true".to_string() } else{ left.join(" && ") };
+    format!("{} ==> {}", left, right.join(" && "))
+}
+
+pub fn safety_implication_to_string(implication: &SafetyImplication) -> String {
+    let (inputs, outputs) = implication;
+    format!("inputs {:?} determine outputs {:?}", inputs, outputs)
+}
+
+// Signals of the executed specifications are stored as variables named by their index
+pub fn spec_expression_to_string(expr: &Expression) -> String {
+    spec_expression_to_string_with_names(expr, &HashMap::new())
+}
+
+// uses the given names for the signals, the rest are named by their index
+pub fn spec_expression_to_string_with_names(expr: &Expression, signal_names: &HashMap<usize, String>) -> String {
+    use Expression::*;
+    let to_string = |e: &Expression| spec_expression_to_string_with_names(e, signal_names);
+    match expr{
+        Number(_, value) => value.to_string(),
+        Variable { name, .. } => match name.parse::<usize>().ok().and_then(|s| signal_names.get(&s)){
+            Some(signal_name) => signal_name.clone(),
+            None => format!("s_{}", name),
+        },
+        InfixOp { lhe, infix_op, rhe, .. } => {
+            format!("({} {} {})", to_string(lhe), infix_opcode_to_str(infix_op), to_string(rhe))
+        }
+        PrefixOp { prefix_op, rhe, .. } => {
+            let op = match prefix_op{
+                ExpressionPrefixOpcode::Sub => "-",
+                ExpressionPrefixOpcode::BoolNot => "!",
+                ExpressionPrefixOpcode::Complement => "~",
+            };
+            format!("{}{}", op, to_string(rhe))
+        }
+        InlineSwitchOp { cond, if_true, if_false, .. } => {
+            format!("({} ? {} : {})", to_string(cond), to_string(if_true), to_string(if_false))
+        }
+        _ => "<unsupported expression>".to_string(),
+    }
+}
+
+fn infix_opcode_to_str(op: &ExpressionInfixOpcode) -> &'static str {
+    use ExpressionInfixOpcode::*;
+    match op{
+        Mul => "*",
+        Div => "/",
+        Add => "+",
+        Sub => "-",
+        Pow => "**",
+        IntDiv => "\\",
+        Mod => "%",
+        ShiftL => "<<",
+        ShiftR => ">>",
+        LesserEq => "<=",
+        GreaterEq => ">=",
+        Lesser => "<",
+        Greater => ">",
+        Eq => "==",
+        NotEq => "!=",
+        BoolOr => "||",
+        BoolAnd => "&&",
+        BoolImplication => "==>",
+        BitOr => "|",
+        BitAnd => "&",
+        BitXor => "^",
+    }
+}
@@ -0,0 +1,373 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use num_bigint_dig::BigInt;
+use program_structure::ast::{Expression, ExpressionInfixOpcode, ExpressionPrefixOpcode};
+use circom_algebra::algebra::Constraint;
+use circom_algebra::num_traits::ToPrimitive;
+use z3::{Config, Context, SatResult, Solver};
+use z3::ast::{Ast, Bool, Int, BV};
+use crate::{PossibleResult, ExecutedImplication};
+use crate::tags_checking::{get_z3_expression_bool, insert_implication_in_smt};
+
+// Checks that the values computed by the witness generator for the signals of a
+// template (its <-- and <== assignments) satisfy all the constraints of the template
+// for every input that satisfies the preconditions
+pub struct CompletenessVerification {
+    pub signals: Vec<usize>,
+    pub inputs: Vec<usize>,
+    pub constraints: Vec<Constraint<usize>>,
+    pub hints: Vec<(usize, Option<Expression>)>,
+    pub preconditions: Vec<Expression>,
+    pub implications: Vec<ExecutedImplication>,
+    pub field: BigInt,
+    pub verification_timeout: u64,
+    // constraint that is not satisfied by the counterexample, if any
+    pub violated_constraint: Option<usize>,
+    // signal whose value cannot be computed in the counterexample, if any
+    pub failed_hint: Option<usize>,
+}
+
+impl CompletenessVerification {
+    pub fn check(&mut self, logs: &mut Vec<String>) -> PossibleResult{
+        if self.constraints.is_empty(){
+            logs.push("### NOTHING TO VERIFY: THE TEMPLATE HAS NO CONSTRAINTS\n".to_string());
+            return PossibleResult::NOTHING;
+        }
+
+        let mut cfg = Config::new();
+        cfg.set_timeout_msec(self.verification_timeout);
+        let ctx = Context::new(&cfg);
+        let solver = Solver::new(&ctx);
+        let zero = Int::from_i64(&ctx, 0);
+        let p = Int::from_str(&ctx, &self.field.to_string()).unwrap();
+
+        let mut signals_to_smt_rep = HashMap::new();
+        for s in &self.signals{
+            let symbol = Int::new_const(&ctx, format!("s_{}", s));
+            solver.assert(&symbol.ge(&zero));
+            solver.assert(&symbol.lt(&p));
+            signals_to_smt_rep.insert(*s, symbol);
+        }
+
+        // a signal assigned in several branches of an unknown condition has no single hint
+        let mut hints: BTreeMap<usize, Option<&Expression>> = BTreeMap::new();
+        for (s, hint) in &self.hints{
+            if hints.contains_key(s){
+                hints.insert(*s, None);
+            } else{
+                hints.insert(*s, hint.as_ref());
+            }
+        }
+
+        // if some assumption cannot be encoded the counterexamples may be spurious
+        let mut complete_assumptions = true;
+        let mut preconditions = Bool::from_bool(&ctx, true);
+        for precondition in &self.preconditions{
+            match get_z3_expression_bool(&ctx, precondition, &signals_to_smt_rep){
+                Ok(condition) => preconditions &= condition,
+                Err(_) => complete_assumptions = false,
+            }
+        }
+        solver.assert(&preconditions);
+        for implication in &self.implications{
+            insert_implication_in_smt(implication, &ctx, &solver, &signals_to_smt_rep, &self.field, None);
+        }
+
+        let encoder = HintEncoder{ ctx: &ctx, solver: &solver, field: &self.field, p: &p, signals: &signals_to_smt_rep };
+        let mut computed = HashMap::new();
+        let mut hint_failures = Vec::new();
+        for (s, hint) in &hints{
+            let symbol = match signals_to_smt_rep.get(s){
+                Some(symbol) => symbol,
+                None => continue,
+            };
+            if let Some((value, ok)) = hint.and_then(|h| encoder.encode(h)){
+                solver.assert(&ok.implies(&symbol._eq(&value)));
+                hint_failures.push((*s, ok.not()));
+                computed.insert(*s, hint.unwrap());
+            }
+        }
+
+        let mut violations = Vec::new();
+        for c in &self.constraints{
+            let a = linear_expression_in_smt(&ctx, c.a(), &signals_to_smt_rep);
+            let b = linear_expression_in_smt(&ctx, c.b(), &signals_to_smt_rep);
+            let c = linear_expression_in_smt(&ctx, c.c(), &signals_to_smt_rep);
+            violations.push((a * b - c).modulo(&p)._eq(&zero).not());
+        }
+
+        let mut failures: Vec<&Bool> = hint_failures.iter().map(|(_, f)| f).collect();
+        failures.extend(violations.iter());
+        solver.assert(&Bool::or(&ctx, &failures));
+
+        match solver.check(){
+            SatResult::Sat =>{
+                let model = solver.get_model().unwrap();
+                let holds = |condition: &Bool| model.eval(condition, true).and_then(|v| v.as_bool()).unwrap_or(false);
+                let mut involved = HashSet::new();
+                if let Some((s, _)) = hint_failures.iter().find(|(_, f)| holds(f)){
+                    self.failed_hint = Some(*s);
+                    expression_signals(computed.get(s).unwrap(), &mut involved);
+                } else if let Some(i) = violations.iter().position(holds){
+                    self.violated_constraint = Some(i);
+                    involved.extend(self.constraints[i].take_cloned_signals());
+                }
+
+                // the values of the signals not computed by the template (outputs of the
+                // subcomponents, signals assigned under unknown conditions...) are not encoded
+                let free_signals = self.free_signals(&involved, &computed);
+                if !free_signals.is_empty() || !complete_assumptions{
+                    logs.push(format!("### UNKNOWN: COULD NOT PROVE THE COMPLETENESS OF THE WITNESS GENERATOR, THE VALUES OF THE SIGNALS {:?} ARE NOT KNOWN\n", free_signals));
+                    self.violated_constraint = None;
+                    self.failed_hint = None;
+                    return PossibleResult::UNKNOWN;
+                }
+
+                logs.push("### THE WITNESS GENERATOR OF THE TEMPLATE IS NOT COMPLETE. FOUND COUNTEREXAMPLE USING SMT:\n".to_string());
+                for s in &self.inputs{
+                    let v = model.eval(signals_to_smt_rep.get(s).unwrap(), true).unwrap();
+                    logs.push(format!("Input signal {}: {}\n", s, v));
+                }
+                if let Some(s) = self.failed_hint{
+                    logs.push(format!("The value assigned to the signal {} cannot be computed\n", s));
+                }
+                if let Some(i) = self.violated_constraint{
+                    logs.push(format!("The computed values do not satisfy the constraint {}\n", i));
+                }
+                PossibleResult::FAILED
+            },
+            SatResult::Unsat =>{
+                logs.push("### THE VALUES COMPUTED BY THE WITNESS GENERATOR SATISFY THE CONSTRAINTS\n".to_string());
+                PossibleResult::VERIFIED
+            },
+            _ =>{
+                logs.push("### UNKNOWN: VERIFICATION OF THE COMPLETENESS TIMEOUT\n".to_string());
+                PossibleResult::UNKNOWN
+            }
+        }
+    }
+
+    // signals whose values are not determined by the inputs and the computed hints
+    fn free_signals(&self, involved: &HashSet<usize>, computed: &HashMap<usize, &Expression>) -> Vec<usize>{
+        let mut free = Vec::new();
+        let mut visited = HashSet::new();
+        let mut to_visit: Vec<usize> = involved.iter().cloned().collect();
+        while let Some(s) = to_visit.pop(){
+            if s == 0 || !visited.insert(s) || self.inputs.contains(&s){
+                continue;
+            }
+            match computed.get(&s){
+                Some(hint) => {
+                    let mut signals = HashSet::new();
+                    expression_signals(hint, &mut signals);
+                    to_visit.extend(signals);
+                }
+                None => free.push(s),
+            }
+        }
+        free.sort();
+        free
+    }
+}
+
+pub(crate) fn linear_expression_in_smt<'a>(ctx: &'a Context, le: &HashMap<usize, BigInt>, signals_to_smt_symbols: &HashMap<usize, Int<'a>>) -> Int<'a>{
+    let mut value = Int::from_i64(ctx, 0);
+    for (signal, coef) in le{
+        let coef = Int::from_str(ctx, &coef.to_string()).unwrap();
+        if *signal == 0{
+            value += coef;
+        } else{
+            value += signals_to_smt_symbols.get(signal).unwrap() * coef;
+        }
+    }
+    value
+}
+
+fn expression_signals(expr: &Expression, signals: &mut HashSet<usize>){
+    use Expression::*;
+    match expr{
+        Variable { name, .. } => {
+            if let Ok(s) = name.parse::<usize>(){
+                signals.insert(s);
+            }
+        }
+        InfixOp { lhe, rhe, .. } => {
+            expression_signals(lhe, signals);
+            expression_signals(rhe, signals);
+        }
+        PrefixOp { rhe, .. } => expression_signals(rhe, signals),
+        InlineSwitchOp { cond, if_true, if_false, .. } => {
+            expression_signals(cond, signals);
+            expression_signals(if_true, signals);
+            expression_signals(if_false, signals);
+        }
+        _ => {}
+    }
+}
+
+// Encodes the expressions computed by the witness generator using the semantics of
+// the circom operators. Each expression is encoded as its value in [0, p) and the
+// condition under which it can be computed (no divisions by zero)
+struct HintEncoder<'a, 'b>{
+    ctx: &'a Context,
+    solver: &'b Solver<'a>,
+    field: &'b BigInt,
+    p: &'b Int<'a>,
+    signals: &'b HashMap<usize, Int<'a>>,
+}
+
+impl<'a, 'b> HintEncoder<'a, 'b>{
+    fn encode(&self, expr: &Expression) -> Option<(Int<'a>, Bool<'a>)>{
+        use Expression::*;
+        let ctx = self.ctx;
+        match expr{
+            Number(_, value) => {
+                let value = ((value % self.field) + self.field) % self.field;
+                Some((self.constant(&value), Bool::from_bool(ctx, true)))
+            }
+            Variable { name, .. } => {
+                let symbol = self.signals.get(&name.parse::<usize>().ok()?)?;
+                Some((symbol.clone(), Bool::from_bool(ctx, true)))
+            }
+            InfixOp { lhe, infix_op, rhe, .. } => {
+                let (l_value, l_ok) = self.encode(lhe)?;
+                let (r_value, r_ok) = self.encode(rhe)?;
+                let ok = Bool::and(ctx, &[&l_ok, &r_ok]);
+                let (value, op_ok) = self.encode_infix(*infix_op, l_value, r_value, rhe)?;
+                Some((value, Bool::and(ctx, &[&ok, &op_ok])))
+            }
+            PrefixOp { prefix_op, rhe, .. } => {
+                let (value, ok) = self.encode(rhe)?;
+                let value = match prefix_op{
+                    ExpressionPrefixOpcode::Sub => (self.p - &value).modulo(self.p),
+                    ExpressionPrefixOpcode::BoolNot => self.bool_to_int(&value._eq(&self.int(0))),
+                    ExpressionPrefixOpcode::Complement => {
+                        BV::from_int(&value, 256).bvnot().to_int(false).modulo(self.p)
+                    }
+                };
+                Some((value, ok))
+            }
+            InlineSwitchOp { cond, if_true, if_false, .. } => {
+                let (c_value, c_ok) = self.encode(cond)?;
+                let (t_value, t_ok) = self.encode(if_true)?;
+                let (f_value, f_ok) = self.encode(if_false)?;
+                let c = c_value._eq(&self.int(0)).not();
+                let ok = Bool::and(ctx, &[&c_ok, &c.ite(&t_ok, &f_ok)]);
+                Some((c.ite(&t_value, &f_value), ok))
+            }
+            _ => None,
+        }
+    }
+
+    fn encode_infix(&self, infix_op: ExpressionInfixOpcode, l: Int<'a>, r: Int<'a>, rhe: &Expression) -> Option<(Int<'a>, Bool<'a>)>{
+        use ExpressionInfixOpcode::*;
+        let ctx = self.ctx;
+        let p = self.p;
+        let non_zero = r._eq(&self.int(0)).not();
+        let always = Bool::from_bool(ctx, true);
+        let result = match infix_op{
+            Add => ((l + r).modulo(p), always),
+            Sub => ((l - r).modulo(p), always),
+            Mul => ((l * r).modulo(p), always),
+            Div => {
+                // the witness generator multiplies by the inverse of r
+                let q = Int::fresh_const(ctx, "div");
+                self.solver.assert(&q.ge(&self.int(0)));
+                self.solver.assert(&q.lt(p));
+                self.solver.assert(&non_zero.implies(&(&q * &r).modulo(p)._eq(&l)));
+                (q, non_zero)
+            }
+            IntDiv => (l.div(&r), non_zero),
+            Mod => (l.modulo(&r), non_zero),
+            Pow => {
+                let exponent = constant_value(rhe)?.to_usize().filter(|e| *e <= 64)?;
+                let mut value = self.int(1);
+                for _ in 0..exponent{
+                    value = (value * &l).modulo(p);
+                }
+                (value, always)
+            }
+            ShiftL | ShiftR => {
+                let k = constant_value(rhe)?;
+                let top = self.field / BigInt::from(2);
+                let (left, k) = if k <= top{
+                    (infix_op == ShiftL, k)
+                } else{
+                    (infix_op != ShiftL, self.field - k)
+                };
+                let k = k.to_usize()?;
+                let bits = self.field.bits();
+                let value = if left{
+                    if k >= bits{
+                        self.int(0)
+                    } else{
+                        let mask = self.constant(&(BigInt::from(1) << bits));
+                        (l * self.constant(&(BigInt::from(1) << k))).modulo(&mask).modulo(p)
+                    }
+                } else if k >= bits{
+                    self.int(0)
+                } else{
+                    l.div(&self.constant(&(BigInt::from(1) << k)))
+                };
+                (value, always)
+            }
+            BitAnd => {
+                // usual case of masks 2^m - 1 that take the m lowest bits
+                let power = constant_value(rhe)
+                    .map(|m| m + BigInt::from(1))
+                    .filter(|m| *m > BigInt::from(1) && (m & (m - BigInt::from(1))) == BigInt::from(0));
+                match power{
+                    Some(power) => (l.modulo(&self.constant(&power)), always),
+                    None => (self.bitwise(&l, &r, BV::bvand), always),
+                }
+            }
+            BitOr => (self.bitwise(&l, &r, BV::bvor), always),
+            BitXor => (self.bitwise(&l, &r, BV::bvxor), always),
+            Eq => (self.bool_to_int(&l._eq(&r)), always),
+            NotEq => (self.bool_to_int(&l._eq(&r).not()), always),
+            Lesser => (self.bool_to_int(&self.val(&l).lt(&self.val(&r))), always),
+            LesserEq => (self.bool_to_int(&self.val(&l).le(&self.val(&r))), always),
+            Greater => (self.bool_to_int(&self.val(&l).gt(&self.val(&r))), always),
+            GreaterEq => (self.bool_to_int(&self.val(&l).ge(&self.val(&r))), always),
+            BoolAnd => {
+                let both = Bool::and(ctx, &[&l._eq(&self.int(0)).not(), &non_zero]);
+                (self.bool_to_int(&both), always)
+            }
+            BoolOr => {
+                let any = Bool::or(ctx, &[&l._eq(&self.int(0)).not(), &non_zero]);
+                (self.bool_to_int(&any), always)
+            }
+            BoolImplication => return None,
+        };
+        Some(result)
+    }
+
+    fn bitwise(&self, l: &Int<'a>, r: &Int<'a>, op: fn(&BV<'a>, &BV<'a>) -> BV<'a>) -> Int<'a>{
+        let bits = self.field.bits() as u32;
+        op(&BV::from_int(l, bits), &BV::from_int(r, bits)).to_int(false).modulo(self.p)
+    }
+
+    // the comparisons of circom consider the values greater than p/2 as negative
+    fn val(&self, value: &Int<'a>) -> Int<'a>{
+        let c = self.constant(&(self.field / BigInt::from(2) + BigInt::from(1)));
+        value.ge(&c).ite(&(value - self.p), value)
+    }
+
+    fn bool_to_int(&self, condition: &Bool<'a>) -> Int<'a>{
+        condition.ite(&self.int(1), &self.int(0))
+    }
+
+    fn int(&self, value: i64) -> Int<'a>{
+        Int::from_i64(self.ctx, value)
+    }
+
+    fn constant(&self, value: &BigInt) -> Int<'a>{
+        Int::from_str(self.ctx, &value.to_string()).unwrap()
+    }
+}
+
+fn constant_value(expr: &Expression) -> Option<BigInt>{
+    match expr{
+        Expression::Number(_, value) => Some(value.clone()),
+        _ => None,
+    }
+}
@@ -2,9 +2,11 @@ use super::Node;
 use circom_algebra::algebra::Constraint;
 use program_structure::error_code::ReportCode;
 use program_structure::error_definition::{Report, ReportCollection};
-use std::collections::{HashMap, HashSet};
+use program_structure::file_definition::{FileID, FileLocation};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 type C = Constraint<usize>;
+type Locations = BTreeSet<(FileID, usize, usize)>;
 const UNCONSTRAINED_SIGNAL_CODE: ReportCode = ReportCode::UnconstrainedSignal;
 const UNCONSTRAINED_IOSIGNAL_CODE: ReportCode = ReportCode::UnconstrainedIOSignal;
 
@@ -46,6 +48,14 @@ impl UnconstrainedIOSignal {
     }
 }
 
+// points at the statements that constrain the rest of the positions of the array
+fn add_locations(report: &mut Report, signal: &str, locations: Option<&Locations>) {
+    for (file_id, start, end) in locations.into_iter().flatten() {
+        let location: FileLocation = *start..*end;
+        report.add_primary(location, *file_id, format!("Other positions of {} are constrained here", signal));
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq)]
 enum SignalType {
     Local,
@@ -55,6 +65,8 @@ struct Analysis {
     template_name: String,
     // signal name, type and number of appearances
     signal_stats: Vec<(String, SignalType, usize)>,
+    // statements constraining the signals of each array
+    array_locations: HashMap<String, Locations>,
 }
 
 fn split_signal_name_index(name: &String)-> String{
@@ -91,11 +103,13 @@ fn analysis_interpretation(analysis: Analysis, result: &mut AnalysisResult) {
         }
     }
     for (name, (xtype, examples)) in signal2unconstrainedex{
-        if xtype == SignalType::Local{
-            result.warnings.push(UnconstrainedSignal::new(&name, &tmp_name, &examples));
+        let mut report = if xtype == SignalType::Local{
+            UnconstrainedSignal::new(&name, &tmp_name, &examples)
         } else{
-            result.warnings.push(UnconstrainedIOSignal::new(&name, &tmp_name, &examples));
-        }
+            UnconstrainedIOSignal::new(&name, &tmp_name, &examples)
+        };
+        add_locations(&mut report, &name, analysis.array_locations.get(&name));
+        result.warnings.push(report);
     }
 }
 
@@ -109,11 +123,18 @@ fn visit_node(node: &Node) -> Analysis {
             constraint_counter.insert(*id, 0);
         }
     }
-    for constraint in &node.constraints {
+    let mut array_locations: HashMap<String, Locations> = HashMap::new();
+    for (constraint, location) in node.constraints.iter().zip(node.constraint_locations.iter()) {
         let signals = constraint.take_cloned_signals();
         for signal in signals {
             let prev = constraint_counter.remove(&signal).unwrap();
             constraint_counter.insert(signal, prev + 1);
+            if let (Some(name), Some(file_id)) = (rev_correspondence.get(&signal), location.file_id) {
+                array_locations
+                    .entry(split_signal_name_index(name))
+                    .or_default()
+                    .insert((file_id, location.location.start, location.location.end));
+            }
         }
     }
 
@@ -136,6 +157,7 @@ fn visit_node(node: &Node) -> Analysis {
     Analysis {
         template_name: node.template_name.clone(),
         signal_stats,
+        array_locations,
     }
 }
 
@@ -147,10 +169,12 @@ pub fn clean_constraints(nodes: &mut [Node]){
     for node in nodes{
         let length_bound = Vec::len(&node.constraints);
         let work = std::mem::replace(&mut node.constraints, Vec::with_capacity(length_bound));
-        for mut constraint in work {
+        let locations = std::mem::replace(&mut node.constraint_locations, Vec::with_capacity(length_bound));
+        for (mut constraint, location) in work.into_iter().zip(locations) {
             C::remove_zero_value_coefficients(&mut constraint);
             if !C::is_empty(&constraint) {
                 Vec::push(&mut node.constraints, constraint);
+                Vec::push(&mut node.constraint_locations, location);
             }
         }
     }
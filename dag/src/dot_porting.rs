@@ -0,0 +1,97 @@
+use super::DAG;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+// writes the graph of template instances of the circuit in the DOT format of Graphviz,
+// the edges from a node to the same subcomponent template are merged into a single one
+pub fn write(dag: &DAG, file_name: &str, signal_ranges: bool) -> Result<(), ()> {
+    let file = File::create(file_name).map_err(|_err| {})?;
+    let mut writer = BufWriter::new(file);
+    writer.write_all(generate(dag, signal_ranges).as_bytes()).map_err(|_err| {})?;
+    writer.flush().map_err(|_err| {})
+}
+
+fn generate(dag: &DAG, signal_ranges: bool) -> String {
+    // the subcomponents always have smaller ids than their parents
+    let mut times_reached = vec![0; dag.number_of_nodes()];
+    times_reached[dag.main_id()] = 1;
+    for id in (0..dag.number_of_nodes()).rev() {
+        for edge in &dag.adjacency[id] {
+            times_reached[edge.goes_to] += times_reached[id];
+        }
+    }
+
+    let mut dot = String::new();
+    dot.push_str("digraph circuit {\n");
+    dot.push_str("    node [shape=box, style=rounded, fontname=\"Helvetica\"];\n");
+    dot.push_str("    edge [fontname=\"Helvetica\", fontsize=10];\n");
+    for (id, node) in dag.nodes.iter().enumerate() {
+        if times_reached[id] == 0 {
+            continue;
+        }
+        let mut label = vec![node.pretty_template_name.clone()];
+        if !node.parameters.is_empty() {
+            let parameters: Vec<String> = node.parameters.iter().map(|p| p.to_string()).collect();
+            label.push(format!("parameters: {}", parameters.join(", ")));
+        }
+        label.push(format!("constraints: {}", node.constraints.len()));
+        label.push(format!(
+            "signals: {} inputs, {} outputs, {} intermediates",
+            node.number_of_inputs(),
+            node.number_of_outputs(),
+            node.number_of_intermediates()
+        ));
+        label.push(format!(
+            "reached {} times ({} constraints in total)",
+            times_reached[id],
+            times_reached[id] * node.constraints.len()
+        ));
+        let attributes = if node.is_custom_gate {
+            label.push("custom template".to_string());
+            "shape=box3d, style=filled, fillcolor=\"#fff3c4\", "
+        } else if node.is_parallel {
+            label.push("parallel".to_string());
+            "style=\"rounded,dashed,bold\", "
+        } else if id == dag.main_id() {
+            "style=\"rounded,bold\", "
+        } else {
+            ""
+        };
+        dot.push_str(&format!("    n{} [{}label=\"{}\"];\n", id, attributes, escape(&label.join("\n"))));
+    }
+
+    for (id, edges) in dag.adjacency.iter().enumerate() {
+        if times_reached[id] == 0 {
+            continue;
+        }
+        let mut groups: Vec<(usize, Vec<usize>)> = Vec::new();
+        for (position, edge) in edges.iter().enumerate() {
+            match groups.iter_mut().find(|(to, _)| *to == edge.goes_to) {
+                Some((_, positions)) => positions.push(position),
+                None => groups.push((edge.goes_to, vec![position])),
+            }
+        }
+        for (to, positions) in groups {
+            let first = &edges[positions[0]];
+            let mut label = if positions.len() == 1 {
+                first.label.clone()
+            } else {
+                format!("{} (x{})", first.label, positions.len())
+            };
+            if signal_ranges {
+                let start = positions.iter().map(|p| edges[*p].in_number).min().unwrap();
+                let end = positions.iter().map(|p| edges[*p].out_number).max().unwrap();
+                label.push_str(&format!("\nsignals [{}, {})", start, end));
+            }
+            // the components created with the parallel keyword
+            let style = if positions.iter().any(|p| edges[*p].is_parallel) { "style=dashed, " } else { "" };
+            dot.push_str(&format!("    n{} -> n{} [{}label=\"{}\"];\n", id, to, style, escape(&label)));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use num_bigint_dig::BigInt;
+use program_structure::ast::Expression;
+use circom_algebra::algebra::Constraint;
+use circom_algebra::modular_arithmetic;
+use z3::{Config, Context, SatResult, Solver};
+use z3::ast::{Ast, Bool, Int};
+use crate::PossibleResult;
+use crate::tags_checking::{constraint_in_smt, evaluate_constraint, get_z3_expression_bool};
+
+// One of the two circuits compared when checking equivalence, the inputs and outputs
+// of both circuits are matched by their position
+pub struct EquivalenceCircuit<'a> {
+    pub constraints: Vec<&'a Constraint<usize>>,
+    pub number_signals: usize,
+    pub inputs: Vec<usize>,
+    pub outputs: Vec<usize>,
+    pub assumptions: Vec<Expression>,
+    pub signal_names: &'a HashMap<usize, String>,
+}
+
+// Checks that two circuits produce the same outputs for the same inputs, whenever
+// the inputs satisfy the assumptions of both main components
+pub struct EquivalenceVerification<'a> {
+    pub left: EquivalenceCircuit<'a>,
+    pub right: EquivalenceCircuit<'a>,
+    pub field: BigInt,
+    pub verification_timeout: u64,
+}
+
+impl<'a> EquivalenceVerification<'a> {
+    pub fn check(&self, logs: &mut Vec<String>) -> PossibleResult{
+        if self.left.inputs.len() != self.right.inputs.len() || self.left.outputs.len() != self.right.outputs.len(){
+            logs.push("### THE MAIN COMPONENTS HAVE A DIFFERENT NUMBER OF INPUTS OR OUTPUTS\n".to_string());
+            return PossibleResult::FAILED;
+        }
+        let mut cfg = Config::new();
+        cfg.set_timeout_msec(self.verification_timeout);
+        let ctx = Context::new(&cfg);
+        let solver = Solver::new(&ctx);
+        let zero = Int::from_i64(&ctx, 0);
+        let field = Int::from_str(&ctx, &self.field.to_string()).unwrap();
+
+        let mut left_to_smt = HashMap::new();
+        for s in 1..self.left.number_signals{
+            left_to_smt.insert(s, Int::new_const(&ctx, format!("l_{}", s)));
+        }
+        // the inputs of the second circuit are the ones of the first one
+        let mut right_to_smt = HashMap::new();
+        for (left, right) in self.left.inputs.iter().zip(self.right.inputs.iter()){
+            right_to_smt.insert(*right, left_to_smt.get(left).unwrap().clone());
+        }
+        for s in 1..self.right.number_signals{
+            right_to_smt.entry(s).or_insert_with(|| Int::new_const(&ctx, format!("r_{}", s)));
+        }
+        for symbol in left_to_smt.values().chain(right_to_smt.values()){
+            solver.assert(&symbol.ge(&zero));
+            solver.assert(&symbol.lt(&field));
+        }
+
+        let deductions = HashMap::new();
+        let mut num_k = 0;
+        for (name, circuit, signals_to_smt) in [("FIRST", &self.left, &left_to_smt), ("SECOND", &self.right, &right_to_smt)]{
+            for assumption in &circuit.assumptions{
+                match get_z3_expression_bool(&ctx, assumption, signals_to_smt){
+                    Ok(condition) => solver.assert(&condition),
+                    Err(_) => {
+                        // without the assumption any counterexample may be spurious
+                        logs.push(format!("### UNKNOWN: SOME ASSUMPTION OF THE MAIN COMPONENT OF THE {} CIRCUIT CANNOT BE ENCODED IN SMT\n", name));
+                        return PossibleResult::UNKNOWN;
+                    }
+                }
+            }
+            for constraint in &circuit.constraints{
+                solver.assert(&constraint_in_smt(constraint, &ctx, signals_to_smt, &self.field, &deductions, num_k, &field).0);
+                num_k += 1;
+            }
+        }
+
+        let mut different_outputs = Vec::new();
+        for (left, right) in self.left.outputs.iter().zip(self.right.outputs.iter()){
+            different_outputs.push(left_to_smt.get(left).unwrap()._eq(right_to_smt.get(right).unwrap()).not());
+        }
+        let different_outputs: Vec<&Bool> = different_outputs.iter().collect();
+        solver.assert(&Bool::or(&ctx, &different_outputs));
+
+        match solver.check(){
+            SatResult::Sat =>{
+                let model = solver.get_model().unwrap();
+                let value = |symbol: &Int| -> BigInt{
+                    model.eval(symbol, true).unwrap().to_string().parse::<BigInt>().unwrap()
+                };
+                // the counterexample is checked in the arithmetic of the field
+                let mut violated = Vec::new();
+                for (name, circuit, signals_to_smt) in [("first", &self.left, &left_to_smt), ("second", &self.right, &right_to_smt)]{
+                    let values: HashMap<usize, BigInt> = signals_to_smt.iter().map(|(s, symbol)| (*s, value(symbol))).collect();
+                    for (i, c) in circuit.constraints.iter().enumerate(){
+                        if let Some((a, b, c)) = evaluate_constraint(c, &values, &self.field){
+                            let result = modular_arithmetic::sub(&modular_arithmetic::mul(&a, &b, &self.field), &c, &self.field);
+                            if result != BigInt::from(0){
+                                violated.push(format!("Constraint {} of the {} circuit: A = {}, B = {}, C = {}, A * B - C = {}\n", i, name, a, b, c, result));
+                            }
+                        }
+                    }
+                }
+                if !violated.is_empty(){
+                    logs.push("### ENCODING ERROR: THE COUNTEREXAMPLE FOUND USING SMT DOES NOT SATISFY THE CONSTRAINTS\n".to_string());
+                    logs.append(&mut violated);
+                    return PossibleResult::SPURIOUS;
+                }
+
+                logs.push("### THE CIRCUITS ARE NOT EQUIVALENT. FOUND COUNTEREXAMPLE USING SMT:\n".to_string());
+                for s in &self.left.inputs{
+                    logs.push(format!("Input signal {}: {}\n", signal_name(self.left.signal_names, s), value(left_to_smt.get(s).unwrap())));
+                }
+                for (left, right) in self.left.outputs.iter().zip(self.right.outputs.iter()){
+                    logs.push(format!("Output signal {}: values {} | {}\n", 
+                        signal_name(self.left.signal_names, left), 
+                        value(left_to_smt.get(left).unwrap()), 
+                        value(right_to_smt.get(right).unwrap())
+                    ));
+                }
+                PossibleResult::FAILED
+            },
+            SatResult::Unsat =>{
+                logs.push("### SUCCESS: BOTH CIRCUITS PRODUCE THE SAME OUTPUTS FOR EVERY VALID INPUT\n".to_string());
+                PossibleResult::VERIFIED
+            },
+            _=> {
+                logs.push("### UNKNOWN: VERIFICATION OF THE EQUIVALENCE TIMEOUT\n".to_string());
+                PossibleResult::UNKNOWN
+            }
+        }
+    }
+}
+
+fn signal_name(signal_names: &HashMap<usize, String>, s: &usize) -> String{
+    match signal_names.get(s){
+        Some(name) => name.clone(),
+        None => format!("s_{}", s),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use program_structure::ast::{ExpressionInfixOpcode, Meta};
+
+    fn identity<'a>(assumptions: Vec<Expression>, signal_names: &'a HashMap<usize, String>, constraints: Vec<&'a Constraint<usize>>) -> EquivalenceCircuit<'a> {
+        EquivalenceCircuit{ constraints, number_signals: 3, inputs: vec![2], outputs: vec![1], assumptions, signal_names }
+    }
+
+    #[test]
+    fn unencodable_assumption_is_unknown() {
+        // out - in = 0 over the field of 7 elements
+        let constraint = Constraint::new(
+            HashMap::new(),
+            HashMap::new(),
+            HashMap::from([(1, BigInt::from(1)), (2, BigInt::from(6))]),
+        );
+        let names = HashMap::new();
+        let infix = |lhe: Expression, infix_op, rhe: Expression| Expression::InfixOp {
+            meta: Meta::new(0, 0), lhe: Box::new(lhe), infix_op, rhe: Box::new(rhe)
+        };
+        let input = Expression::Variable { meta: Meta::new(0, 0), name: "2".to_string(), access: Vec::new() };
+        let xor = infix(input, ExpressionInfixOpcode::BitXor, Expression::Number(Meta::new(0, 0), BigInt::from(1)));
+        let assumption = infix(xor, ExpressionInfixOpcode::Eq, Expression::Number(Meta::new(0, 0), BigInt::from(0)));
+        let field = BigInt::from(7);
+
+        let verification = EquivalenceVerification{
+            left: identity(Vec::new(), &names, vec![&constraint]),
+            right: identity(vec![assumption], &names, vec![&constraint]),
+            field: field.clone(),
+            verification_timeout: 10000,
+        };
+        let mut logs = Vec::new();
+        assert_eq!(verification.check(&mut logs), PossibleResult::UNKNOWN);
+        assert!(logs[0].contains("SECOND CIRCUIT CANNOT BE ENCODED"));
+
+        let verification = EquivalenceVerification{
+            left: identity(Vec::new(), &names, vec![&constraint]),
+            right: identity(Vec::new(), &names, vec![&constraint]),
+            field,
+            verification_timeout: 10000,
+        };
+        assert_eq!(verification.check(&mut Vec::new()), PossibleResult::VERIFIED);
+    }
+}
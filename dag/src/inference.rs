@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use num_bigint_dig::BigInt;
+use program_structure::ast::{Expression, ExpressionInfixOpcode, Meta};
+use crate::TagSemantics;
+use crate::tags_checking::TemplateVerification;
+
+// A candidate postcondition together with its representation in circom
+struct Candidate{
+    expression: Expression,
+    text: String,
+}
+
+fn number(value: BigInt) -> Expression{
+    Expression::Number(Meta::new(0, 0), value)
+}
+
+fn signal(s: usize) -> Expression{
+    Expression::Variable { meta: Meta::new(0, 0), name: s.to_string(), access: Vec::new() }
+}
+
+fn infix(lhe: Expression, infix_op: ExpressionInfixOpcode, rhe: Expression) -> Expression{
+    Expression::InfixOp { meta: Meta::new(0, 0), lhe: Box::new(lhe), infix_op, rhe: Box::new(rhe) }
+}
+
+fn binary((s, name): &(usize, String)) -> Candidate{
+    use ExpressionInfixOpcode::*;
+    Candidate{
+        expression: infix(infix(number(BigInt::from(0)), LesserEq, signal(*s)), BoolAnd, infix(signal(*s), LesserEq, number(BigInt::from(1)))),
+        text: format!("0 <= {} && {} <= 1", name, name),
+    }
+}
+
+fn range((s, name): &(usize, String), bits: usize) -> Candidate{
+    use ExpressionInfixOpcode::*;
+    let bound = BigInt::from(1) << bits;
+    Candidate{
+        expression: infix(infix(number(BigInt::from(0)), LesserEq, signal(*s)), BoolAnd, infix(signal(*s), Lesser, number(bound))),
+        text: format!("0 <= {} && {} < 2**{}", name, name, bits),
+    }
+}
+
+fn equal((s, name): &(usize, String), (other, other_name): &(usize, String)) -> Candidate{
+    Candidate{
+        expression: infix(signal(*s), ExpressionInfixOpcode::Eq, signal(*other)),
+        text: format!("{} == {}", name, other_name),
+    }
+}
+
+fn one_hot(signals: &[&(usize, String)]) -> Candidate{
+    let mut expression = signal(signals[0].0);
+    for (s, _) in &signals[1..]{
+        expression = infix(expression, ExpressionInfixOpcode::Add, signal(*s));
+    }
+    let names: Vec<&str> = signals.iter().map(|(_, name)| name.as_str()).collect();
+    Candidate{
+        expression: infix(expression, ExpressionInfixOpcode::Eq, number(BigInt::from(1))),
+        text: format!("{} == 1", names.join(" + ")),
+    }
+}
+
+// name of the array containing the signal, if any
+fn array_name(name: &str) -> Option<&str>{
+    name.find('[').map(|i| &name[..i])
+}
+
+// returns the strongest candidates satisfied by each output of the template: the equality
+// with an input, the smallest range [0, 2^k) and the one-hot encoding of the output arrays
+pub fn infer_postconditions(
+    verification: &mut TemplateVerification,
+    outputs: &[(usize, String)],
+    inputs: &[(usize, String)]
+) -> Vec<String>{
+    let mut inferred = Vec::new();
+    let max_bits = verification.field.bits();
+    let mut binary_outputs = Vec::new();
+    for output in outputs{
+        if let Some(input) = inputs.iter().find(|input| verification.prove_candidate(&equal(output, input).expression)){
+            inferred.push(equal(output, input).text);
+        }
+
+        // the range candidates are monotone in the number of bits, the deduced
+        // bounds give the first upper limit of the search, the ranges that contain
+        // the whole field are not reported
+        let mut upper = match verification.deductions.get(&output.0){
+            Some(bounds) if bounds.min >= BigInt::from(0) => bounds.max.bits().max(1),
+            _ => max_bits - 1,
+        };
+        if upper >= max_bits || !verification.prove_candidate(&range(output, upper).expression){
+            continue;
+        }
+        let mut lower = 0;
+        while lower + 1 < upper{
+            let middle = (lower + upper) / 2;
+            if verification.prove_candidate(&range(output, middle).expression){
+                upper = middle;
+            } else{
+                lower = middle;
+            }
+        }
+        if upper == 1{
+            inferred.push(binary(output).text);
+            binary_outputs.push(output);
+        } else{
+            inferred.push(range(output, upper).text);
+        }
+    }
+
+    let mut position = 0;
+    while position < binary_outputs.len(){
+        let name = array_name(&binary_outputs[position].1);
+        let group: Vec<&(usize, String)> = binary_outputs[position..].iter()
+            .take_while(|output| name.is_some() && array_name(&output.1) == name)
+            .copied()
+            .collect();
+        position += group.len().max(1);
+        if group.len() > 1 && verification.prove_candidate(&one_hot(&group).expression){
+            inferred.push(one_hot(&group).text);
+        }
+    }
+    inferred
+}
+
+// returns the tags whose semantics is satisfied by every position of an output declared
+// without them, together with the smallest value found for the tags with value
+pub fn suggest_tags(
+    verification: &mut TemplateVerification,
+    outputs: &[(usize, String)],
+    semantics: &[TagSemantics],
+    signal_tags: &HashMap<usize, Vec<String>>
+) -> Vec<(String, String, Option<BigInt>)>{
+    let mut suggested = Vec::new();
+    // greater values would be satisfied by every element of the field
+    let max_value = verification.field.bits() - 1;
+    let mut position = 0;
+    while position < outputs.len(){
+        let name = array_name(&outputs[position].1).unwrap_or(&outputs[position].1).to_string();
+        let group: Vec<usize> = outputs[position..].iter()
+            .take_while(|(_, output)| array_name(output).unwrap_or(output) == name)
+            .map(|(s, _)| *s)
+            .collect();
+        position += group.len();
+        let declared = signal_tags.get(&group[0]);
+        for tag in semantics{
+            if declared.is_some_and(|tags| tags.contains(&tag.tag)){
+                continue;
+            }
+            let mut candidate = |value: Option<BigInt>|{
+                let mut conditions = group.iter().map(|s| (tag.instantiate)(*s, value.clone()));
+                let first = conditions.next().unwrap();
+                let condition = conditions.fold(first, |all, condition| infix(all, ExpressionInfixOpcode::BoolAnd, condition));
+                verification.prove_tag_candidate(&condition)
+            };
+            if !tag.has_value{
+                if candidate(None){
+                    suggested.push((name.clone(), tag.tag.clone(), None));
+                }
+                continue;
+            }
+            // the search assumes that the condition is weaker for greater values
+            // of the tag, as it happens for tags bounding the signals
+            if !candidate(Some(BigInt::from(max_value))){
+                continue;
+            }
+            let (mut lower, mut upper) = (0, max_value);
+            if candidate(Some(BigInt::from(0))){
+                upper = 0;
+            }
+            while lower + 1 < upper{
+                let middle = (lower + upper) / 2;
+                if candidate(Some(BigInt::from(middle))){
+                    upper = middle;
+                } else{
+                    lower = middle;
+                }
+            }
+            suggested.push((name.clone(), tag.tag.clone(), Some(BigInt::from(upper))));
+        }
+    }
+    suggested
+}
@@ -8,7 +8,7 @@ use std::collections::HashMap;
 
 type C = Constraint<usize>;
 
-fn transform_constraint_to_json(constraint: &C) -> JsonValue {
+pub(crate) fn transform_constraint_to_json(constraint: &C) -> JsonValue {
     JsonValue::Array(vec![
         hashmap_as_json(constraint.a()),
         hashmap_as_json(constraint.b()),
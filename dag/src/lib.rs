@@ -1,29 +1,73 @@
 mod constraint_correctness_analysis;
+mod dot_porting;
 mod json_porting;
 mod map_to_constraint_list;
 mod r1cs_porting;
 mod sym_porting;
 mod witness_producer;
 mod tags_checking;
+mod relational_domain;
+mod certificates;
+mod completeness;
+mod equivalence;
+mod safety_witnesses;
+mod inference;
+mod portfolio;
+mod bit_vector;
+mod assumptions;
 
 use tags_checking::TemplateVerification;
+use completeness::CompletenessVerification;
+use equivalence::{EquivalenceCircuit, EquivalenceVerification};
+use safety_witnesses::SafetyWitnesses;
+pub use tags_checking::Signal2Bounds;
+pub use certificates::{TemplateCertificate, certificates_to_json};
+pub use portfolio::{Encoding, SolverConfiguration, default_portfolio};
+pub use assumptions::{Assumption, AssumptionKind};
 use circom_algebra::num_bigint::BigInt;
 use constraint_list::ConstraintList;
 use constraint_writers::debug_writer::DebugWriter;
 use constraint_writers::ConstraintExporter;
 use program_structure::constants::UsefulConstants;
-use program_structure::error_definition::ReportCollection;
-use std::collections::{HashMap, HashSet, LinkedList};
+use program_structure::error_code::ReportCode;
+use program_structure::error_definition::{Report, ReportCollection};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList};
 use std::time::Instant;
 type Signal = usize;
 type Constraint = circom_algebra::algebra::Constraint<usize>;
 type Substitution = circom_algebra::algebra::Substitution<usize>;
 type Range = std::ops::Range<usize>;
-use program_structure::ast::Expression;
+use program_structure::ast::{Expression, Meta};
+use program_structure::file_definition::{FileID, FileLibrary, FileLocation};
 
 pub type FastSubAccess = HashMap<usize, Substitution>;
 pub type SafetyImplication = (Vec<usize>, Vec<usize>);
 
+// file and span of the statement that generated a constraint
+#[derive(Clone, Default)]
+pub struct ConstraintLocation{
+    pub file_id: Option<FileID>,
+    pub location: FileLocation,
+}
+
+impl ConstraintLocation{
+    pub fn new(meta: &Meta) -> ConstraintLocation{
+        ConstraintLocation{file_id: meta.file_id, location: meta.location.clone()}
+    }
+
+    // returns file:line of the statement
+    pub fn describe(&self, files: &FileLibrary) -> String{
+        let file_id = match self.file_id{
+            Some(file_id) => file_id,
+            None => return "unknown location".to_string(),
+        };
+        match (files.get_name(file_id), files.get_line(self.location.start, file_id)){
+            (Some(name), Some(line)) => format!("{}:{}", name.trim_matches('"'), line),
+            _ => "unknown location".to_string(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct ExecutedImplication{
     pub left: Vec<Expression>,
@@ -31,17 +75,17 @@ pub struct ExecutedImplication{
 }
 
 
-#[derive(PartialEq, Eq, Clone)] 
+#[derive(PartialEq, Eq, Clone, Debug)] 
 pub enum PossibleResult{
-    VERIFIED, UNKNOWN, FAILED, NOSTUDIED, NOTHING, TOO_BIG
+    VERIFIED, UNKNOWN, FAILED, NOSTUDIED, NOTHING, TOO_BIG, SPURIOUS, VACUOUS
 } impl PossibleResult {
     fn finished_verification(&self) -> bool{
         // Depending if fast or not, it includes the childrens when timeout
         let fast_check = true;
         if fast_check{
-            self == &PossibleResult::VERIFIED || self == &PossibleResult::NOSTUDIED || self == &PossibleResult::NOTHING || self == &PossibleResult::UNKNOWN || self == &PossibleResult::TOO_BIG
+            self == &PossibleResult::VERIFIED || self == &PossibleResult::NOSTUDIED || self == &PossibleResult::NOTHING || self == &PossibleResult::UNKNOWN || self == &PossibleResult::TOO_BIG || self == &PossibleResult::SPURIOUS || self == &PossibleResult::VACUOUS
         }else{
-            self == &PossibleResult::VERIFIED || self == &PossibleResult::NOSTUDIED || self == &PossibleResult::NOTHING
+            self == &PossibleResult::VERIFIED || self == &PossibleResult::NOSTUDIED || self == &PossibleResult::NOTHING || self == &PossibleResult::SPURIOUS || self == &PossibleResult::VACUOUS
         }    
     }
     fn result_to_str(&self)-> String{
@@ -50,15 +94,81 @@ pub enum PossibleResult{
             &PossibleResult::FAILED => {format!("FAILED -> FOUND COUNTEREXAMPLE\n")}
             &PossibleResult::UNKNOWN => {format!("UNKNOWN -> VERIFICATION TIMEOUT\n")}
             &PossibleResult::NOTHING => {format!("NOTHING TO VERIFY\n")}
+            &PossibleResult::VACUOUS => {"VERIFIED VACUOUSLY -> THE ASSUMPTIONS OF THE TEMPLATE ARE CONTRADICTORY\n".to_string()}
+            &PossibleResult::SPURIOUS => {"ENCODING ERROR -> THE COUNTEREXAMPLE FOUND BY THE SOLVER DOES NOT SATISFY THE CONSTRAINTS\n".to_string()}
             _ => {format!("VERIFIED\n")}
         }
     }
 }
 
 
+// used to compare constraints independently of the order of their coefficients
+fn constraint_key(c: &Constraint) -> String{
+    let mut sides = Vec::new();
+    for side in [c.a(), c.b(), c.c()]{
+        let mut entries: Vec<String> = side.iter().map(|(s, v)| format!("{}:{}", s, v)).collect();
+        entries.sort();
+        sides.push(entries.join(","));
+    }
+    sides.join("|")
+}
+
+// time in seconds and number of rounds of the verification of a template, where each
+// round adds the constraints of one more level of descendants
+#[derive(Default, Clone, Debug)]
+pub struct VerificationStats {
+    pub time: f64,
+    pub rounds: usize,
+    // configuration of the solver that answered first each query of the portfolio
+    pub portfolio_winners: Vec<String>,
+}
+
+// properties checked in each template and how they are checked
+#[derive(Clone, Copy, Debug)]
+pub struct CheckOptions {
+    pub verification_timeout: u64,
+    pub check_tags: bool,
+    pub check_postconditions: bool,
+    pub check_safety: bool,
+    pub add_tags_info: bool,
+    pub add_postconditions_info: bool,
+    pub apply_deduction_assigned: bool,
+    pub record_certificates: bool,
+    pub unsat_core: bool,
+    // checks weak safety running several configurations of the solver concurrently
+    pub portfolio: bool,
+}
+
+// results of the verification of a template, NOSTUDIED if the property was not checked
+pub struct TemplateResults {
+    pub tags: PossibleResult,
+    pub postconditions: PossibleResult,
+    pub safety: PossibleResult,
+    pub logs: Vec<String>,
+    pub certificate: Option<TemplateCertificate>,
+    pub stats: VerificationStats,
+}
+
+// semantics of a tag loaded with --civer, instantiates the condition of the tag for a
+// signal and a value of the tag (only given if the tag has a value)
+pub struct TagSemantics<'a> {
+    pub tag: String,
+    pub has_value: bool,
+    pub instantiate: Box<dyn Fn(usize, Option<BigInt>) -> Expression + 'a>,
+}
+
+// specifications covering an output of a template
+pub struct OutputCoverage {
+    pub signal: String,
+    pub tags: Vec<String>,
+    pub postconditions: bool,
+    pub tag_specifications: bool,
+}
+
 #[derive(Default)]
 pub struct TreeConstraints {
     constraints: Vec<Constraint>,
+    constraint_locations: Vec<ConstraintLocation>,
     node_id: usize,
     template_name: String,
     pretty_template_name: String,
@@ -70,12 +180,16 @@ pub struct TreeConstraints {
     tags_preconditions: Vec<Expression>,
     tags_postconditions_intermediates: Vec<Expression>,
     tags_postconditions_outputs: Vec<Expression>,
+    hints: Vec<(usize, Option<Expression>)>,
     number_signals: usize,
     number_inputs: usize, 
     number_outputs: usize,
     initial_signal: usize,
     subcomponents: LinkedList<TreeConstraints>,
     is_custom: bool,
+    signal_names: HashMap<usize, String>,
+    // tags of the outputs of the template
+    signal_tags: HashMap<usize, Vec<String>>,
 }
 
 impl TreeConstraints {
@@ -99,6 +213,14 @@ impl TreeConstraints {
         &self.constraints
     }
 
+    pub fn constraint_locations(&self)-> &Vec<ConstraintLocation>{
+        &self.constraint_locations
+    }
+
+    pub fn hints(&self)-> &Vec<(usize, Option<Expression>)>{
+        &self.hints
+    }
+
     pub fn number_signals(&self)-> usize{
         self.number_signals
     }
@@ -119,6 +241,10 @@ impl TreeConstraints {
         self.is_custom
     }
 
+    pub fn signal_names(&self)-> &HashMap<usize, String>{
+        &self.signal_names
+    }
+
     pub fn get_no_postconditions(&self) -> usize{
         self.postconditions_intermediates.len() + self.postconditions_outputs.len() 
     }
@@ -131,6 +257,13 @@ impl TreeConstraints {
         //if self.constraints.len() <= 150{
             for c in &self.constraints{
                 verification.constraints.push(c.clone());
+                verification.r1cs.push(c.clone());
+            }
+            if let Some(certificate) = &mut verification.certificate{
+                certificate.constraints.extend(self.constraints.iter().cloned());
+                for subtree_child in &self.subcomponents{
+                    certificate.subcomponents.push((subtree_child.pretty_template_name.clone(), subtree_child.generate_implications_safety()));
+                }
             }
             for s in (self.number_inputs + self.number_outputs)..self.number_signals{
                 verification.signals.push_back(s+self.initial_signal);
@@ -155,25 +288,15 @@ impl TreeConstraints {
         }*/
     }
 
-    pub fn check_tags(&self, field: &BigInt, verification_timeout: u64, check_tags: bool, check_postconditions: bool, check_safety: bool, 
+    fn build_verification(&self, field: &BigInt, verification_timeout: u64, check_tags: bool, check_postconditions: bool, check_safety: bool, 
         add_tags_info: bool, add_postconditions_info: bool, apply_deduction_assigned: bool
-    ) -> (PossibleResult, PossibleResult, PossibleResult, Vec<String>){
-        
-        
+    ) -> TemplateVerification{
         let mut implications: Vec<ExecutedImplication> = Vec::new();
         let mut tags_implications: Vec<ExecutedImplication> = Vec::new();
 
         let mut implications_safety: Vec<SafetyImplication> = Vec::new();
 
         let mut signals: LinkedList<usize> = LinkedList::new(); 
-
-        let mut logs =  Vec::new();
-        logs.push(format!("Checking template {}\n", self.pretty_template_name));
-
-        if self.is_custom{
-            logs.push(format!("Not checking custom templates\n"));
-            return (PossibleResult::VERIFIED, PossibleResult::VERIFIED, PossibleResult::VERIFIED, logs);
-        }
         
         for s in 0..self.number_signals{
             signals.push_back(s+self.initial_signal);
@@ -191,12 +314,7 @@ impl TreeConstraints {
             implications_safety.push(new_implications_safety)
         }
 
-        let mut postconditions = self.postconditions_outputs.clone();
-        for post in self.postconditions_intermediates.clone(){
-            postconditions.push(post);
-        }
-
-        let mut verification = TemplateVerification::new(
+        TemplateVerification::new(
             &self.template_name, 
             signals, 
             self.initial_signal,
@@ -222,7 +340,154 @@ impl TreeConstraints {
             add_tags_info,
             add_postconditions_info,
             apply_deduction_assigned
+        )
+    }
+
+    // returns the bounds deduced for the signals of the template using its 
+    // preconditions and the constraints of the template and all its descendants
+    pub fn deduce_bounds(&self, field: &BigInt) -> Signal2Bounds{
+        let mut verification = self.build_verification(field, 0, false, false, false, false, false, false);
+        if !self.is_custom{
+            let mut to_add: Vec<&TreeConstraints> = self.subcomponents.iter().collect();
+            while !to_add.is_empty(){
+                let new_components = std::mem::take(&mut to_add);
+                for subtree in new_components{
+                    if let Some(children) = subtree.add_info_component(&mut verification){
+                        to_add.extend(children.iter());
+                    }
+                }
+            }
+        }
+        let deductions = verification.deduce_bounds();
+        deductions.into_iter()
+            .filter(|(s, _)| *s >= self.initial_signal && *s < self.initial_signal + self.number_signals)
+            .collect()
+    }
+
+    // returns the strongest postconditions of the outputs that CIVER can prove among a set of
+    // candidates, written in circom using the names of the signals
+    pub fn infer_postconditions(&self, field: &BigInt, verification_timeout: u64) -> Vec<String>{
+        let mut verification = match self.verification_with_descendants(field, verification_timeout, false, true){
+            Some(verification) => verification,
+            None => return Vec::new(),
+        };
+        let outputs: Vec<(usize, String)> = (0..self.number_outputs).map(|s| self.named_signal(s + self.initial_signal)).collect();
+        let inputs: Vec<(usize, String)> = (0..self.number_inputs).map(|s| self.named_signal(s + self.initial_signal + self.number_outputs)).collect();
+        inference::infer_postconditions(&mut verification, &outputs, &inputs)
+    }
+
+    // reports the outputs that satisfy the semantics of a tag they are not declared with
+    pub fn suggest_tags(&self, field: &BigInt, verification_timeout: u64, semantics: &[TagSemantics], reports: &mut ReportCollection){
+        let mut verification = match self.verification_with_descendants(field, verification_timeout, true, false){
+            Some(verification) => verification,
+            None => return,
+        };
+        let outputs: Vec<(usize, String)> = (0..self.number_outputs).map(|s| self.named_signal(s + self.initial_signal)).collect();
+        for (signal, tag, value) in inference::suggest_tags(&mut verification, &outputs, semantics, &self.signal_tags){
+            let msg = match value{
+                Some(value) => format!("In template \"{}\": the output {} satisfies the semantics of the tag {{{}}} with value {}, consider declaring it with the tag {{{}}} and {}.{} = {}", 
+                    self.pretty_template_name, signal, tag, value, tag, signal, tag, value),
+                None => format!("In template \"{}\": the output {} satisfies the semantics of the tag {{{}}}, consider declaring it with the tag {{{}}}", 
+                    self.pretty_template_name, signal, tag, tag),
+            };
+            reports.push(Report::warning(msg, ReportCode::CIVERTagSuggestion));
+        }
+    }
+
+    // returns, for each output of the template, its tags and whether it appears in the
+    // postconditions and in the specifications of the tags of the template
+    pub fn output_coverage(&self) -> Vec<OutputCoverage>{
+        let mut postconditions = Vec::new();
+        for postcondition in &self.postconditions_outputs{
+            tags_checking::expression_signals(postcondition, &mut postconditions);
+        }
+        let mut tag_specifications = Vec::new();
+        for specification in &self.tags_postconditions_outputs{
+            tags_checking::expression_signals(specification, &mut tag_specifications);
+        }
+        (0..self.number_outputs).map(|s| {
+            let (s, signal) = self.named_signal(s + self.initial_signal);
+            OutputCoverage{
+                signal,
+                tags: self.signal_tags.get(&s).cloned().unwrap_or_default(),
+                postconditions: postconditions.contains(&s),
+                tag_specifications: tag_specifications.contains(&s),
+            }
+        }).collect()
+    }
+
+    // the template is only checked for weak safety when it has no specifications
+    pub fn has_specifications(&self) -> bool{
+        !self.preconditions.is_empty() || !self.preconditions_intermediates.is_empty()
+            || !self.tags_preconditions.is_empty() || self.get_no_postconditions() > 0
+            || self.get_no_tags_postconditions() > 0
+    }
+
+    fn named_signal(&self, s: usize) -> (usize, String){
+        match self.signal_names.get(&s){
+            Some(name) => (s, name.clone()),
+            None => (s, format!("s_{}", s)),
+        }
+    }
+
+    // verification of the template including the constraints of all its descendants, after
+    // applying the deduction rules, returns None for custom templates and too big templates
+    fn verification_with_descendants(&self, field: &BigInt, verification_timeout: u64, check_tags: bool, check_postconditions: bool) -> Option<TemplateVerification>{
+        if self.is_custom{
+            return None;
+        }
+        let mut verification = self.build_verification(field, verification_timeout, check_tags, check_postconditions, false, false, false, false);
+        let mut to_add: Vec<&TreeConstraints> = self.subcomponents.iter().collect();
+        while !to_add.is_empty(){
+            let new_components = std::mem::take(&mut to_add);
+            for subtree in new_components{
+                if let Some(children) = subtree.add_info_component(&mut verification){
+                    to_add.extend(children.iter());
+                }
+            }
+        }
+        if verification.deduce_round_if_possible(){
+            Some(verification)
+        } else{
+            None
+        }
+    }
+
+    pub fn check_tags(&self, field: &BigInt, options: &CheckOptions, files: &FileLibrary, reports: &mut ReportCollection) -> TemplateResults{
+
+        let mut logs =  Vec::new();
+        logs.push(format!("Checking template {}\n", self.pretty_template_name));
+
+        if self.is_custom{
+            logs.push(format!("Not checking custom templates\n"));
+            return TemplateResults{
+                tags: PossibleResult::VERIFIED,
+                postconditions: PossibleResult::VERIFIED,
+                safety: PossibleResult::VERIFIED,
+                logs,
+                certificate: None,
+                stats: VerificationStats::default(),
+            };
+        }
+
+        let (check_tags, check_postconditions, check_safety) = (options.check_tags, options.check_postconditions, options.check_safety);
+        let mut verification = self.build_verification(
+            field,
+            options.verification_timeout,
+            check_tags,
+            check_postconditions,
+            check_safety,
+            options.add_tags_info,
+            options.add_postconditions_info,
+            options.apply_deduction_assigned
         );
+        if options.record_certificates{
+            verification.certificate = Some(self.new_certificate());
+        }
+        verification.unsat_core = options.unsat_core;
+        if options.portfolio{
+            verification.portfolio = default_portfolio();
+        }
         logs.push(format!("Number of signals (i,int,o): {}\n", self.number_signals));
         if check_tags{
             logs.push(format!("Number of tagged signals to check: {}\n", self.tags_postconditions_intermediates.len() + self.tags_postconditions_outputs.len()));
@@ -240,6 +505,8 @@ impl TreeConstraints {
             let duration = inicio.elapsed();    
             logs.push(format!("Verification time per template: {}\n", duration.as_secs_f64()));    
             logs.push(format!("     NUMBER OF ROUNDS: 0\n\n"));
+            let stats = VerificationStats{ time: duration.as_secs_f64(), rounds: 0, portfolio_winners: verification.portfolio_winners.clone() };
+            self.check_vacuity(&verification, [(check_tags, &mut result_tags), (check_postconditions, &mut result_postconditions), (check_safety, &mut result_safety)], &mut logs, reports);
             logs.push(format!("******** VERIFICATION RESULTS ********\n"));
             if check_tags{
                 logs.push(format!("-----> TAGS CHECKING: "));
@@ -254,7 +521,14 @@ impl TreeConstraints {
                 logs.push(result_safety.result_to_str());
             }
             logs.push(format!("\n\n"));
-            (result_tags, result_postconditions, result_safety, logs)
+            if options.unsat_core{
+                logs.append(&mut self.unsat_core_logs(&verification, files));
+            }
+            reports.append(&mut self.counterexample_reports(&verification));
+            let certificate = TreeConstraints::complete_certificate(&mut verification, 
+                [("tags", check_tags, &result_tags), ("postconditions", check_postconditions, &result_postconditions), ("safety", check_safety, &result_safety)]
+            );
+            TemplateResults{ tags: result_tags, postconditions: result_postconditions, safety: result_safety, logs, certificate, stats }
         } else if !self.subcomponents.is_empty(){
             let mut to_check_next = Vec::new();
             let mut n_rounds = 1;
@@ -312,6 +586,8 @@ impl TreeConstraints {
             let duration = inicio.elapsed();    
             logs.push(format!("Verification time per template: {}\n", duration.as_secs_f64()));    
             logs.push(format!("     NUMBER OF ROUNDS: {}\n\n ", n_rounds));
+            let stats = VerificationStats{ time: duration.as_secs_f64(), rounds: n_rounds, portfolio_winners: verification.portfolio_winners.clone() };
+            self.check_vacuity(&verification, [(check_tags, &mut result_tags), (check_postconditions, &mut result_postconditions), (check_safety, &mut result_safety)], &mut logs, reports);
             logs.push(format!("******** VERIFICATION RESULTS ********\n"));
             if check_tags{
                 logs.push(format!("-----> TAGS CHECKING: "));
@@ -326,11 +602,20 @@ impl TreeConstraints {
                 logs.push(result_safety.result_to_str());
             }
             logs.push(format!("\n\n"));
-            (result_tags, result_postconditions, result_safety, logs)
+            if options.unsat_core{
+                logs.append(&mut self.unsat_core_logs(&verification, files));
+            }
+            reports.append(&mut self.counterexample_reports(&verification));
+            let certificate = TreeConstraints::complete_certificate(&mut verification, 
+                [("tags", check_tags, &result_tags), ("postconditions", check_postconditions, &result_postconditions), ("safety", check_safety, &result_safety)]
+            );
+            TemplateResults{ tags: result_tags, postconditions: result_postconditions, safety: result_safety, logs, certificate, stats }
         } else{
             let duration = inicio.elapsed();  
             logs.push(format!("Verification time per template: {}\n", duration.as_secs_f64()));    
             logs.push(format!("     NUMBER OF ROUNDS: 0  \n\n"));
+            let stats = VerificationStats{ time: duration.as_secs_f64(), rounds: 0, portfolio_winners: verification.portfolio_winners.clone() };
+            self.check_vacuity(&verification, [(check_tags, &mut result_tags), (check_postconditions, &mut result_postconditions), (check_safety, &mut result_safety)], &mut logs, reports);
             logs.push(format!("******** VERIFICATION RESULTS ********\n"));
             if check_tags{
                 logs.push(format!("-----> TAGS CHECKING: "));
@@ -345,10 +630,354 @@ impl TreeConstraints {
                 logs.push(result_safety.result_to_str());
             }
             logs.push(format!("\n\n"));
-            (result_tags, result_postconditions, result_safety, logs)
+            if options.unsat_core{
+                logs.append(&mut self.unsat_core_logs(&verification, files));
+            }
+            reports.append(&mut self.counterexample_reports(&verification));
+            let certificate = TreeConstraints::complete_certificate(&mut verification, 
+                [("tags", check_tags, &result_tags), ("postconditions", check_postconditions, &result_postconditions), ("safety", check_safety, &result_safety)]
+            );
+            TemplateResults{ tags: result_tags, postconditions: result_postconditions, safety: result_safety, logs, certificate, stats }
         }
     }
 
+    // checks that the values computed by the witness generator of the template satisfy
+    // its constraints, assuming the preconditions and the contracts of the subcomponents
+    pub fn check_completeness(&self, field: &BigInt, verification_timeout: u64, files: &FileLibrary, reports: &mut ReportCollection
+    ) -> (PossibleResult, Vec<String>){
+        let mut logs = Vec::new();
+        logs.push(format!("Checking completeness of template {}\n", self.pretty_template_name));
+        if self.is_custom{
+            logs.push("Not checking custom templates\n\n".to_string());
+            return (PossibleResult::NOTHING, logs);
+        }
+
+        let mut signals: Vec<usize> = (0..self.number_signals).map(|s| s + self.initial_signal).collect();
+        let mut implications = Vec::new();
+        for subtree in &self.subcomponents{
+            signals.extend(subtree.generate_io_signals());
+            implications.extend(subtree.generate_implications());
+            implications.extend(subtree.generate_tags_implications());
+        }
+        let mut preconditions = self.preconditions.clone();
+        preconditions.extend(self.tags_preconditions.iter().cloned());
+        preconditions.extend(self.facts.iter().cloned());
+        let mut verification = CompletenessVerification{
+            signals,
+            inputs: (0..self.number_inputs).map(|s| s + self.initial_signal + self.number_outputs).collect(),
+            constraints: self.constraints.clone(),
+            hints: self.hints.clone(),
+            preconditions,
+            implications,
+            field: field.clone(),
+            verification_timeout,
+            violated_constraint: None,
+            failed_hint: None,
+        };
+        let result = verification.check(&mut logs);
+
+        let signal_name = |s: &usize| match self.signal_names.get(s){
+            Some(name) => name.clone(),
+            None => format!("s_{}", s),
+        };
+        if let Some(s) = verification.failed_hint{
+            let msg = format!("In template \"{}\": the witness generator cannot compute the value of {} for some inputs", self.pretty_template_name, signal_name(&s));
+            reports.push(Report::warning(msg, ReportCode::CIVERIncompleteWitness));
+        }
+        if let Some(i) = verification.violated_constraint{
+            let location = self.constraint_locations.get(i).cloned().unwrap_or_default();
+            logs.push(format!("       {}: [{}]\n", location.describe(files), i));
+            let msg = format!("In template \"{}\": the values computed by the witness generator do not satisfy a constraint for some inputs", self.pretty_template_name);
+            let mut report = Report::warning(msg, ReportCode::CIVERIncompleteWitness);
+            match location.file_id{
+                Some(file_id) => report.add_primary(location.location.start..location.location.end, file_id, "Constraint not satisfied by the witness".to_string()),
+                None => report.add_note("The location of the constraint is unknown".to_string()),
+            };
+            reports.push(report);
+        }
+        logs.push("-----> WITNESS COMPLETENESS: ".to_string());
+        logs.push(result.result_to_str());
+        logs.push(format!("\n\n"));
+        (result, logs)
+    }
+
+    // checks that the constraints of the template and of all its descendants admit a witness
+    // for every input satisfying its preconditions. The contracts of the subcomponents are not
+    // used since they over-approximate them: a witness satisfying the contracts may not
+    // satisfy the constraints of the subcomponents
+    pub fn check_satisfiability(&self, field: &BigInt, verification_timeout: u64, files: &FileLibrary, reports: &mut ReportCollection
+    ) -> (PossibleResult, Vec<String>){
+        let mut logs = Vec::new();
+        logs.push(format!("Checking satisfiability of template {}\n", self.pretty_template_name));
+        if self.is_custom{
+            logs.push("Not checking custom templates\n\n".to_string());
+            return (PossibleResult::NOTHING, logs);
+        }
+
+        let mut verification = self.build_verification(field, verification_timeout, true, true, false, false, false, false);
+        let mut to_add: Vec<&TreeConstraints> = self.subcomponents.iter().collect();
+        while !to_add.is_empty(){
+            let new_components = std::mem::take(&mut to_add);
+            for subtree in new_components{
+                if let Some(children) = subtree.add_info_component(&mut verification){
+                    to_add.extend(children.iter());
+                }
+            }
+        }
+        verification.implications.clear();
+        verification.tags_implications.clear();
+        let mut result = verification.try_prove_satisfiability(&mut logs);
+        if result == PossibleResult::VERIFIED && self.has_custom_descendants(){
+            // the outputs of the custom templates are not restricted by any constraint
+            logs.push("### UNKNOWN: THE TEMPLATE USES CUSTOM TEMPLATES, WHOSE CONSTRAINTS ARE NOT KNOWN\n".to_string());
+            result = PossibleResult::UNKNOWN;
+        }
+        if result == PossibleResult::UNKNOWN && !self.hints.is_empty() && self.subcomponents.is_empty(){
+            // a complete witness generator computes a witness for every valid input, the
+            // completeness of the templates with subcomponents assumes their contracts
+            logs.push("### Trying to prove satisfiability using the witness generator\n".to_string());
+            let (completeness, mut completeness_logs) = self.check_completeness(field, verification_timeout, files, &mut Vec::new());
+            if completeness == PossibleResult::VERIFIED{
+                logs.append(&mut completeness_logs);
+                result = PossibleResult::VERIFIED;
+            }
+        }
+        if result == PossibleResult::FAILED{
+            let msg = format!("In template \"{}\": the constraints do not admit any witness for some inputs satisfying the preconditions", self.pretty_template_name);
+            let mut report = Report::warning(msg, ReportCode::CIVERUnsatisfiableConstraints);
+            report.add_note("The values of the inputs are included in the .civer file".to_string());
+            reports.push(report);
+        }
+        logs.push("-----> SATISFIABILITY: ".to_string());
+        logs.push(result.result_to_str());
+        logs.push(format!("\n\n"));
+        (result, logs)
+    }
+
+    fn has_custom_descendants(&self) -> bool{
+        self.subcomponents.iter().any(|subtree| subtree.is_custom || subtree.has_custom_descendants())
+    }
+
+    // checks that the circuits whose main components are self and other produce the same
+    // outputs for every input satisfying the preconditions of both main components
+    pub fn check_equivalence(&self, other: &TreeConstraints, field: &BigInt, verification_timeout: u64) -> (PossibleResult, Vec<String>){
+        let mut logs = Vec::new();
+        logs.push(format!("Checking equivalence of {} and {}\n", self.pretty_template_name, other.pretty_template_name));
+        let verification = EquivalenceVerification{
+            left: self.equivalence_circuit(),
+            right: other.equivalence_circuit(),
+            field: field.clone(),
+            verification_timeout,
+        };
+        let result = verification.check(&mut logs);
+        logs.push("-----> EQUIVALENCE: ".to_string());
+        logs.push(result.result_to_str());
+        logs.push(format!("\n\n"));
+        (result, logs)
+    }
+
+    fn equivalence_circuit(&self) -> EquivalenceCircuit<'_>{
+        let mut constraints = Vec::new();
+        let mut number_signals = 1;
+        self.collect_constraints(&mut constraints, &mut number_signals);
+        let (inputs, outputs) = self.generate_implications_safety();
+        let mut assumptions = self.preconditions.clone();
+        assumptions.extend(self.tags_preconditions.iter().cloned());
+        assumptions.extend(self.facts.iter().cloned());
+        EquivalenceCircuit{
+            constraints,
+            number_signals,
+            inputs,
+            outputs,
+            assumptions,
+            signal_names: &self.signal_names,
+        }
+    }
+
+    // two assignments of all the signals of the circuit, self being the main component,
+    // that share the inputs and show that the given template does not satisfy weak safety
+    pub fn safety_witnesses(&self, template: &TreeConstraints, field: &BigInt, verification_timeout: u64) -> Option<(Vec<BigInt>, Vec<BigInt>)>{
+        let mut constraints = Vec::new();
+        let mut number_signals = 1;
+        self.collect_constraints(&mut constraints, &mut number_signals);
+        let (main_inputs, _) = self.generate_implications_safety();
+        let (template_inputs, template_outputs) = template.generate_implications_safety();
+        let witnesses = SafetyWitnesses{
+            constraints,
+            number_signals,
+            main_inputs,
+            template_inputs,
+            template_outputs,
+            field: field.clone(),
+            verification_timeout,
+        };
+        witnesses.compute()
+    }
+
+    fn collect_constraints<'a>(&'a self, constraints: &mut Vec<&'a Constraint>, number_signals: &mut usize){
+        constraints.extend(self.constraints.iter());
+        *number_signals = std::cmp::max(*number_signals, self.initial_signal + self.number_signals);
+        for subtree in &self.subcomponents{
+            subtree.collect_constraints(constraints, number_signals);
+        }
+    }
+
+    // maps the unsat cores of the SMT proofs back to the constraints of the template
+    fn unsat_core_logs(&self, verification: &TemplateVerification, files: &FileLibrary) -> Vec<String>{
+        let mut logs = Vec::new();
+        if verification.unsat_cores.is_empty(){
+            return logs;
+        }
+        let own_constraints: HashMap<String, usize> = self.constraints.iter().enumerate()
+            .map(|(i, c)| (constraint_key(c), i))
+            .collect();
+        let remaining: HashSet<String> = verification.constraints.iter().map(constraint_key).collect();
+        let mut used = HashSet::new();
+
+        logs.push("******** UNSAT CORES ********\n".to_string());
+        for proof in &verification.unsat_cores{
+            let mut indices = Vec::new();
+            let mut children_constraints = 0;
+            for c in &proof.core_constraints{
+                match own_constraints.get(&constraint_key(c)){
+                    Some(i) => indices.push(*i),
+                    None => children_constraints += 1,
+                }
+            }
+            indices.sort();
+            indices.dedup();
+            logs.push(format!("-----> {}: {} of {} constraints of the template needed: {:?}\n", 
+                proof.property.to_uppercase(), indices.len(), self.constraints.len(), indices));
+            logs.append(&mut self.location_logs(&indices, files));
+            used.extend(indices);
+            if children_constraints > 0{
+                logs.push(format!("       Constraints of the subcomponents needed: {}\n", children_constraints));
+            }
+            for assumption in &proof.core_assumptions{
+                logs.push(format!("       Assumption needed: {}\n", assumption));
+            }
+            for contract in &proof.core_contracts{
+                logs.push(format!("       Contract of a subcomponent needed: {}\n", contract));
+            }
+        }
+        // the constraints completely studied by the deduction rules are not sent to the solver
+        let mut solved = Vec::new();
+        let mut not_needed = Vec::new();
+        for (i, c) in self.constraints.iter().enumerate(){
+            if !remaining.contains(&constraint_key(c)){
+                solved.push(i);
+            } else if !used.contains(&i){
+                not_needed.push(i);
+            }
+        }
+        if !solved.is_empty(){
+            logs.push(format!("Constraints of the template solved by the deduction rules: {:?}\n", solved));
+            logs.append(&mut self.location_logs(&solved, files));
+        }
+        // they may still be needed to deduce the bounds used by the proofs
+        logs.push(format!("Constraints of the template not needed by any SMT proof: {:?}\n", not_needed));
+        logs.append(&mut self.location_logs(&not_needed, files));
+        logs.push("\n".to_string());
+        logs
+    }
+
+    // groups the given constraints of the template by the statement that generated them
+    fn location_logs(&self, indices: &[usize], files: &FileLibrary) -> Vec<String>{
+        let mut statements: BTreeMap<(Option<FileID>, usize), (String, Vec<usize>)> = BTreeMap::new();
+        for i in indices{
+            if let Some(location) = self.constraint_locations.get(*i){
+                statements.entry((location.file_id, location.location.start))
+                    .or_insert_with(|| (location.describe(files), Vec::new()))
+                    .1.push(*i);
+            }
+        }
+        statements.into_values()
+            .map(|(location, constraints)| format!("       {}: {:?}\n", location, constraints))
+            .collect()
+    }
+
+    // the properties proved under contradictory assumptions are marked as vacuous
+    fn check_vacuity(&self, verification: &TemplateVerification, results: [(bool, &mut PossibleResult); 3], logs: &mut Vec<String>, reports: &mut ReportCollection){
+        let proved = |(checked, result): &(bool, &mut PossibleResult)| *checked && **result == PossibleResult::VERIFIED;
+        if !results.iter().any(proved) || !verification.contradictory_assumptions(logs){
+            return;
+        }
+        for entry in results{
+            if proved(&entry){
+                *entry.1 = PossibleResult::VACUOUS;
+            }
+        }
+        let msg = format!("In template \"{}\": the preconditions, facts and contracts of the subcomponents are contradictory, its proofs hold vacuously", self.pretty_template_name);
+        reports.push(Report::warning(msg, ReportCode::CIVERVacuousProof));
+    }
+
+    // points at the constraints of the template involving the signals of the counterexamples
+    fn counterexample_reports(&self, verification: &TemplateVerification) -> ReportCollection{
+        let mut reports = Vec::new();
+        for property in ["tags", "postconditions", "safety"]{
+            let signals = match verification.counterexamples.get(property){
+                Some(signals) if !signals.is_empty() => signals,
+                _ => continue,
+            };
+            let signal_name = |s: &usize| match self.signal_names.get(s){
+                Some(name) => name.clone(),
+                None => format!("s_{}", s),
+            };
+            let names: Vec<String> = signals.iter().map(signal_name).collect();
+            let msg = if property == "safety"{
+                format!("In template \"{}\": found a counterexample of weak safety, the outputs {} can take different values", self.pretty_template_name, names.join(", "))
+            } else{
+                format!("In template \"{}\": found a counterexample of the {} involving the signals {}", self.pretty_template_name, property, names.join(", "))
+            };
+            let mut report = Report::warning(msg, ReportCode::CIVERCounterexample);
+
+            let mut statements: BTreeMap<(FileID, usize, usize), BTreeSet<String>> = BTreeMap::new();
+            for (c, location) in self.constraints.iter().zip(self.constraint_locations.iter()){
+                if let Some(file_id) = location.file_id{
+                    for s in c.take_cloned_signals(){
+                        if signals.contains(&s){
+                            statements.entry((file_id, location.location.start, location.location.end))
+                                .or_default()
+                                .insert(signal_name(&s));
+                        }
+                    }
+                }
+            }
+            if statements.is_empty(){
+                report.add_note("None of the constraints of the template involve these signals".to_string());
+            }
+            for ((file_id, start, end), involved) in statements{
+                let involved: Vec<String> = involved.into_iter().collect();
+                report.add_primary(start..end, file_id, format!("Constraint involving {}", involved.join(", ")));
+            }
+            reports.push(report);
+        }
+        reports
+    }
+
+    fn new_certificate(&self) -> TemplateCertificate{
+        let outputs = (0..self.number_outputs).map(|s| s + self.initial_signal).collect();
+        let inputs = (0..self.number_inputs).map(|s| s + self.initial_signal + self.number_outputs).collect();
+        let mut certificate = TemplateCertificate::new(&self.pretty_template_name, inputs, outputs);
+        certificate.signal_names = self.signal_names.clone();
+        certificate.constraints = self.constraints.clone();
+        for subtree in &self.subcomponents{
+            certificate.subcomponents.push((subtree.pretty_template_name.clone(), subtree.generate_implications_safety()));
+        }
+        certificate
+    }
+
+    // adds the final results of the checked properties to the certificate of the verification
+    fn complete_certificate(verification: &mut TemplateVerification, results: [(&str, bool, &PossibleResult); 3]) -> Option<TemplateCertificate>{
+        let mut certificate = verification.certificate.take()?;
+        for (property, checked, result) in results{
+            if checked{
+                certificate.add_result(property, result.result_to_str().trim_end().to_string());
+            }
+        }
+        Some(certificate)
+    }
+
     fn generate_info_subtree(&self)-> (LinkedList<usize>, Option<ExecutedImplication>, Option<ExecutedImplication>, SafetyImplication){
         (   self.generate_io_signals(),
             self.generate_implications(), 
@@ -426,6 +1055,7 @@ pub struct Tree<'a> {
     pub forbidden: HashSet<usize>,
     pub id_to_name: HashMap<usize, String>,
     pub constraints: Vec<Constraint>,
+    pub constraint_locations: Vec<ConstraintLocation>,
     pub preconditions: Vec<Expression>,
     pub preconditions_intermediates: Vec<Expression>,
     pub postconditions_outputs: Vec<Expression>,
@@ -434,6 +1064,7 @@ pub struct Tree<'a> {
     pub tags_preconditions: Vec<Expression>,
     pub tags_postconditions_outputs: Vec<Expression>,
     pub tags_postconditions_intermediates: Vec<Expression>,
+    pub hints: Vec<(usize, Option<Expression>)>,
 }
 
 impl<'a> Tree<'a> {
@@ -445,6 +1076,7 @@ impl<'a> Tree<'a> {
         let offset = dag.get_entry().unwrap().in_number;
         let path = dag.get_entry().unwrap().label.clone();
         let constraints = root.constraints.clone();
+        let constraint_locations = root.constraint_locations.clone();
         let preconditions = root.preconditions.clone();
         let preconditions_intermediates = root.preconditions_intermediates.clone();
         let postconditions_intermediates = root.postconditions_intermediates.clone();
@@ -453,6 +1085,7 @@ impl<'a> Tree<'a> {
         let tags_preconditions = root.tags_preconditions.clone();
         let tags_postconditions_intermediates = root.tags_postconditions_intermediates.clone();
         let tags_postconditions_outputs = root.tags_postconditions_outputs.clone();
+        let hints = root.hints.clone();
         let mut id_to_name = HashMap::new();
         let mut signals: Vec<_> = Vec::new();
         let forbidden: HashSet<_> =
@@ -464,10 +1097,11 @@ impl<'a> Tree<'a> {
             }
         }
         signals.sort();
-        Tree { field, dag, path, offset, node_id, signals, forbidden, id_to_name, constraints,
+        Tree { field, dag, path, offset, node_id, signals, forbidden, id_to_name, constraints, constraint_locations,
             preconditions, preconditions_intermediates, postconditions_intermediates, postconditions_outputs,
             facts,
             tags_preconditions, tags_postconditions_intermediates, tags_postconditions_outputs,
+            hints,
          }   
         }
 
@@ -488,12 +1122,13 @@ impl<'a> Tree<'a> {
             }
         }
         signals.sort();
-        let constraints: Vec<_> = node
+        let (constraints, constraint_locations): (Vec<_>, Vec<_>) = node
             .constraints
             .iter()
-            .filter(|c| !c.is_empty())
-            .map(|c| Constraint::apply_offset(c, offset))
-            .collect();
+            .zip(node.constraint_locations.iter())
+            .filter(|(c, _)| !c.is_empty())
+            .map(|(c, l)| (Constraint::apply_offset(c, offset), l.clone()))
+            .unzip();
         let preconditions: Vec<_> = node
             .preconditions
             .iter()
@@ -534,13 +1169,19 @@ impl<'a> Tree<'a> {
             .iter()
             .map(|c| c.apply_offset(offset))
             .collect();
+        let hints: Vec<_> = node
+            .hints
+            .iter()
+            .map(|(s, hint)| (s + offset, hint.as_ref().map(|h| h.apply_offset(offset))))
+            .collect();
 
         
             
-        Tree { field, dag, path, offset, node_id, signals, forbidden, id_to_name, constraints,
+        Tree { field, dag, path, offset, node_id, signals, forbidden, id_to_name, constraints, constraint_locations,
             preconditions, preconditions_intermediates, postconditions_intermediates, postconditions_outputs,
             facts, 
             tags_preconditions, tags_postconditions_intermediates, tags_postconditions_outputs,
+            hints,
          }
     }
 
@@ -556,11 +1197,12 @@ pub struct Edge {
     in_number: usize,
     out_number: usize,
     in_component_number: usize,
-    out_component_number: usize
+    out_component_number: usize,
+    is_parallel: bool,
 }
 impl Edge {
     fn new_entry(id: usize) -> Edge {
-        Edge { label: "main".to_string(), goes_to: id, in_number: 0, out_number: 0, in_component_number: 0, out_component_number: 0  }
+        Edge { label: "main".to_string(), goes_to: id, in_number: 0, out_number: 0, in_component_number: 0, out_component_number: 0, is_parallel: false }
     }
 
     pub fn get_goes_to(&self) -> usize {
@@ -615,6 +1257,7 @@ pub struct Node {
     forbidden_if_main: HashSet<usize>,
     io_signals: Vec<usize>,
     constraints: Vec<Constraint>,
+    constraint_locations: Vec<ConstraintLocation>,
     underscored_signals: Vec<usize>,
     is_parallel: bool,
     has_parallel_sub_cmp: bool,
@@ -628,6 +1271,8 @@ pub struct Node {
     tags_preconditions: Vec<Expression>,
     tags_postconditions_intermediates: Vec<Expression>,
     tags_postconditions_outputs: Vec<Expression>,
+    hints: Vec<(usize, Option<Expression>)>,
+    signal_tags: HashMap<usize, Vec<String>>,
 }
 
 impl Node {
@@ -692,8 +1337,9 @@ impl Node {
         self.intermediates_length += 1;
     }
 
-    fn add_constraint(&mut self, constraint: Constraint) {
-        self.constraints.push(constraint)
+    fn add_constraint(&mut self, constraint: Constraint, location: ConstraintLocation) {
+        self.constraints.push(constraint);
+        self.constraint_locations.push(location)
     }
 
     fn add_underscored_signal(&mut self, signal: usize) {
@@ -733,6 +1379,16 @@ impl Node {
         self.tags_postconditions_outputs.push(post)
     }
 
+    fn add_signal_tags(&mut self, name: &str, tags: Vec<String>) {
+        if let Some(id) = self.signal_correspondence.get(name) {
+            self.signal_tags.insert(*id, tags);
+        }
+    }
+
+    fn add_hint(&mut self, signal: usize, hint: Option<Expression>) {
+        self.hints.push((signal, hint))
+    }
+
     fn set_number_of_subcomponents_indexes(&mut self, number_scmp: usize) {
         self.number_of_subcomponents_indexes = number_scmp
     }
@@ -853,6 +1509,7 @@ impl DAG {
                 out_number: out_num,
                 in_component_number: in_component_num,
                 out_component_number: out_component_num,
+                is_parallel,
             };
             // add correspondence to current node
             let mut correspondence = std::mem::take(&mut self.nodes[from].signal_correspondence);
@@ -913,9 +1570,21 @@ impl DAG {
         }
     }
 
-    pub fn add_constraint(&mut self, constraint: Constraint) {
+    pub fn add_constraint(&mut self, constraint: Constraint, location: ConstraintLocation) {
+        if let Option::Some(node) = self.get_mut_main() {
+            node.add_constraint(constraint, location);
+        }
+    }
+
+    pub fn add_signal_tags(&mut self, name: &str, tags: Vec<String>) {
         if let Option::Some(node) = self.get_mut_main() {
-            node.add_constraint(constraint);
+            node.add_signal_tags(name, tags);
+        }
+    }
+
+    pub fn add_hint(&mut self, signal: usize, hint: Option<Expression>) {
+        if let Option::Some(node) = self.get_mut_main() {
+            node.add_hint(signal, hint);
         }
     }
 
@@ -1037,6 +1706,10 @@ impl DAG {
         sym_porting::write(self, output_file)
     }
 
+    pub fn generate_dot_output(&self, output_file: &str, signal_ranges: bool) -> Result<(), ()> {
+        dot_porting::write(self, output_file, signal_ranges)
+    }
+
     pub fn generate_json_constraints(&self, debug: &DebugWriter) -> Result<(), ()> {
         json_porting::port_constraints(self, debug)
     }
@@ -41,12 +41,24 @@ fn map_tree_constraints(
     tree_constraints.tags_preconditions = tree.tags_preconditions.clone();
     tree_constraints.tags_postconditions_intermediates = tree.tags_postconditions_intermediates.clone();
     tree_constraints.tags_postconditions_outputs = tree.tags_postconditions_outputs.clone();
+    tree_constraints.hints = tree.hints.clone();
     if tree_constraints.number_signals > 0{
         tree_constraints.initial_signal = tree.signals[0];
     }
 
     tree_constraints.node_id = tree.node_id;
 
+    let node = &tree.dag.nodes[tree.node_id];
+    for (name, id) in node.correspondence() {
+        if node.is_local_signal(*id) {
+            tree_constraints.signal_names.insert(*id + tree.offset, name.clone());
+            if let Some(tags) = node.signal_tags.get(id) {
+                tree_constraints.signal_tags.insert(*id + tree.offset, tags.clone());
+            }
+        }
+    }
+
+    tree_constraints.constraint_locations = tree.constraint_locations.clone();
     for constraint in &tree.constraints {
         tree_constraints.constraints.push(constraint.clone());
         if Constraint::is_constant_equality(constraint) {
@@ -0,0 +1,223 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::time::Duration;
+use z3::{Context, Params, SatResult, Solver};
+
+// encoding of the signals in the weak safety queries, the automatic one uses bit-vectors
+// if the bounds of the signals allow it and integers otherwise
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Automatic,
+    Integer,
+    BitVector,
+}
+
+// configuration of the solver used in one of the queries of the portfolio
+#[derive(Clone, Debug)]
+pub struct SolverConfiguration {
+    pub name: String,
+    pub encoding: Encoding,
+    pub seed: u32,
+    // non-linear arithmetic reasoning of the arithmetic solver
+    pub nla: bool,
+    // adds the axioms of apply_deduction_rule_homologues when checking weak safety
+    pub homologues: bool,
+}
+
+impl Default for SolverConfiguration {
+    fn default() -> Self {
+        SolverConfiguration { name: "default".to_string(), encoding: Encoding::Automatic, seed: 0, nla: true, homologues: true }
+    }
+}
+
+// names of the solver parameters, z3 ignores silently the parameters it does not know
+const SEED_PARAM: &str = "random_seed";
+const NLA_PARAM: &str = "smt.arith.nl";
+
+impl SolverConfiguration {
+    pub fn set_params(&self, ctx: &Context, solver: &Solver) {
+        let mut params = Params::new(ctx);
+        params.set_u32(SEED_PARAM, self.seed);
+        params.set_bool(NLA_PARAM, self.nla);
+        solver.set_params(&params);
+    }
+}
+
+pub fn default_portfolio() -> Vec<SolverConfiguration> {
+    let integer = SolverConfiguration { name: "integer".to_string(), encoding: Encoding::Integer, ..SolverConfiguration::default() };
+    vec![
+        integer.clone(),
+        SolverConfiguration { name: "seed=1".to_string(), seed: 1, ..integer.clone() },
+        SolverConfiguration { name: "seed=2".to_string(), seed: 2, ..integer.clone() },
+        SolverConfiguration { name: "no_nla".to_string(), nla: false, ..integer.clone() },
+        SolverConfiguration { name: "no_homologues".to_string(), homologues: false, ..integer },
+        SolverConfiguration { name: "bit_vector".to_string(), encoding: Encoding::BitVector, ..SolverConfiguration::default() },
+    ]
+}
+
+// runs the query with each configuration in a different thread and returns the first
+// definitive answer together with the position of the configuration that found it, the
+// rest of the queries are interrupted. If no answer is definitive the result of the first
+// configuration is returned
+pub fn solve<T, Q, D>(configurations: &[SolverConfiguration], query: Q, is_definitive: D) -> (T, Option<usize>)
+where
+    T: Send,
+    Q: Fn(&SolverConfiguration, &AtomicBool) -> T + Sync,
+    D: Fn(&T) -> bool,
+{
+    let stop = AtomicBool::new(false);
+    let (sender, receiver) = mpsc::channel();
+    std::thread::scope(|scope| {
+        for (position, configuration) in configurations.iter().enumerate() {
+            let sender = sender.clone();
+            let query = &query;
+            let stop = &stop;
+            scope.spawn(move || {
+                let _ = sender.send((position, query(configuration, stop)));
+            });
+        }
+        drop(sender);
+        let mut results = Vec::new();
+        for (position, result) in receiver.iter() {
+            if is_definitive(&result) {
+                stop.store(true, Ordering::SeqCst);
+                return (result, Some(position));
+            }
+            results.push((position, result));
+        }
+        let (_, result) = results.into_iter().min_by_key(|(position, _)| *position).unwrap();
+        (result, None)
+    })
+}
+
+// checks the solver interrupting it as soon as stop is set by another query of the portfolio
+pub fn check_interruptible(ctx: &Context, stop: Option<&AtomicBool>, check: impl FnOnce() -> SatResult) -> SatResult {
+    let stop = match stop {
+        Some(stop) => stop,
+        None => return check(),
+    };
+    if stop.load(Ordering::SeqCst) {
+        return SatResult::Unknown;
+    }
+    let handle = ctx.handle();
+    let finished = AtomicBool::new(false);
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            // the interruption is repeated since it has no effect if the check did not start
+            while !finished.load(Ordering::SeqCst) {
+                if stop.load(Ordering::SeqCst) {
+                    handle.interrupt();
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        });
+        let result = check();
+        finished.store(true, Ordering::SeqCst);
+        result
+    })
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::ffi::CString;
+    use std::sync::atomic::AtomicUsize;
+    use std::time::Instant;
+    use z3::ast::{Ast, Int};
+    use z3_sys::*;
+
+    // validates the parameter against the descriptions of the parameters of a z3 solver
+    fn is_solver_param(name: &str, flag: bool) -> bool {
+        unsafe {
+            let config = Z3_mk_config();
+            let ctx = Z3_mk_context(config);
+            Z3_set_error_handler(ctx, None);
+            let solver = Z3_mk_solver(ctx);
+            Z3_solver_inc_ref(ctx, solver);
+            let descriptions = Z3_solver_get_param_descrs(ctx, solver);
+            Z3_param_descrs_inc_ref(ctx, descriptions);
+            let params = Z3_mk_params(ctx);
+            Z3_params_inc_ref(ctx, params);
+            let symbol = Z3_mk_string_symbol(ctx, CString::new(name).unwrap().as_ptr());
+            if flag {
+                Z3_params_set_bool(ctx, params, symbol, false);
+            } else {
+                Z3_params_set_uint(ctx, params, symbol, 1);
+            }
+            Z3_params_validate(ctx, params, descriptions);
+            let valid = Z3_get_error_code(ctx) == ErrorCode::OK;
+            Z3_params_dec_ref(ctx, params);
+            Z3_param_descrs_dec_ref(ctx, descriptions);
+            Z3_solver_dec_ref(ctx, solver);
+            Z3_del_context(ctx);
+            Z3_del_config(config);
+            valid
+        }
+    }
+
+    #[test]
+    fn params_are_known_by_z3() {
+        assert!(is_solver_param(SEED_PARAM, false));
+        assert!(is_solver_param(NLA_PARAM, true));
+        assert!(!is_solver_param("smt.arith.unknown", true));
+    }
+
+    #[test]
+    fn solve_returns_the_first_definitive_result() {
+        let interrupted = AtomicUsize::new(0);
+        let (result, winner) = solve(
+            &default_portfolio()[..3],
+            |configuration, stop| {
+                if configuration.seed == 1 {
+                    return Some(configuration.seed);
+                }
+                while !stop.load(Ordering::SeqCst) {
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                interrupted.fetch_add(1, Ordering::SeqCst);
+                None
+            },
+            |result| result.is_some(),
+        );
+        assert_eq!(result, Some(1));
+        assert_eq!(winner, Some(1));
+        assert_eq!(interrupted.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn solve_without_definitive_result() {
+        let (result, winner) = solve(&default_portfolio(), |configuration, _| configuration.seed + 10, |_| false);
+        assert_eq!(result, 10);
+        assert_eq!(winner, None);
+    }
+
+    #[test]
+    fn solve_interrupts_the_running_checks() {
+        let start = Instant::now();
+        let (result, winner) = solve(
+            &default_portfolio()[..2],
+            |configuration, stop| {
+                if configuration.seed == 1 {
+                    std::thread::sleep(Duration::from_millis(100));
+                    return SatResult::Unsat;
+                }
+                // factorization of a product of two primes of 31 bits
+                let ctx = Context::new(&z3::Config::new());
+                let solver = Solver::new(&ctx);
+                let x = Int::new_const(&ctx, "x");
+                let y = Int::new_const(&ctx, "y");
+                let one = Int::from_u64(&ctx, 1);
+                solver.assert(&Int::mul(&ctx, &[&x, &y])._eq(&Int::from_u64(&ctx, 2147483647 * 2147483629)));
+                solver.assert(&x.gt(&one));
+                solver.assert(&y.gt(&one));
+                configuration.set_params(&ctx, &solver);
+                check_interruptible(&ctx, Some(stop), || solver.check())
+            },
+            |result| *result != SatResult::Unknown,
+        );
+        assert_eq!(result, SatResult::Unsat);
+        assert_eq!(winner, Some(1));
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+}
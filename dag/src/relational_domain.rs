@@ -0,0 +1,521 @@
+use std::collections::{HashMap, HashSet};
+use num_bigint_dig::BigInt;
+use circom_algebra::algebra::{Constraint, ExecutedInequation};
+use crate::tags_checking::{Signal2Bounds, update_bounds_signal, deduction_rule_apply_bounds_constraint};
+use crate::certificates::DeterminismStep;
+
+// Relational facts that complement the interval of each signal:
+//   - bit decompositions: signal = offset + sum 2^i * b_i, with b_i binary
+//   - one-hot selectors: sum s_i = 1, with s_i binary
+pub struct BitDecomposition {
+    pub signal: usize,
+    pub offset: BigInt,
+    pub bits: Vec<(usize, BigInt)>, // (bit, weight)
+    pub constraint: Constraint<usize>,
+}
+
+pub struct OneHotSelector {
+    pub selectors: HashSet<usize>,
+    pub constraint: Constraint<usize>,
+}
+
+#[derive(Default)]
+pub struct RelationalFacts {
+    pub bit_decompositions: Vec<BitDecomposition>,
+    pub one_hot_selectors: Vec<OneHotSelector>,
+    // signals of the constraints that were binary when the facts were computed
+    binary_signals: HashSet<usize>,
+}
+
+impl RelationalFacts {
+    // the facts only depend on which signals are binary, so they have to be
+    // recomputed when the bounds of a signal make it binary
+    pub fn compute_facts(constraints: &[Constraint<usize>], deductions: &Signal2Bounds, field: &BigInt) -> RelationalFacts{
+        let mut facts = RelationalFacts::default();
+        for c in constraints{
+            facts.bit_decompositions.append(&mut recognise_bit_decompositions(c, deductions, field));
+            if let Some(selectors) = recognise_one_hot_selector(c, deductions, field){
+                facts.one_hot_selectors.push(OneHotSelector{selectors, constraint: c.clone()});
+            }
+            for s in c.take_signals(){
+                if is_binary(deductions, *s){
+                    facts.binary_signals.insert(*s);
+                }
+            }
+        }
+        facts
+    }
+
+    // true if some of the updated signals became binary after computing the facts
+    pub fn is_outdated(&self, updated_signals: &[usize], deductions: &Signal2Bounds) -> bool{
+        updated_signals.iter().any(|s| !self.binary_signals.contains(s) && is_binary(deductions, *s))
+    }
+}
+
+fn non_zero_coefficients(le: &HashMap<usize, BigInt>) -> HashMap<usize, BigInt>{
+    le.iter()
+        .filter(|(_, coef)| **coef != BigInt::from(0))
+        .map(|(s, coef)| (*s, coef.clone()))
+        .collect()
+}
+
+// A * B = 0 when A or B is empty
+fn is_linear_constraint(c: &Constraint<usize>) -> bool{
+    non_zero_coefficients(c.a()).is_empty() || non_zero_coefficients(c.b()).is_empty()
+}
+
+fn is_binary(deductions: &Signal2Bounds, signal: usize) -> bool{
+    match deductions.get(&signal){
+        Some(bounds) => bounds.min >= BigInt::from(0) && bounds.max <= BigInt::from(1),
+        None => false
+    }
+}
+
+fn is_power_of_two(value: &BigInt) -> bool{
+    let zero = BigInt::from(0);
+    value > &zero && (value & (value - BigInt::from(1))) == zero
+}
+
+fn is_unit(coef: &BigInt, field: &BigInt) -> bool{
+    coef == &BigInt::from(1) || *coef == field - BigInt::from(1)
+}
+
+// Given the linear expression c = 0 and a signal x with coefficient 1 or -1,
+// returns the expression x = k + sum w_i * s_i as (k, [(s_i, w_i)])
+fn solve_linear_for_signal(c: &HashMap<usize, BigInt>, signal: usize, field: &BigInt) -> (BigInt, Vec<(usize, BigInt)>){
+    let positive = c.get(&signal).unwrap() == &BigInt::from(1);
+    let mut offset = BigInt::from(0);
+    let mut rest = Vec::new();
+    for (s, coef) in c{
+        if *s == signal{
+            continue;
+        }
+        let value = if positive{
+            (field - coef) % field
+        } else{
+            coef % field
+        };
+        if *s == 0{
+            offset = value;
+        } else{
+            rest.push((*s, value));
+        }
+    }
+    (offset, rest)
+}
+
+// x = k + sum 2^i * b_i with distinct powers and the sum not overflowing the field
+fn recognise_bit_decompositions(constraint: &Constraint<usize>, deductions: &Signal2Bounds, field: &BigInt) -> Vec<BitDecomposition>{
+    let mut decompositions = Vec::new();
+    let c = non_zero_coefficients(constraint.c());
+    if !is_linear_constraint(constraint){
+        return decompositions;
+    }
+    for (signal, coef) in &c{
+        if *signal == 0 || !is_unit(coef, field){
+            continue;
+        }
+        let (offset, bits) = solve_linear_for_signal(&c, *signal, field);
+        if bits.len() < 2{
+            continue;
+        }
+        let mut weights = HashSet::new();
+        let mut total = offset.clone();
+        let mut valid = true;
+        for (bit, weight) in &bits{
+            if !is_binary(deductions, *bit) || !is_power_of_two(weight) || !weights.insert(weight.clone()){
+                valid = false;
+                break;
+            }
+            total += weight;
+        }
+        if valid && &total < field{
+            decompositions.push(BitDecomposition{signal: *signal, offset, bits, constraint: constraint.clone()});
+        }
+    }
+    decompositions
+}
+
+// c * (sum s_i - 1) = 0 with s_i binary
+fn recognise_one_hot_selector(constraint: &Constraint<usize>, deductions: &Signal2Bounds, field: &BigInt) -> Option<HashSet<usize>>{
+    let c = non_zero_coefficients(constraint.c());
+    if !is_linear_constraint(constraint){
+        return None;
+    }
+    let constant = c.get(&0)?;
+    let coef_selectors = (field - constant) % field;
+    let mut selectors = HashSet::new();
+    for (signal, coef) in &c{
+        if *signal == 0{
+            continue;
+        }
+        if coef != &coef_selectors || !is_binary(deductions, *signal){
+            return None;
+        }
+        selectors.insert(*signal);
+    }
+    if selectors.len() >= 2{
+        Some(selectors)
+    } else{
+        None
+    }
+}
+
+// if x = k + sum w_i * b_i is a bit decomposition and max(x) - k < w_i then b_i = 0
+pub fn deduction_rule_bit_decomposition(
+    deductions: &mut Signal2Bounds,
+    decomposition: &BitDecomposition,
+    field: &BigInt,
+) -> Vec<usize>{
+    let mut updated_signals = Vec::new();
+    let max_signal = match deductions.get(&decomposition.signal){
+        Some(bounds) if bounds.min >= BigInt::from(0) => bounds.max.clone(),
+        _ => return updated_signals,
+    };
+    let max_sum = &max_signal - &decomposition.offset;
+    for (bit, weight) in &decomposition.bits{
+        if weight > &max_sum && update_bounds_signal(deductions, *bit, BigInt::from(0), BigInt::from(0), field){
+            updated_signals.push(*bit);
+        }
+    }
+    updated_signals
+}
+
+// x = k + sum w_i * s_i where the s_i belong to a one-hot selector ==> x in {k} U {k + w_i}
+pub fn deduction_rule_one_hot_selector(
+    deductions: &mut Signal2Bounds,
+    constraint: &Constraint<usize>,
+    one_hot_selectors: &[OneHotSelector],
+    field: &BigInt,
+) -> Vec<usize>{
+    let mut updated_signals = Vec::new();
+    let c = non_zero_coefficients(constraint.c());
+    if !is_linear_constraint(constraint){
+        return updated_signals;
+    }
+    for (signal, coef) in &c{
+        if *signal == 0 || !is_unit(coef, field){
+            continue;
+        }
+        let (offset, selected) = solve_linear_for_signal(&c, *signal, field);
+        if selected.is_empty(){
+            continue;
+        }
+        for group in one_hot_selectors{
+            let group = &group.selectors;
+            if !selected.iter().all(|(s, _)| group.contains(s)){
+                continue;
+            }
+            let mut values: Vec<BigInt> = selected.iter()
+                .map(|(_, weight)| (&offset + weight) % field)
+                .collect();
+            if selected.len() < group.len(){
+                values.push(offset.clone());
+            }
+            let min = values.iter().min().unwrap().clone();
+            let max = values.iter().max().unwrap().clone();
+            if update_bounds_signal(deductions, *signal, min, max, field){
+                updated_signals.push(*signal);
+            }
+            break;
+        }
+    }
+    updated_signals
+}
+
+// (a * s) * B - C = 0 with s binary ==> C = 0 \/ a * B - C = 0,
+// the bounds of the signals of C are the union of the bounds of both cases
+pub fn deduction_rule_binary_selector(
+    deductions: &mut Signal2Bounds,
+    constraint: &Constraint<usize>,
+    field: &BigInt,
+    verbose: bool,
+) -> Vec<usize>{
+    let mut updated_signals = Vec::new();
+    let a = non_zero_coefficients(constraint.a());
+    let b = non_zero_coefficients(constraint.b());
+    let c = non_zero_coefficients(constraint.c());
+
+    let (selector, coef_selector, other) = if let Some((s, coef)) = get_binary_selector(&a, deductions){
+        (s, coef, b)
+    } else if let Some((s, coef)) = get_binary_selector(&b, deductions){
+        (s, coef, a)
+    } else{
+        return updated_signals;
+    };
+    if other.is_empty() || c.is_empty(){
+        return updated_signals;
+    }
+
+    // case selector = 0: 0 - C = 0
+    let case_zero = Constraint::new(HashMap::new(), HashMap::new(), c.clone());
+    // case selector = 1: coef * B - C = 0
+    let mut c_one = c.clone();
+    for (s, coef) in &other{
+        let value = (c_one.get(s).cloned().unwrap_or(BigInt::from(0)) + field - (&coef_selector * coef) % field) % field;
+        c_one.insert(*s, value);
+    }
+    let case_one = Constraint::new(HashMap::new(), HashMap::new(), non_zero_coefficients(&c_one));
+
+    let bounds_zero = bounds_under_case(deductions, &case_zero, selector, BigInt::from(0), field, verbose);
+    let bounds_one = bounds_under_case(deductions, &case_one, selector, BigInt::from(1), field, verbose);
+
+    for signal in c.keys(){
+        if *signal == 0 || *signal == selector{
+            continue;
+        }
+        if let (Some(zero), Some(one)) = (bounds_zero.get(signal), bounds_one.get(signal)){
+            if zero.min >= BigInt::from(0) && one.min >= BigInt::from(0){
+                let min = std::cmp::min(&zero.min, &one.min).clone();
+                let max = std::cmp::max(&zero.max, &one.max).clone();
+                if update_bounds_signal(deductions, *signal, min, max, field){
+                    updated_signals.push(*signal);
+                }
+            }
+        }
+    }
+    updated_signals
+}
+
+fn get_binary_selector(le: &HashMap<usize, BigInt>, deductions: &Signal2Bounds) -> Option<(usize, BigInt)>{
+    if le.len() == 1{
+        let (signal, coef) = le.iter().next().unwrap();
+        if *signal != 0 && is_binary(deductions, *signal){
+            return Some((*signal, coef.clone()));
+        }
+    }
+    None
+}
+
+// applies the bounds rule to the constraint using only the bounds of its signals
+// and fixing the value of the selector
+fn bounds_under_case(
+    deductions: &Signal2Bounds,
+    constraint: &Constraint<usize>,
+    selector: usize,
+    value: BigInt,
+    field: &BigInt,
+    verbose: bool,
+) -> Signal2Bounds{
+    let mut local_deductions = Signal2Bounds::new();
+    for signal in constraint.c().keys(){
+        if let Some(bounds) = deductions.get(signal){
+            local_deductions.insert(*signal, ExecutedInequation{signal: *signal, min: bounds.min.clone(), max: bounds.max.clone()});
+        }
+    }
+    local_deductions.insert(selector, ExecutedInequation{signal: selector, min: value.clone(), max: value});
+    local_deductions.insert(0, ExecutedInequation{signal: 0, min: BigInt::from(1), max: BigInt::from(1)});
+    deduction_rule_apply_bounds_constraint(&mut local_deductions, constraint, field, verbose);
+    local_deductions
+}
+
+// Tries to prove weak safety without calling the SMT solver: a signal is determined
+// if it is an input, if it is the only unknown signal of a constraint whose quadratic
+// part is determined, if it is a bit of a decomposition of a determined signal or if it
+// is an output of a subcomponent whose inputs are determined.
+// Returns the derivation of the determined signals in case it succeeds
+pub fn prove_safety_by_propagation(
+    constraints: &[Constraint<usize>],
+    deductions: &Signal2Bounds,
+    facts: &RelationalFacts,
+    inputs: &[usize],
+    outputs: &[usize],
+    implications_safety: &[(Vec<usize>, Vec<usize>)],
+) -> Option<Vec<DeterminismStep>>{
+    let mut steps = Vec::new();
+    let mut determined: HashSet<usize> = HashSet::new();
+    determined.insert(0);
+    for s in inputs{
+        if determined.insert(*s){
+            steps.push(DeterminismStep{rule: "input".to_string(), signal: *s, constraint: None, sources: Vec::new()});
+        }
+    }
+    let mut fixed: Vec<&usize> = deductions.iter()
+        .filter(|(_, bounds)| bounds.min == bounds.max)
+        .map(|(signal, _)| signal)
+        .collect();
+    fixed.sort();
+    for s in fixed{
+        if determined.insert(*s){
+            steps.push(DeterminismStep{rule: "fixed".to_string(), signal: *s, constraint: None, sources: Vec::new()});
+        }
+    }
+
+    let mut updated = true;
+    while updated && !outputs.iter().all(|s| determined.contains(s)){
+        updated = false;
+        for c in constraints{
+            let a = non_zero_coefficients(c.a());
+            let b = non_zero_coefficients(c.b());
+            let quadratic_determined = is_linear_constraint(c)
+                || (a.keys().all(|s| determined.contains(s)) && b.keys().all(|s| determined.contains(s)));
+            if !quadratic_determined{
+                continue;
+            }
+            let unknown: Vec<usize> = non_zero_coefficients(c.c()).keys()
+                .filter(|s| !determined.contains(s))
+                .cloned()
+                .collect();
+            if unknown.len() == 1{
+                determined.insert(unknown[0]);
+                steps.push(DeterminismStep{rule: "constraint".to_string(), signal: unknown[0], constraint: Some(c.clone()), sources: Vec::new()});
+                updated = true;
+            }
+        }
+        for decomposition in &facts.bit_decompositions{
+            if determined.contains(&decomposition.signal){
+                for (bit, _) in &decomposition.bits{
+                    if determined.insert(*bit){
+                        steps.push(DeterminismStep{
+                            rule: "bit_decomposition".to_string(), 
+                            signal: *bit, 
+                            constraint: Some(decomposition.constraint.clone()), 
+                            sources: vec![decomposition.signal]
+                        });
+                        updated = true;
+                    }
+                }
+            }
+        }
+        for (inputs_child, outputs_child) in implications_safety{
+            if inputs_child.iter().all(|s| determined.contains(s)){
+                for s in outputs_child{
+                    if determined.insert(*s){
+                        steps.push(DeterminismStep{rule: "subcomponent".to_string(), signal: *s, constraint: None, sources: inputs_child.clone()});
+                        updated = true;
+                    }
+                }
+            }
+        }
+    }
+    if outputs.iter().all(|s| determined.contains(s)){
+        Some(steps)
+    } else{
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    const FIELD: u64 = 257;
+
+    fn field() -> BigInt {
+        BigInt::from(FIELD)
+    }
+
+    fn bounds(signals: &[(usize, i64, i64)]) -> Signal2Bounds {
+        signals.iter()
+            .map(|(s, min, max)| (*s, ExecutedInequation{signal: *s, min: BigInt::from(*min), max: BigInt::from(*max)}))
+            .collect()
+    }
+
+    // the constraint sum coef * s = 0
+    fn linear(terms: &[(usize, i64)]) -> Constraint<usize> {
+        let c = terms.iter()
+            .map(|(s, coef)| (*s, (BigInt::from(*coef) + field()) % field()))
+            .collect();
+        Constraint::new(HashMap::new(), HashMap::new(), c)
+    }
+
+    // x = offset + sum 2^i * b_i for the given number of bits, with x = 1 and b_i = 2 + i
+    fn decomposition(offset: i64, n_bits: usize) -> (Constraint<usize>, Signal2Bounds) {
+        let mut terms = vec![(1, 1), (0, -offset)];
+        let mut signals = Vec::new();
+        for i in 0..n_bits {
+            terms.push((2 + i, -(1 << i)));
+            signals.push((2 + i, 0, 1));
+        }
+        (linear(&terms), bounds(&signals))
+    }
+
+    #[test]
+    fn bit_decomposition_below_the_prime_is_recognised() {
+        let (c, deductions) = decomposition(1, 8);
+        let facts = RelationalFacts::compute_facts(&[c], &deductions, &field());
+        assert_eq!(facts.bit_decompositions.len(), 1);
+        assert_eq!(facts.bit_decompositions[0].signal, 1);
+    }
+
+    #[test]
+    fn bit_decomposition_reaching_the_prime_is_not_recognised() {
+        // 2 + (2^8 - 1) = 257 can be reached, so x does not determine the bits
+        let (c, mut deductions) = decomposition(2, 8);
+        let facts = RelationalFacts::compute_facts(&[c], &deductions, &field());
+        assert!(facts.bit_decompositions.is_empty());
+        let (c, _) = decomposition(0, 9);
+        let facts = RelationalFacts::compute_facts(std::slice::from_ref(&c), &deductions, &field());
+        assert!(facts.bit_decompositions.is_empty());
+
+        // no bit is fixed to 0 even if x is small
+        deductions.extend(bounds(&[(1, 0, 3)]));
+        let determined = prove_safety_by_propagation(&[c], &deductions, &facts, &[1], &[2, 3], &[]);
+        assert!(determined.is_none());
+    }
+
+    #[test]
+    fn bit_decomposition_fixes_the_bits_above_the_bound() {
+        let (c, mut deductions) = decomposition(0, 8);
+        deductions.extend(bounds(&[(1, 0, 3)]));
+        let facts = RelationalFacts::compute_facts(&[c], &deductions, &field());
+        let updated = deduction_rule_bit_decomposition(&mut deductions, &facts.bit_decompositions[0], &field());
+        assert_eq!(updated.len(), 6);
+        assert_eq!(deductions[&2].max, BigInt::from(1));
+        assert_eq!(deductions[&4].max, BigInt::from(0));
+    }
+
+    #[test]
+    fn one_hot_selector_requires_the_sum_constraint() {
+        // x = 2 * s1 + 3 * s2 with s1, s2 binary
+        let selection = linear(&[(1, 1), (2, -2), (3, -3)]);
+        let sum = linear(&[(2, 1), (3, 1), (0, -1)]);
+        let binary = bounds(&[(2, 0, 1), (3, 0, 1)]);
+
+        let facts = RelationalFacts::compute_facts(std::slice::from_ref(&selection), &binary, &field());
+        assert!(facts.one_hot_selectors.is_empty());
+        let mut deductions = binary.clone();
+        let updated = deduction_rule_one_hot_selector(&mut deductions, &selection, &facts.one_hot_selectors, &field());
+        assert!(updated.is_empty());
+        assert!(!deductions.contains_key(&1));
+
+        let facts = RelationalFacts::compute_facts(&[selection.clone(), sum], &binary, &field());
+        assert_eq!(facts.one_hot_selectors.len(), 1);
+        let mut deductions = binary;
+        let updated = deduction_rule_one_hot_selector(&mut deductions, &selection, &facts.one_hot_selectors, &field());
+        assert_eq!(updated, vec![1]);
+        assert_eq!((&deductions[&1].min, &deductions[&1].max), (&BigInt::from(2), &BigInt::from(3)));
+    }
+
+    #[test]
+    fn binary_selector_requires_a_boolean_selector() {
+        // s * y = z with y in [0, 5]
+        let constraint = Constraint::new(
+            HashMap::from([(1, BigInt::from(1))]),
+            HashMap::from([(2, BigInt::from(1))]),
+            HashMap::from([(3, BigInt::from(1))]),
+        );
+        for selector in [None, Some((1, 0, 2))] {
+            let mut deductions = bounds(&[(2, 0, 5)]);
+            deductions.extend(selector.map(|s| bounds(&[s])).unwrap_or_default());
+            let updated = deduction_rule_binary_selector(&mut deductions, &constraint, &field(), false);
+            assert!(updated.is_empty());
+            assert!(!deductions.contains_key(&3));
+        }
+
+        let mut deductions = bounds(&[(1, 0, 1), (2, 0, 5)]);
+        let updated = deduction_rule_binary_selector(&mut deductions, &constraint, &field(), false);
+        assert_eq!(updated, vec![3]);
+        assert_eq!((&deductions[&3].min, &deductions[&3].max), (&BigInt::from(0), &BigInt::from(5)));
+    }
+
+    #[test]
+    fn facts_are_outdated_when_a_signal_becomes_binary() {
+        let (c, mut deductions) = decomposition(0, 2);
+        deductions.remove(&3);
+        let facts = RelationalFacts::compute_facts(&[c], &deductions, &field());
+        assert!(facts.bit_decompositions.is_empty());
+        assert!(!facts.is_outdated(&[2], &deductions));
+        deductions.extend(bounds(&[(3, 0, 1)]));
+        assert!(facts.is_outdated(&[3], &deductions));
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+use num_bigint_dig::BigInt;
+use circom_algebra::algebra::Constraint;
+use z3::{Config, Context, SatResult, Solver};
+use z3::ast::{Ast, Bool, Int};
+use crate::completeness::linear_expression_in_smt;
+
+// Lifts a counterexample of weak safety of a template to two assignments of all the
+// signals of the circuit that satisfy every constraint, share the inputs of the main
+// component and differ in some output of the template
+pub struct SafetyWitnesses<'a> {
+    pub constraints: Vec<&'a Constraint<usize>>,
+    pub number_signals: usize,
+    pub main_inputs: Vec<usize>,
+    pub template_inputs: Vec<usize>,
+    pub template_outputs: Vec<usize>,
+    pub field: BigInt,
+    pub verification_timeout: u64,
+}
+
+impl<'a> SafetyWitnesses<'a> {
+    pub fn compute(&self) -> Option<(Vec<BigInt>, Vec<BigInt>)>{
+        let mut cfg = Config::new();
+        cfg.set_timeout_msec(self.verification_timeout);
+        let ctx = Context::new(&cfg);
+        let solver = Solver::new(&ctx);
+        let zero = Int::from_i64(&ctx, 0);
+        let p = Int::from_str(&ctx, &self.field.to_string()).unwrap();
+
+        let mut copies = Vec::new();
+        for copy in ["s", "saux"]{
+            let mut signals_to_smt_rep = HashMap::new();
+            for s in 1..self.number_signals{
+                let symbol = Int::new_const(&ctx, format!("{}_{}", copy, s));
+                solver.assert(&symbol.ge(&zero));
+                solver.assert(&symbol.lt(&p));
+                signals_to_smt_rep.insert(s, symbol);
+            }
+            for c in &self.constraints{
+                let a = linear_expression_in_smt(&ctx, c.a(), &signals_to_smt_rep);
+                let b = linear_expression_in_smt(&ctx, c.b(), &signals_to_smt_rep);
+                let c = linear_expression_in_smt(&ctx, c.c(), &signals_to_smt_rep);
+                solver.assert(&(a * b - c).modulo(&p)._eq(&zero));
+            }
+            copies.push(signals_to_smt_rep);
+        }
+        let (first, second) = (&copies[0], &copies[1]);
+
+        for s in self.main_inputs.iter().chain(self.template_inputs.iter()){
+            solver.assert(&first.get(s)?._eq(second.get(s)?));
+        }
+        let mut different_outputs = Vec::new();
+        for s in &self.template_outputs{
+            different_outputs.push(first.get(s)?._eq(second.get(s)?).not());
+        }
+        let different_outputs: Vec<&Bool> = different_outputs.iter().collect();
+        solver.assert(&Bool::or(&ctx, &different_outputs));
+
+        match solver.check(){
+            SatResult::Sat =>{
+                let model = solver.get_model().unwrap();
+                let values = |copy: &HashMap<usize, Int>| -> Option<Vec<BigInt>>{
+                    let mut values = vec![BigInt::from(1)];
+                    for s in 1..self.number_signals{
+                        let value = model.eval(copy.get(&s).unwrap(), true)?;
+                        values.push(value.to_string().parse::<BigInt>().ok()?);
+                    }
+                    Some(values)
+                };
+                Some((values(first)?, values(second)?))
+            },
+            _ => None,
+        }
+    }
+}
@@ -2,6 +2,15 @@ use std::{collections::{HashMap, LinkedList}, cmp::max};
 use num_bigint_dig::BigInt;
 use program_structure::ast::{Expression, ExpressionInfixOpcode, ExpressionPrefixOpcode};
 use crate::{PossibleResult, ExecutedImplication};
+use crate::certificates::{implication_to_string, safety_implication_to_string, spec_expression_to_string};
+use crate::relational_domain::{
+    RelationalFacts, deduction_rule_binary_selector, deduction_rule_bit_decomposition, 
+    deduction_rule_one_hot_selector, prove_safety_by_propagation
+};
+use crate::certificates::{TemplateCertificate, BoundStep, SmtProof};
+use crate::portfolio::{self, Encoding, SolverConfiguration, check_interruptible};
+use crate::bit_vector;
+use std::sync::atomic::AtomicBool;
 use circom_algebra::{modular_arithmetic, algebra::{
     Constraint, ExecutedInequation}};
 
@@ -29,6 +38,14 @@ fn to_neg(a: &BigInt, field: &BigInt) -> BigInt{
 
 pub type Signal2Bounds = HashMap<usize, ExecutedInequation<usize>>;
 
+// result of a weak safety query, only applied to the verification if the query is chosen
+struct SafetyOutcome{
+    result: PossibleResult,
+    logs: Vec<String>,
+    counterexample: Option<Vec<usize>>,
+    unsat_core: Option<SmtProof>,
+}
+
 pub struct TemplateVerification {
     pub template_name: String,
     pub signals: LinkedList<usize>,
@@ -44,6 +61,8 @@ pub struct TemplateVerification {
     pub tags_postconditions_intermediates : Vec<Expression>,
     pub tags_postconditions : Vec<Expression>,
     pub constraints: Vec<Constraint<usize>>,
+    // constraints as received, before the deduction rules rewrite or remove them
+    pub r1cs: Vec<Constraint<usize>>,
     pub implications: Vec<ExecutedImplication>,
     pub tags_implications: Vec<ExecutedImplication>,
     pub implications_safety: Vec<(Vec<usize>, Vec<usize>)>,
@@ -57,7 +76,17 @@ pub struct TemplateVerification {
     pub check_safety: bool,
     pub add_tags_info: bool,
     pub add_postconditions_info: bool,
-    pub apply_deduction_assigned: bool
+    pub apply_deduction_assigned: bool,
+    pub certificate: Option<TemplateCertificate>,
+    pub unsat_core: bool,
+    pub unsat_cores: Vec<SmtProof>,
+    // signals involved in the counterexample found for each property
+    pub counterexamples: HashMap<String, Vec<usize>>,
+    // configurations of the solver run concurrently to check weak safety, empty if the
+    // portfolio is not used
+    pub portfolio: Vec<SolverConfiguration>,
+    // configuration that answered first each query of the portfolio
+    pub portfolio_winners: Vec<String>,
 }
 
 impl TemplateVerification{
@@ -119,6 +148,7 @@ impl TemplateVerification{
             implications_safety,
             deductions: HashMap::new(),
             substitutions,
+            r1cs: fixed_constraints.clone(),
             constraints: fixed_constraints,
             field: field.clone(),
             verbose: false,      
@@ -128,7 +158,13 @@ impl TemplateVerification{
             check_safety,
             add_tags_info,
             add_postconditions_info,
-            apply_deduction_assigned
+            apply_deduction_assigned,
+            certificate: None,
+            unsat_core: false,
+            unsat_cores: Vec::new(),
+            counterexamples: HashMap::new(),
+            portfolio: Vec::new(),
+            portfolio_winners: Vec::new(),
         }
     }
 
@@ -190,6 +226,23 @@ impl TemplateVerification{
 
         }
 
+        if let Some(certificate) = &mut self.certificate{
+            init_signals.insert(0);
+            let mut assumed: Vec<usize> = init_signals.into_iter().collect();
+            assumed.sort();
+            for signal in assumed{
+                let bounds = self.deductions.get(&signal).unwrap();
+                certificate.bound_steps.push(BoundStep{
+                    rule: "assumption".to_string(),
+                    signal,
+                    min: bounds.min.clone(),
+                    max: bounds.max.clone(),
+                    constraints: Vec::new(),
+                    premises: Vec::new(),
+                    source: None,
+                });
+            }
+        }
     }
 
 
@@ -203,6 +256,13 @@ impl TemplateVerification{
 
         let mut logs = Vec::new();
 
+        // the counterexamples of the previous rounds are no longer valid
+        for (property, checked) in [("tags", self.check_tags), ("postconditions", self.check_postconditions), ("safety", self.check_safety)]{
+            if checked{
+                self.counterexamples.remove(property);
+            }
+        }
+
         let result_tags = if self.check_tags{
             if self.tags_postconditions_intermediates.is_empty() && self.tags_postconditions.is_empty(){
                 logs.push(format!("### NOTHING TO VERIFY: THE TEMPLATE DOES NOT CONTAIN TAGGED OUTPUTS\n"));
@@ -233,7 +293,11 @@ impl TemplateVerification{
         };
         let result_safety = if self.check_safety{
             if self.constraints.len() <= MAX_CONSTRAINTS{
-                    self.try_prove_safety(&mut logs)
+                    if self.try_prove_safety_without_smt(&mut logs){
+                        PossibleResult::VERIFIED
+                    } else{
+                        self.try_prove_safety(&mut logs)
+                    }
                 } else{
                     PossibleResult::TOO_BIG
                 }
@@ -244,6 +308,23 @@ impl TemplateVerification{
         (result_tags, result_post, result_safety, logs)
     }
 
+    // applies the deduction rules, returns false if the template is too big to be studied
+    pub fn deduce_round_if_possible(&mut self) -> bool{
+        if self.constraints.len() > MAX_CONSTRAINTS{
+            return false;
+        }
+        self.deduce_round();
+        true
+    }
+
+    // applies the deduction rules without calling the SMT solver and returns the obtained bounds
+    pub fn deduce_bounds(&mut self)-> Signal2Bounds{
+        if self.constraints.len() <= MAX_CONSTRAINTS{
+            self.deduce_round();
+        }
+        std::mem::take(&mut self.deductions)
+    }
+
     // normalizes the constraints choosing the smaller coefficients
     pub fn normalize(&mut self){
         let old_constraints = std::mem::take(&mut self.constraints);
@@ -263,28 +344,161 @@ impl TemplateVerification{
         let filter_const = std::mem::take(&mut self.constraints);
 
         for c in filter_const{
+            let snapshot = snapshot_bounds(&self.certificate, &self.deductions, &[&c]);
             let should_remove = deduction_rule_integrity_domain(&mut self.deductions, &c, &self.field); 
+            record_bound_steps(&mut self.certificate, &self.deductions, "integrity_domain", &[&c], snapshot, None);
             if !should_remove{ 
                 self.constraints.push(c);
             }
         } 
 
-        for c in &self.constraints{
-            new_signal_bounds_iteration.append(&mut deduction_rule_apply_bounds_constraint(&mut self.deductions, &c, &self.field, self.verbose));
-        }
+        let mut facts = RelationalFacts::compute_facts(&self.constraints, &self.deductions, &self.field);
+        new_signal_bounds_iteration.append(&mut self.apply_deduction_rules(&facts));
 
         while !new_signal_bounds_iteration.is_empty(){
+            if facts.is_outdated(&new_signal_bounds_iteration, &self.deductions){
+                facts = RelationalFacts::compute_facts(&self.constraints, &self.deductions, &self.field);
+            }
             new_signal_bounds.append(&mut new_signal_bounds_iteration);
+            new_signal_bounds_iteration.append(&mut self.apply_deduction_rules(&facts));
+        }
+        new_signal_bounds
+    }
+
+    // applies the bounds rules and the rules of the relational domain once
+    fn apply_deduction_rules(&mut self, facts: &RelationalFacts)-> Vec<usize>{
+        let mut new_signal_bounds = Vec::new();
+        for c in &self.constraints{
+            let snapshot = snapshot_bounds(&self.certificate, &self.deductions, &[c]);
+            new_signal_bounds.append(&mut deduction_rule_apply_bounds_constraint(&mut self.deductions, &c, &self.field, self.verbose));
+            record_bound_steps(&mut self.certificate, &self.deductions, "linear_bounds", &[c], snapshot, None);
+
+            let snapshot = snapshot_bounds(&self.certificate, &self.deductions, &[c]);
+            new_signal_bounds.append(&mut deduction_rule_binary_selector(&mut self.deductions, &c, &self.field, self.verbose));
+            record_bound_steps(&mut self.certificate, &self.deductions, "binary_selector", &[c], snapshot, None);
+        }
+        for decomposition in &facts.bit_decompositions{
+            let snapshot = snapshot_bounds(&self.certificate, &self.deductions, &[&decomposition.constraint]);
+            new_signal_bounds.append(&mut deduction_rule_bit_decomposition(&mut self.deductions, decomposition, &self.field));
+            record_bound_steps(&mut self.certificate, &self.deductions, "bit_decomposition", &[&decomposition.constraint], snapshot, Some(decomposition.signal));
+        }
+        if !facts.one_hot_selectors.is_empty(){
             for c in &self.constraints{
-              
-                new_signal_bounds_iteration.append(&mut deduction_rule_apply_bounds_constraint(&mut self.deductions, &c, &self.field, self.verbose));
+                let mut used_constraints = vec![c];
+                if self.certificate.is_some(){
+                    let signals = c.take_signals();
+                    for group in &facts.one_hot_selectors{
+                        if group.selectors.iter().any(|s| signals.contains(s)){
+                            used_constraints.push(&group.constraint);
+                        }
+                    }
+                }
+                let snapshot = snapshot_bounds(&self.certificate, &self.deductions, &used_constraints);
+                new_signal_bounds.append(&mut deduction_rule_one_hot_selector(&mut self.deductions, &c, &facts.one_hot_selectors, &self.field));
+                record_bound_steps(&mut self.certificate, &self.deductions, "one_hot_selector", &used_constraints, snapshot, None);
             }
         }
         new_signal_bounds
     }
 
+    // checks that for every input satisfying the preconditions there is an assignment of the
+    // rest of the signals satisfying the constraints, using the contracts of the subcomponents
+    pub fn try_prove_satisfiability(&mut self, logs: &mut Vec<String>) -> PossibleResult{
+        let mut cfg = Config::new();
+        cfg.set_timeout_msec(self.verification_timeout);
+        let ctx = Context::new(&cfg);
+        let solver = Solver::new(&ctx);
+        let zero = z3::ast::Int::from_i64(&ctx, 0);
+        let field = z3::ast::Int::from_str(&ctx, &self.field.to_string()).unwrap();
+        let first_input = self.initial_signal + self.number_outputs;
+        let is_input = |s: &usize| *s >= first_input && *s < first_input + self.number_inputs;
+
+        let mut aux_signals_to_smt_rep = HashMap::new();
+        for s in &self.signals{
+            aux_signals_to_smt_rep.insert(*s, z3::ast::Int::new_const(&ctx, format!("s_{}", s)));
+        }
 
-    pub fn try_prove_tags(&self, logs: &mut Vec<String>)-> PossibleResult{
+        // the deduced bounds already assume that the constraints hold, so they are not used
+        let deductions = HashMap::new();
+        let mut witness_signals = Vec::new();
+        let mut witness = Vec::new();
+        for s in &self.signals{
+            let symbol = aux_signals_to_smt_rep.get(s).unwrap();
+            let in_field = symbol.ge(&zero) & symbol.lt(&field);
+            if is_input(s){
+                solver.assert(&in_field);
+            } else{
+                witness_signals.push(symbol.clone());
+                witness.push(in_field);
+            }
+        }
+
+        // the assumptions only involving inputs restrict the inputs, the rest must hold for the witness
+        for assumption in self.preconditions.iter().chain(self.tags_preconditions.iter()).chain(self.facts.iter()){
+            let condition = match get_z3_expression_bool(&ctx, assumption, &aux_signals_to_smt_rep){
+                Ok(condition) => condition,
+                Err(_) => {
+                    logs.push("### UNKNOWN: SOME ASSUMPTION OF THE TEMPLATE CANNOT BE ENCODED IN SMT\n".to_string());
+                    return PossibleResult::UNKNOWN;
+                }
+            };
+            let mut signals = Vec::new();
+            expression_signals(assumption, &mut signals);
+            if signals.iter().all(&is_input){
+                solver.assert(&condition);
+            } else{
+                witness.push(condition);
+            }
+        }
+
+        for (i, constraint) in self.constraints.iter().enumerate(){
+            let (condition, mut auxiliary_variables) = constraint_in_smt(constraint, &ctx, &aux_signals_to_smt_rep, &self.field, &deductions, i, &field);
+            witness.push(condition);
+            witness_signals.append(&mut auxiliary_variables);
+        }
+        for implication in self.implications.iter().chain(self.tags_implications.iter()){
+            witness.push(implication_in_smt(implication, &ctx, &aux_signals_to_smt_rep));
+        }
+
+        let witness: Vec<&z3::ast::Bool> = witness.iter().collect();
+        let bounds: Vec<&dyn Ast> = witness_signals.iter().map(|s| s as &dyn Ast).collect();
+        solver.assert(&z3::ast::forall_const(&ctx, &bounds, &[], &!z3::ast::Bool::and(&ctx, &witness)));
+
+        match solver.check(){
+            SatResult::Sat =>{
+                logs.push("### THE CONSTRAINTS OF THE TEMPLATE ARE NOT SATISFIABLE FOR SOME INPUTS. FOUND COUNTEREXAMPLE USING SMT:\n".to_string());
+                let model = solver.get_model().unwrap();
+                let mut signals = Vec::new();
+                for s in first_input..first_input + self.number_inputs{
+                    let v = model.eval(aux_signals_to_smt_rep.get(&s).unwrap(), true).unwrap();
+                    logs.push(format!("Input signal {}: {}\n", s, v));
+                    signals.push(s);
+                }
+                self.counterexamples.insert("satisfiability".to_string(), signals);
+                PossibleResult::FAILED
+            },
+            SatResult::Unsat =>{
+                logs.push("### SUCCESS: THE CONSTRAINTS OF THE TEMPLATE ARE SATISFIABLE FOR EVERY VALID INPUT\n".to_string());
+                PossibleResult::VERIFIED
+            },
+            _=> {
+                logs.push("### UNKNOWN: VERIFICATION OF THE SATISFIABILITY OF THE CONSTRAINTS TIMEOUT\n".to_string());
+                PossibleResult::UNKNOWN
+            }
+        }
+    }
+
+    // checks whether the assumptions of the proofs (preconditions, facts, tags of the inputs
+    // and contracts of the subcomponents) contradict each other together with the constraints
+    pub fn contradictory_assumptions(&self, logs: &mut Vec<String>) -> bool{
+        let assumptions: Vec<&Expression> = self.preconditions.iter()
+            .chain(self.preconditions_intermediates.iter())
+            .chain(self.tags_preconditions.iter())
+            .chain(self.facts.iter())
+            .collect();
+        if assumptions.is_empty() && self.implications.is_empty() && self.tags_implications.is_empty(){
+            return false;
+        }
         let mut cfg = Config::new();
         cfg.set_timeout_msec(self.verification_timeout);
         let ctx = Context::new(&cfg);
@@ -292,6 +506,87 @@ impl TemplateVerification{
         let zero = z3::ast::Int::from_i64(&ctx, 0);
         let field = z3::ast::Int::from_str(&ctx, &self.field.to_string()).unwrap();
         let mut aux_signals_to_smt_rep = HashMap::new();
+        for s in &self.signals{
+            let aux_signal_to_smt = z3::ast::Int::new_const(&ctx, format!("s_{}", s));
+            solver.assert(&aux_signal_to_smt.ge(&zero));
+            solver.assert(&aux_signal_to_smt.lt(&field));
+            aux_signals_to_smt_rep.insert(*s, aux_signal_to_smt);
+        }
+        for assumption in assumptions{
+            match get_z3_expression_bool(&ctx, assumption, &aux_signals_to_smt_rep){
+                Ok(condition) => solver.assert(&condition),
+                Err(_) => {
+                    // the assumptions that are not encoded cannot be shown contradictory
+                    logs.push("### THE ASSUMPTIONS OF THE TEMPLATE ARE NOT CHECKED FOR CONTRADICTIONS: SOME ASSUMPTION CANNOT BE ENCODED IN SMT\n".to_string());
+                    return false;
+                }
+            }
+        }
+        // the deduced bounds are not used, they may come from the contradictory assumptions
+        let deductions = HashMap::new();
+        for (i, constraint) in self.r1cs.iter().enumerate(){
+            solver.assert(&constraint_in_smt(constraint, &ctx, &aux_signals_to_smt_rep, &self.field, &deductions, i, &field).0);
+        }
+        for implication in self.implications.iter().chain(self.tags_implications.iter()){
+            solver.assert(&implication_in_smt(implication, &ctx, &aux_signals_to_smt_rep));
+        }
+        match solver.check(){
+            SatResult::Unsat =>{
+                logs.push("### WARNING: THE ASSUMPTIONS OF THE TEMPLATE ARE CONTRADICTORY, THE PROOFS HOLD VACUOUSLY\n".to_string());
+                true
+            },
+            _ => false
+        }
+    }
+
+    fn record_unsat_core(&mut self, proof: SmtProof){
+        if self.unsat_core{
+            self.unsat_cores.push(proof.clone());
+        }
+        if let Some(certificate) = &mut self.certificate{
+            certificate.smt_proofs.push(proof);
+        }
+    }
+
+    // tries to prove weak safety using only the deduced bounds and the relational facts
+    pub fn try_prove_safety_without_smt(&mut self, logs: &mut Vec<String>)-> bool{
+        let facts = RelationalFacts::compute_facts(&self.constraints, &self.deductions, &self.field);
+        let inputs: Vec<usize> = (0..self.number_inputs)
+            .map(|s| self.initial_signal + self.number_outputs + s)
+            .collect();
+        let outputs: Vec<usize> = (0..self.number_outputs)
+            .map(|s| self.initial_signal + s)
+            .collect();
+        let possible_steps = prove_safety_by_propagation(
+            &self.constraints, 
+            &self.deductions, 
+            &facts, 
+            &inputs, 
+            &outputs, 
+            &self.implications_safety
+        );
+        match possible_steps{
+            Some(mut steps) => {
+                logs.push("### WEAK SAFETY ENSURED BY THE DEDUCTION RULES (WITHOUT CALLING THE SMT SOLVER)\n".to_string());
+                if let Some(certificate) = &mut self.certificate{
+                    certificate.determinism_steps.append(&mut steps);
+                }
+                true
+            }
+            None => false
+        }
+    }
+
+
+    pub fn try_prove_tags(&mut self, logs: &mut Vec<String>)-> PossibleResult{
+        let mut cfg = Config::new();
+        cfg.set_timeout_msec(self.verification_timeout);
+        let ctx = Context::new(&cfg);
+        let solver = Solver::new(&ctx);
+        let mut trackers = SmtTrackers::new(&ctx, &solver, self.certificate.is_some() || self.unsat_core, self.unsat_core);
+        let zero = z3::ast::Int::from_i64(&ctx, 0);
+        let field = z3::ast::Int::from_str(&ctx, &self.field.to_string()).unwrap();
+        let mut aux_signals_to_smt_rep = HashMap::new();
         for s in &self.signals{
             let aux_signal_to_smt = z3::ast::Int::new_const(&ctx, format!("s_{}", s));
             
@@ -310,7 +605,7 @@ impl TemplateVerification{
                         &self.field
                     );
 
-                    solver.assert(&condition);
+                    assert_tracked(&solver, trackers.bounds_literal(&ctx, *s, bounds).as_ref(), &condition);
                 }
             }
             aux_signals_to_smt_rep.insert(*s, aux_signal_to_smt);
@@ -318,16 +613,16 @@ impl TemplateVerification{
 
         let mut value_preconditions = z3::ast::Bool::from_bool(&ctx, true);
         for precondition in &self.preconditions{
-            value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap();
+            trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap());
         }
         for precondition in &self.preconditions_intermediates{
-            value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap();
+            trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap());
         }
         for precondition in &self.tags_preconditions{
-            value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap();
+            trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap());
         }
         for precondition in &self.facts{
-            value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap();
+            trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap());
         }
 
         solver.assert(&value_preconditions);
@@ -335,19 +630,22 @@ impl TemplateVerification{
 
         let mut i = 0;
         for constraint in &self.constraints{
+            let tracker = trackers.track_constraint(&ctx, i);
             insert_constraint_in_smt(constraint, &ctx, &solver, &aux_signals_to_smt_rep, &self.field, 
-                                        &self.deductions, i, &field, self.verbose);
+                                        &self.deductions, i, &field, tracker.as_ref());
             i = i + 1;
         }
 
 
         for implication in &self.tags_implications{
-            insert_implication_in_smt(implication, &ctx, &solver, &aux_signals_to_smt_rep, &self.field);
+            let tracker = trackers.track_contract(&ctx, implication_to_string(implication));
+            insert_implication_in_smt(implication, &ctx, &solver, &aux_signals_to_smt_rep, &self.field, tracker.as_ref());
         }
 
         if self.check_postconditions{
             for implication in &self.implications{
-                insert_implication_in_smt(implication, &ctx, &solver, &aux_signals_to_smt_rep, &self.field);
+                let tracker = trackers.track_contract(&ctx, implication_to_string(implication));
+                insert_implication_in_smt(implication, &ctx, &solver, &aux_signals_to_smt_rep, &self.field, tracker.as_ref());
             }
         }
 
@@ -361,21 +659,28 @@ impl TemplateVerification{
 
         solver.assert(&!value_postconditions);
 
-        match solver.check(){
+        match trackers.check(&solver){
             SatResult::Sat =>{
+                let model = solver.get_model().unwrap();
+                if let Some(result) = spurious_model(&self.r1cs, &self.field, &model, &[&aux_signals_to_smt_rep], logs){
+                    return result;
+                }
                 logs.push(format!("### THE VERIFICATION OF THE TAGS OF THE TEMPLATE FAILED. FOUND COUNTEREXAMPLE USING SMT:\n"));
                 //if self.verbose{
 
-                 let model = solver.get_model().unwrap();
                  for s in &self.signals{
                      let v = model.eval(aux_signals_to_smt_rep.get(s).unwrap(), true).unwrap();
                      logs.push(format!("Signal {}: {}\n", s, v.to_string()));
                  }
                 //}
+                let specifications: Vec<&Expression> = self.tags_postconditions.iter().chain(self.tags_postconditions_intermediates.iter()).collect();
+                let signals = violated_signals(&ctx, &model, &specifications, &aux_signals_to_smt_rep);
+                self.counterexamples.insert("tags".to_string(), signals);
                 PossibleResult::FAILED
             },
             SatResult::Unsat =>{
                 logs.push(format!("### SUCCESS: THE TAGS OF THE TEMPLATE ARE VERIFIED\n"));
+                self.record_unsat_core(trackers.unsat_core(&solver, &self.constraints, "tags"));
                 PossibleResult::VERIFIED
             },
             _=> {
@@ -385,11 +690,12 @@ impl TemplateVerification{
         }
     }
 
-    pub fn try_prove_postconditions(&self, logs: &mut Vec<String>)-> PossibleResult{
+    pub fn try_prove_postconditions(&mut self, logs: &mut Vec<String>)-> PossibleResult{
         let mut cfg = Config::new();
         cfg.set_timeout_msec(self.verification_timeout);
         let ctx = Context::new(&cfg);
         let solver = Solver::new(&ctx);
+        let mut trackers = SmtTrackers::new(&ctx, &solver, self.certificate.is_some() || self.unsat_core, self.unsat_core);
         let zero = z3::ast::Int::from_i64(&ctx, 0);
         let field = z3::ast::Int::from_str(&ctx, &self.field.to_string()).unwrap();
         let mut aux_signals_to_smt_rep = HashMap::new();
@@ -411,7 +717,7 @@ impl TemplateVerification{
                         &self.field
                     );
 
-                    solver.assert(&condition);
+                    assert_tracked(&solver, trackers.bounds_literal(&ctx, *s, bounds).as_ref(), &condition);
                 }
             }
             aux_signals_to_smt_rep.insert(*s, aux_signal_to_smt);
@@ -419,24 +725,24 @@ impl TemplateVerification{
 
         let mut value_preconditions = z3::ast::Bool::from_bool(&ctx, true);
         for precondition in &self.preconditions{
-            value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap();
+            trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap());
         }
         for precondition in &self.preconditions_intermediates{
-            value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap();
+            trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap());
         }
         for precondition in &self.tags_preconditions{
-            value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap();
+            trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap());
         }
         if self.add_tags_info{
             for precondition in &self.tags_postconditions_intermediates{
-                value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap();
+                trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap());
             }
             for precondition in &self.tags_postconditions{
-                value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap();
+                trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap());
             }
         }
         for precondition in &self.facts{
-            value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap();
+            trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap());
         }
 
         solver.assert(&value_preconditions);
@@ -444,17 +750,20 @@ impl TemplateVerification{
 
         let mut i = 0;
         for constraint in &self.constraints{
+            let tracker = trackers.track_constraint(&ctx, i);
             insert_constraint_in_smt(constraint, &ctx, &solver, &aux_signals_to_smt_rep, &self.field, 
-                                        &self.deductions, i, &field, self.verbose);
+                                        &self.deductions, i, &field, tracker.as_ref());
             i = i + 1;
         }
         if self.check_tags{
             for implication in &self.tags_implications{
-                insert_implication_in_smt(implication, &ctx, &solver, &aux_signals_to_smt_rep, &self.field);
+                let tracker = trackers.track_contract(&ctx, implication_to_string(implication));
+                insert_implication_in_smt(implication, &ctx, &solver, &aux_signals_to_smt_rep, &self.field, tracker.as_ref());
             }
         }
         for implication in &self.implications{
-            insert_implication_in_smt(implication, &ctx, &solver, &aux_signals_to_smt_rep, &self.field);
+            let tracker = trackers.track_contract(&ctx, implication_to_string(implication));
+            insert_implication_in_smt(implication, &ctx, &solver, &aux_signals_to_smt_rep, &self.field, tracker.as_ref());
         }
 
         let mut value_postconditions = z3::ast::Bool::from_bool(&ctx, true);
@@ -468,21 +777,28 @@ impl TemplateVerification{
 
         solver.assert(&!value_postconditions);
 
-        match solver.check(){
+        match trackers.check(&solver){
             SatResult::Sat =>{
+                let model = solver.get_model().unwrap();
+                if let Some(result) = spurious_model(&self.r1cs, &self.field, &model, &[&aux_signals_to_smt_rep], logs){
+                    return result;
+                }
                 logs.push(format!("### THE VERIFICATION OF THE SPECIFICATION OF THE TEMPLATE FAILED. FOUND COUNTEREXAMPLE USING SMT:\n"));
                 //if self.verbose{
 
-                 let model = solver.get_model().unwrap();
                  for s in &self.signals{
                      let v = model.eval(aux_signals_to_smt_rep.get(s).unwrap(), true).unwrap();
                      logs.push(format!("Signal {}: {}\n", s, v.to_string()));
                  }
                 //}
+                let specifications: Vec<&Expression> = self.postconditions.iter().chain(self.postconditions_intermediates.iter()).collect();
+                let signals = violated_signals(&ctx, &model, &specifications, &aux_signals_to_smt_rep);
+                self.counterexamples.insert("postconditions".to_string(), signals);
                 PossibleResult::FAILED
             },
             SatResult::Unsat =>{
                 logs.push(format!("### SUCCESS: THE SPECIFICATION OF THE TEMPLATE IS VERIFIED\n"));
+                self.record_unsat_core(trackers.unsat_core(&solver, &self.constraints, "postconditions"));
                 PossibleResult::VERIFIED
             },
             _=> {
@@ -493,11 +809,206 @@ impl TemplateVerification{
     }
 
 
-    pub fn try_prove_safety(&self, logs: &mut Vec<String>) -> PossibleResult{
+    // checks a candidate postcondition, used to infer the specification of the template
+    pub fn prove_candidate(&mut self, candidate: &Expression) -> bool{
+        let postconditions = std::mem::replace(&mut self.postconditions, vec![candidate.clone()]);
+        let postconditions_intermediates = std::mem::take(&mut self.postconditions_intermediates);
+        let result = self.try_prove_postconditions(&mut Vec::new());
+        self.postconditions = postconditions;
+        self.postconditions_intermediates = postconditions_intermediates;
+        self.counterexamples.remove("postconditions");
+        result == PossibleResult::VERIFIED
+    }
+
+    // checks the semantics of a tag for an output of the template, used to suggest new tags
+    pub fn prove_tag_candidate(&mut self, candidate: &Expression) -> bool{
+        let tags_postconditions = std::mem::replace(&mut self.tags_postconditions, vec![candidate.clone()]);
+        let tags_postconditions_intermediates = std::mem::take(&mut self.tags_postconditions_intermediates);
+        let result = self.try_prove_tags(&mut Vec::new());
+        self.tags_postconditions = tags_postconditions;
+        self.tags_postconditions_intermediates = tags_postconditions_intermediates;
+        self.counterexamples.remove("tags");
+        result == PossibleResult::VERIFIED
+    }
+
+    pub fn try_prove_safety(&mut self, logs: &mut Vec<String>) -> PossibleResult{
+        let outcome = if self.portfolio.is_empty(){
+            self.safety_query(&SolverConfiguration::default(), None)
+        } else{
+            let (outcome, winner) = portfolio::solve(
+                &self.portfolio, 
+                |configuration, stop| self.safety_query(configuration, Some(stop)),
+                |outcome| outcome.result == PossibleResult::VERIFIED || outcome.result == PossibleResult::FAILED
+            );
+            match winner{
+                Some(position) =>{
+                    let name = self.portfolio[position].name.clone();
+                    logs.push(format!("### PORTFOLIO: ANSWER FOUND FIRST BY THE CONFIGURATION {}\n", name));
+                    self.portfolio_winners.push(name);
+                }
+                None => logs.push("### PORTFOLIO: NO CONFIGURATION FOUND A DEFINITIVE ANSWER\n".to_string()),
+            }
+            outcome
+        };
+        let SafetyOutcome{result, logs: mut query_logs, counterexample, unsat_core} = outcome;
+        logs.append(&mut query_logs);
+        if let Some(signals) = counterexample{
+            self.counterexamples.insert("safety".to_string(), signals);
+        }
+        if let Some(proof) = unsat_core{
+            self.record_unsat_core(proof);
+        }
+        result
+    }
+
+    // checks weak safety using the encoding of the configuration, the automatic encoding
+    // falls back to integers if the bit-vector query does not give a definitive answer
+    fn safety_query(&self, configuration: &SolverConfiguration, stop: Option<&AtomicBool>) -> SafetyOutcome{
+        let width = if configuration.encoding == Encoding::Integer{
+            None
+        } else{
+            self.bit_vector_width()
+        };
+        let mut logs = Vec::new();
+        match width{
+            Some(width) =>{
+                let mut outcome = self.bit_vector_safety_query(width, stop);
+                if configuration.encoding == Encoding::BitVector || outcome.result == PossibleResult::VERIFIED || outcome.result == PossibleResult::FAILED{
+                    return outcome;
+                }
+                logs.append(&mut outcome.logs);
+                logs.push("### NO DEFINITIVE ANSWER USING THE BIT-VECTOR ENCODING, USING THE INTEGER ENCODING\n".to_string());
+            }
+            None if configuration.encoding == Encoding::BitVector =>{
+                logs.push("### THE BIT-VECTOR ENCODING CANNOT BE APPLIED TO THE TEMPLATE\n".to_string());
+                return SafetyOutcome{result: PossibleResult::UNKNOWN, logs, counterexample: None, unsat_core: None};
+            }
+            None => {}
+        }
+        let mut outcome = self.integer_safety_query(configuration, stop);
+        logs.append(&mut outcome.logs);
+        outcome.logs = logs;
+        outcome
+    }
+
+    // width of the bit-vectors used to encode the signals, only if the bounds of all the signals
+    // are small enough and the only assumptions of the template are the bounds and the safety of
+    // its subcomponents, since the specifications are encoded over the integers. Unsat cores are
+    // only recorded using integers
+    fn bit_vector_width(&self) -> Option<u32>{
+        let has_assumptions = !self.preconditions.is_empty() || !self.preconditions_intermediates.is_empty()
+            || !self.tags_preconditions.is_empty() || !self.facts.is_empty()
+            || (self.add_tags_info && (!self.tags_postconditions.is_empty() || !self.tags_postconditions_intermediates.is_empty()))
+            || (self.add_postconditions_info && (!self.postconditions.is_empty() || !self.postconditions_intermediates.is_empty()))
+            || (self.check_tags && !self.tags_implications.is_empty())
+            || (self.check_postconditions && !self.implications.is_empty())
+            || self.apply_deduction_assigned;
+        if has_assumptions || self.certificate.is_some() || self.unsat_core{
+            return None;
+        }
+        bit_vector::encoding_width(&self.constraints, self.signals.iter(), &self.deductions, &self.field)
+    }
+
+    // weak safety query encoding the signed representatives of the signals as bit-vectors
+    fn bit_vector_safety_query(&self, width: u32, stop: Option<&AtomicBool>) -> SafetyOutcome{
+        let mut logs = vec![format!("### USING THE BIT-VECTOR ENCODING ({} BITS)\n", width)];
         let mut cfg = Config::new();
         cfg.set_timeout_msec(self.verification_timeout);
         let ctx = Context::new(&cfg);
         let solver = Solver::new(&ctx);
+        let mut signals_to_smt_rep = HashMap::new();
+        let mut signals_to_smt_rep_aux = HashMap::new();
+        for s in &self.signals{
+            let is_input = s >= &(self.initial_signal + self.number_outputs) && s < &(self.initial_signal + self.number_outputs + self.number_inputs);
+            let signal = z3::ast::BV::new_const(&ctx, format!("s_{}", s), width);
+            let copy_signal = if is_input{
+                signal.clone()
+            } else{
+                z3::ast::BV::new_const(&ctx, format!("saux_{}", s), width)
+            };
+            let bounds = self.deductions.get(s).unwrap();
+            solver.assert(&bit_vector::bounds_condition(&ctx, &signal, &bounds.min, &bounds.max, width));
+            solver.assert(&bit_vector::bounds_condition(&ctx, &copy_signal, &bounds.min, &bounds.max, width));
+            signals_to_smt_rep.insert(*s, signal);
+            signals_to_smt_rep_aux.insert(*s, copy_signal);
+        }
+        for constraint in &self.constraints{
+            solver.assert(&bit_vector::constraint_condition(&ctx, constraint, &signals_to_smt_rep, &self.field, width));
+            solver.assert(&bit_vector::constraint_condition(&ctx, constraint, &signals_to_smt_rep_aux, &self.field, width));
+        }
+        // the signed representatives are equal iff the values of the signals are equal
+        for (inputs, outputs) in &self.implications_safety{
+            let mut implication_left = z3::ast::Bool::from_bool(&ctx, true);
+            for s in inputs{
+                implication_left &= signals_to_smt_rep.get(s).unwrap()._eq(signals_to_smt_rep_aux.get(s).unwrap());
+            }
+            let mut implication_right = z3::ast::Bool::from_bool(&ctx, true);
+            for s in outputs{
+                implication_right &= signals_to_smt_rep.get(s).unwrap()._eq(signals_to_smt_rep_aux.get(s).unwrap());
+            }
+            solver.assert(&implication_left.implies(&implication_right));
+        }
+        let mut all_outputs_equal = z3::ast::Bool::from_bool(&ctx, true);
+        for s in 0..self.number_outputs{
+            let s_1 = signals_to_smt_rep.get(&(self.initial_signal + s)).unwrap();
+            let s_2 = signals_to_smt_rep_aux.get(&(self.initial_signal + s)).unwrap();
+            all_outputs_equal &= s_1._eq(s_2);
+        }
+        solver.assert(&!all_outputs_equal);
+
+        match check_interruptible(&ctx, stop, || solver.check()){
+            SatResult::Sat =>{
+                let model = solver.get_model().unwrap();
+                let mut copies = Vec::new();
+                for signals_to_smt_symbols in [&signals_to_smt_rep, &signals_to_smt_rep_aux]{
+                    let mut values = HashMap::new();
+                    for (s, symbol) in signals_to_smt_symbols{
+                        let value = model.eval(&symbol.to_int(true), true).unwrap().to_string().parse::<BigInt>().unwrap();
+                        values.insert(*s, bit_vector::to_field(&value, &self.field));
+                    }
+                    copies.push(values);
+                }
+                if let Some(result) = spurious_values(&self.r1cs, &self.field, &copies, &mut logs){
+                    return SafetyOutcome{result, logs, counterexample: None, unsat_core: None};
+                }
+                logs.push("### THE TEMPLATE DOES NOT ENSURE SAFETY. FOUND COUNTEREXAMPLE USING SMT:\n".to_string());
+                for s in 0..self.number_inputs{
+                    let signal = self.initial_signal + self.number_outputs + s;
+                    logs.push(format!("Input signal {}: {}\n", signal, copies[0][&signal]));
+                }
+                let mut signals = Vec::new();
+                for s in 0..self.number_outputs{
+                    let signal = self.initial_signal + s;
+                    logs.push(format!("Output signal {}: values {} | {}\n", signal, copies[0][&signal], copies[1][&signal]));
+                    if copies[0][&signal] != copies[1][&signal]{
+                        signals.push(signal);
+                    }
+                }
+                SafetyOutcome{result: PossibleResult::FAILED, logs, counterexample: Some(signals), unsat_core: None}
+            },
+            SatResult::Unsat =>{
+                logs.push("### WEAK SAFETY ENSURED BY THE TEMPLATE\n".to_string());
+                SafetyOutcome{result: PossibleResult::VERIFIED, logs, counterexample: None, unsat_core: None}
+            },
+            _=> {
+                logs.push("### UNKNOWN: VERIFICATION OF WEAK SAFETY USING THE SPECIFICATION TIMEOUT\n".to_string());
+                SafetyOutcome{result: PossibleResult::UNKNOWN, logs, counterexample: None, unsat_core: None}
+            }
+        }
+    }
+
+    // builds and checks the weak safety query encoding the signals as integers, stop is only
+    // given when the query is part of a portfolio
+    fn integer_safety_query(&self, configuration: &SolverConfiguration, stop: Option<&AtomicBool>) -> SafetyOutcome{
+        let mut logs = Vec::new();
+        let mut cfg = Config::new();
+        cfg.set_timeout_msec(self.verification_timeout);
+        let ctx = Context::new(&cfg);
+        let solver = Solver::new(&ctx);
+        if stop.is_some(){
+            configuration.set_params(&ctx, &solver);
+        }
+        let mut trackers = SmtTrackers::new(&ctx, &solver, self.certificate.is_some() || self.unsat_core, self.unsat_core);
         let zero = z3::ast::Int::from_i64(&ctx, 0);
         let field = z3::ast::Int::from_str(&ctx, &self.field.to_string()).unwrap();
         let mut aux_signals_to_smt_rep = HashMap::new();
@@ -531,7 +1042,7 @@ impl TemplateVerification{
                         &bounds.max, 
                         &self.field
                     );
-                    solver.assert(&condition);
+                    assert_tracked(&solver, trackers.bounds_literal(&ctx, *s, bounds).as_ref(), &condition);
 
                     let condition = get_z3_condition_bounds(
                         &ctx, 
@@ -540,7 +1051,7 @@ impl TemplateVerification{
                         &bounds.max, 
                         &self.field
                     );
-                    solver.assert(&condition);
+                    assert_tracked(&solver, trackers.bounds_literal(&ctx, *s, bounds).as_ref(), &condition);
                 }
             }
 
@@ -548,57 +1059,60 @@ impl TemplateVerification{
 
         let mut value_preconditions = z3::ast::Bool::from_bool(&ctx, true);
         for precondition in &self.preconditions{
-            value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap();
-            value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep_aux).unwrap();
+            trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap());
+            trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep_aux).unwrap());
 
         }
         for precondition in &self.preconditions_intermediates{
-            value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap();
-            value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep_aux).unwrap();
+            trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap());
+            trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep_aux).unwrap());
 
         }
         for precondition in &self.tags_preconditions{
-            value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap();
-            value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep_aux).unwrap();
+            trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap());
+            trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep_aux).unwrap());
 
         }
         if self.add_tags_info{
             for precondition in &self.tags_postconditions_intermediates{
-                value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap();
-                value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep_aux).unwrap();
+                trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap());
+                trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep_aux).unwrap());
             }
             for precondition in &self.tags_postconditions{
-                value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap();
-                value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep_aux).unwrap();
+                trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap());
+                trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep_aux).unwrap());
 
             }
         }
         if self.add_postconditions_info{
             for precondition in &self.postconditions_intermediates{
-                value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap();
-                value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep_aux).unwrap();
+                trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap());
+                trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep_aux).unwrap());
             }
             for precondition in &self.postconditions{
-                value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap();
-                value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep_aux).unwrap();
+                trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap());
+                trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep_aux).unwrap());
             }
         }
 
         for precondition in &self.facts{
-            value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap();
-            value_preconditions &= get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep_aux).unwrap();
+            trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep).unwrap());
+            trackers.assume(&ctx, &mut value_preconditions, precondition, get_z3_expression_bool(&ctx, &precondition, &aux_signals_to_smt_rep_aux).unwrap());
         }
 
         solver.assert(&value_preconditions);
 
         let mut i = 0;
+        let mut constraint_trackers = Vec::new();
         for constraint in &self.constraints{
+            let tracker = trackers.track_constraint(&ctx, i / 2);
             insert_constraint_in_smt(constraint, &ctx, &solver, &aux_signals_to_smt_rep, &self.field, 
-                                        &self.deductions, i, &field, self.verbose);
+                                        &self.deductions, i, &field, tracker.as_ref());
             i = i + 1;
             insert_constraint_in_smt(constraint, &ctx, &solver, &aux_signals_to_smt_rep_aux, &self.field, 
-                &self.deductions, i, &field, self.verbose);
+                &self.deductions, i, &field, tracker.as_ref());
             i = i + 1;
+            constraint_trackers.push(tracker);
         }
 
         if self.apply_deduction_assigned{
@@ -607,9 +1121,10 @@ impl TemplateVerification{
                 &ctx, 
                 &solver, 
                 &aux_signals_to_smt_rep, 
-                &aux_signals_to_smt_rep_aux
+                &aux_signals_to_smt_rep_aux,
+                &constraint_trackers
             );
-        } else{
+        } else if configuration.homologues{
             apply_deduction_rule_homologues(
                 &self.constraints, 
                 &ctx, 
@@ -618,7 +1133,8 @@ impl TemplateVerification{
                 &aux_signals_to_smt_rep_aux,
                 &self.deductions,
                 &self.field, 
-                &field
+                &field,
+                &constraint_trackers
             );
         }
 
@@ -637,16 +1153,19 @@ impl TemplateVerification{
                 implication_right &= s_1._eq(s_2);
             }
 
-            solver.assert(&implication_left.implies(&implication_right));
+            let tracker = trackers.track_contract(&ctx, safety_implication_to_string(&(inputs.clone(), outputs.clone())));
+            assert_tracked(&solver, tracker.as_ref(), &implication_left.implies(&implication_right));
         }
         if self.check_tags{
             for implication in &self.tags_implications{
-                insert_implication_in_smt(implication, &ctx, &solver, &aux_signals_to_smt_rep, &self.field);
+                let tracker = trackers.track_contract(&ctx, implication_to_string(implication));
+                insert_implication_in_smt(implication, &ctx, &solver, &aux_signals_to_smt_rep, &self.field, tracker.as_ref());
             }
         }
         if self.check_postconditions{
             for implication in &self.implications{
-                insert_implication_in_smt(implication, &ctx, &solver, &aux_signals_to_smt_rep, &self.field);
+                let tracker = trackers.track_contract(&ctx, implication_to_string(implication));
+                insert_implication_in_smt(implication, &ctx, &solver, &aux_signals_to_smt_rep, &self.field, tracker.as_ref());
             }
         }
 
@@ -660,34 +1179,39 @@ impl TemplateVerification{
         } 
         solver.assert(&!all_outputs_equal);
         
-        match solver.check(){
+        match check_interruptible(&ctx, stop, || trackers.check(&solver)){
             SatResult::Sat =>{
+                let model = solver.get_model().unwrap();
+                if let Some(result) = spurious_model(&self.r1cs, &self.field, &model, &[&aux_signals_to_smt_rep, &aux_signals_to_smt_rep_aux], &mut logs){
+                    return SafetyOutcome{result, logs, counterexample: None, unsat_core: None};
+                }
                 logs.push(format!("### THE TEMPLATE DOES NOT ENSURE SAFETY. FOUND COUNTEREXAMPLE USING SMT:\n"));
 
-                let model = solver.get_model().unwrap();
                 for s in 0..self.number_inputs{
                     let v = model.eval(aux_signals_to_smt_rep.get(&(self.initial_signal + self.number_outputs + s)).unwrap(), true).unwrap();
                     logs.push(format!("Input signal {}: {}\n", self.initial_signal + self.number_outputs + s, v.to_string()));
 
                 }
+                let mut signals = Vec::new();
                 for s in 0..self.number_outputs{
                     let v = model.eval(aux_signals_to_smt_rep.get(&(self.initial_signal + s)).unwrap(), true).unwrap();
                     let v1 = model.eval(aux_signals_to_smt_rep_aux.get(&(self.initial_signal + s)).unwrap(), true).unwrap();
 
                     logs.push(format!("Output signal {}: values {} | {}\n", self.initial_signal + s, v.to_string(), v1.to_string()));
-
+                    if v != v1{
+                        signals.push(self.initial_signal + s);
+                    }
                 }
-
-                PossibleResult::FAILED
-                //}
+                SafetyOutcome{result: PossibleResult::FAILED, logs, counterexample: Some(signals), unsat_core: None}
             },
             SatResult::Unsat =>{
                 logs.push(format!("### WEAK SAFETY ENSURED BY THE TEMPLATE\n"));
-                PossibleResult::VERIFIED
+                let unsat_core = trackers.unsat_core(&solver, &self.constraints, "safety");
+                SafetyOutcome{result: PossibleResult::VERIFIED, logs, counterexample: None, unsat_core: Some(unsat_core)}
             },
             _=> {
                 logs.push(format!("### UNKNOWN: VERIFICATION OF WEAK SAFETY USING THE SPECIFICATION TIMEOUT\n"));
-                PossibleResult::UNKNOWN
+                SafetyOutcome{result: PossibleResult::UNKNOWN, logs, counterexample: None, unsat_core: None}
             }
         }
 
@@ -725,6 +1249,64 @@ pub fn update_bounds_signal(deductions: &mut Signal2Bounds, signal: usize, min:
     }
 }
 
+// bounds of the signals of the constraints before applying a rule, only needed
+// when recording certificates
+type BoundsSnapshot = Vec<(usize, Option<(BigInt, BigInt)>)>;
+
+fn snapshot_bounds(certificate: &Option<TemplateCertificate>, deductions: &Signal2Bounds, constraints: &[&Constraint<usize>]) -> Option<BoundsSnapshot>{
+    certificate.as_ref()?;
+    let mut signals: Vec<usize> = Vec::new();
+    for c in constraints{
+        for s in c.a().keys().chain(c.b().keys()).chain(c.c().keys()){
+            if !signals.contains(s){
+                signals.push(*s);
+            }
+        }
+    }
+    signals.sort();
+    Some(signals.into_iter().map(|s| {
+        let bounds = deductions.get(&s).map(|b| (b.min.clone(), b.max.clone()));
+        (s, bounds)
+    }).collect())
+}
+
+// adds a step to the certificate for each signal whose bounds changed after applying the rule
+fn record_bound_steps(
+    certificate: &mut Option<TemplateCertificate>, 
+    deductions: &Signal2Bounds, 
+    rule: &str, 
+    constraints: &[&Constraint<usize>], 
+    snapshot: Option<BoundsSnapshot>,
+    source: Option<usize>,
+){
+    let (certificate, snapshot) = match (certificate, snapshot){
+        (Some(certificate), Some(snapshot)) => (certificate, snapshot),
+        _ => return,
+    };
+    let premises: Vec<(usize, BigInt, BigInt)> = snapshot.iter()
+        .filter_map(|(s, bounds)| bounds.as_ref().map(|(min, max)| (*s, min.clone(), max.clone())))
+        .collect();
+    for (signal, old_bounds) in &snapshot{
+        if let Some(bounds) = deductions.get(signal){
+            let changed = match old_bounds{
+                Some((min, max)) => *min != bounds.min || *max != bounds.max,
+                None => true,
+            };
+            if changed{
+                certificate.bound_steps.push(BoundStep{
+                    rule: rule.to_string(),
+                    signal: *signal,
+                    min: bounds.min.clone(),
+                    max: bounds.max.clone(),
+                    constraints: constraints.iter().map(|c| (*c).clone()).collect(),
+                    premises: premises.clone(),
+                    source,
+                });
+            }
+        }
+    }
+}
+
 pub fn solve_signal_plus_coef(a: &HashMap<usize, BigInt>, field: &BigInt) -> Option<(usize,BigInt)> {
 
     if (a.len() == 1 && !a.contains_key(&0)) || (a.len() == 2 && a.contains_key(&0)){
@@ -982,13 +1564,14 @@ pub fn deduction_rule_apply_bounds_constraint(
 
 
 pub fn apply_deduction_assigned(
-    constraints: &Vec<Constraint<usize>>,
+    constraints: &[Constraint<usize>],
     ctx: &Context,
     solver: &Solver,
     signals_to_smt_symbols_1: &HashMap<usize, z3::ast::Int>,
     signals_to_smt_symbols_2: &HashMap<usize, z3::ast::Int>,
+    trackers: &[Option<z3::ast::Bool>],
 ) {
-    for c in constraints{
+    for (c, tracker) in constraints.iter().zip(trackers){
         let all_signals = c.take_signals();
         let only_linear_signals = c.take_only_linear_signals();
 
@@ -1016,14 +1599,14 @@ pub fn apply_deduction_assigned(
 
             let mut value_cond = !left_side;
             value_cond |=  &right_side;
-            solver.assert(&value_cond);
+            assert_tracked(solver, tracker.as_ref(), &value_cond);
         }
     }
 }
 
 
 pub fn apply_deduction_rule_homologues(
-    constraints: &Vec<Constraint<usize>>,
+    constraints: &[Constraint<usize>],
     ctx: &Context,
     solver: &Solver,
     signals_to_smt_symbols_1: &HashMap<usize, z3::ast::Int>,
@@ -1031,8 +1614,9 @@ pub fn apply_deduction_rule_homologues(
     deductions: &Signal2Bounds,
     field: &BigInt,
     p : &z3::ast::Int,
+    trackers: &[Option<z3::ast::Bool>],
 ){
-    for c in constraints{
+    for (c, tracker) in constraints.iter().zip(trackers){
         let mut value_a = z3::ast::Int::from_u64(ctx, 0);
         let mut value_b = z3::ast::Int::from_u64(ctx, 0);
         let mut value_c = z3::ast::Int::from_u64(ctx, 0);
@@ -1134,7 +1718,7 @@ pub fn apply_deduction_rule_homologues(
         value_cond |= !&condition_aa;
         value_cond |=  !&condition_bb;
         value_cond |=  &condition_cc;
-        solver.assert(&value_cond);
+        assert_tracked(solver, tracker.as_ref(), &value_cond);
 
         let lower_limit_k_a =  &lower_limit_a /field;
         let upper_limit_k_a = if &upper_limit_a /field > BigInt::from(0) && &upper_limit_a%field != BigInt::from(0) {
@@ -1155,7 +1739,7 @@ pub fn apply_deduction_rule_homologues(
         value_cond |= !(&condition_aa & &condition_a_not_zero);
         value_cond |=  !&condition_cc;
         value_cond |=  &condition_bb;
-        solver.assert(&value_cond);
+        assert_tracked(solver, tracker.as_ref(), &value_cond);
 
         let lower_limit_k_b =  &lower_limit_b /field;
         let upper_limit_k_b = if &upper_limit_b /field > BigInt::from(0) && &upper_limit_b%field != BigInt::from(0) {
@@ -1175,7 +1759,7 @@ pub fn apply_deduction_rule_homologues(
         value_cond |= !(&condition_bb & condition_b_not_zero);
         value_cond |=  !&condition_cc;
         value_cond |=  &condition_aa;
-        solver.assert(&value_cond);
+        assert_tracked(solver, tracker.as_ref(), &value_cond);
 
     }
 
@@ -1193,8 +1777,25 @@ pub fn insert_constraint_in_smt(
     deductions: &Signal2Bounds,
     num_k : usize,
     p : &z3::ast::Int,
-    _verbose: bool,
+    tracker: Option<&z3::ast::Bool>,
 ){
+    let (condition, _) = constraint_in_smt(constraint, ctx, signals_to_smt_symbols, field, deductions, num_k, p);
+    assert_tracked(solver, tracker, &condition);
+}
+
+// encodes the constraint as a formula, also returning the auxiliary variables
+// introduced for the multiples of p
+pub fn constraint_in_smt<'a>(
+    constraint: &Constraint<usize>,
+    ctx: &'a Context,
+    signals_to_smt_symbols: &HashMap<usize, z3::ast::Int<'a>>,
+    field: &BigInt,
+    deductions: &Signal2Bounds,
+    num_k : usize,
+    p : &z3::ast::Int<'a>,
+) -> (z3::ast::Bool<'a>, Vec<z3::ast::Int<'a>>){
+    let mut conditions: Vec<z3::ast::Bool> = Vec::new();
+    let mut auxiliary_variables = Vec::new();
     let mut value_a = z3::ast::Int::from_u64(ctx, 0);
     let mut value_b = z3::ast::Int::from_u64(ctx, 0);
     let mut value_c = z3::ast::Int::from_u64(ctx, 0);
@@ -1283,18 +1884,15 @@ pub fn insert_constraint_in_smt(
                 value_a._eq(&value_right)
             } else{
                 let k = z3::ast::Int::new_const(&ctx, format!("k_{}_a", num_k));
+                auxiliary_variables.push(k.clone());
         
                 let value_right = &k*p;
-                solver.assert( 
-                    &k.ge(
-                        &z3::ast::Int::from_str(&ctx, &lower_limit_k_a.to_string()).unwrap()
-                    )
-                );
-                solver.assert(
-                    &k.le(
-                        &z3::ast::Int::from_str(&ctx, &upper_limit_k_a.to_string()).unwrap()
-                    )
-                );
+                conditions.push(k.ge(
+                    &z3::ast::Int::from_str(&ctx, &lower_limit_k_a.to_string()).unwrap()
+                ));
+                conditions.push(k.le(
+                    &z3::ast::Int::from_str(&ctx, &upper_limit_k_a.to_string()).unwrap()
+                ));
                     
                 value_a._eq(&value_right)
             };  
@@ -1306,25 +1904,22 @@ pub fn insert_constraint_in_smt(
                 value_b._eq(&value_right)
             } else {
                 let k = z3::ast::Int::new_const(&ctx, format!("k_{}_b", num_k));
+                auxiliary_variables.push(k.clone());
         
                 let value_right = &k*p;
-                solver.assert( 
-                    &k.ge(
-                        &z3::ast::Int::from_str(&ctx, &lower_limit_k_b.to_string()).unwrap()
-                    )
-                );
-                solver.assert(
-                    &k.le(
-                        &z3::ast::Int::from_str(&ctx, &upper_limit_k_b.to_string()).unwrap()
-                    )
-                );
+                conditions.push(k.ge(
+                    &z3::ast::Int::from_str(&ctx, &lower_limit_k_b.to_string()).unwrap()
+                ));
+                conditions.push(k.le(
+                    &z3::ast::Int::from_str(&ctx, &upper_limit_k_b.to_string()).unwrap()
+                ));
                     
                 value_b._eq(&value_right)
             };
         
         value_or |= value_or_a;
         value_or |= value_or_b;
-        solver.assert(&value_or);
+        conditions.push(value_or);
     } else{
         // Apply deduction rule A * B = C => (C != 0) \/ (A = 0) \/ (B = 0)
         
@@ -1334,18 +1929,15 @@ pub fn insert_constraint_in_smt(
                 value_c._eq(&value_right)
             } else{
                 let k = z3::ast::Int::new_const(&ctx, format!("k_{}_c", num_k));
+                auxiliary_variables.push(k.clone());
         
                 let value_right = &k*p;
-                solver.assert( 
-                    &k.ge(
-                        &z3::ast::Int::from_str(&ctx, &lower_limit_k_c.to_string()).unwrap()
-                    )
-                );
-                solver.assert(
-                    &k.le(
-                        &z3::ast::Int::from_str(&ctx, &upper_limit_k_c.to_string()).unwrap()
-                    )
-                );
+                conditions.push(k.ge(
+                    &z3::ast::Int::from_str(&ctx, &lower_limit_k_c.to_string()).unwrap()
+                ));
+                conditions.push(k.le(
+                    &z3::ast::Int::from_str(&ctx, &upper_limit_k_c.to_string()).unwrap()
+                ));
                 value_c._eq(&value_right)
             };
 
@@ -1355,18 +1947,15 @@ pub fn insert_constraint_in_smt(
                 value_a._eq(&value_right)
             } else{
                 let k = z3::ast::Int::new_const(&ctx, format!("k_{}_a", num_k));
+                auxiliary_variables.push(k.clone());
         
                 let value_right = &k*p;
-                solver.assert( 
-                    &k.ge(
-                        &z3::ast::Int::from_str(&ctx, &lower_limit_k_a.to_string()).unwrap()
-                    )
-                );
-                solver.assert(
-                    &k.le(
-                        &z3::ast::Int::from_str(&ctx, &upper_limit_k_a.to_string()).unwrap()
-                    )
-                );
+                conditions.push(k.ge(
+                    &z3::ast::Int::from_str(&ctx, &lower_limit_k_a.to_string()).unwrap()
+                ));
+                conditions.push(k.le(
+                    &z3::ast::Int::from_str(&ctx, &upper_limit_k_a.to_string()).unwrap()
+                ));
                     
                 value_a._eq(&value_right)
             };
@@ -1378,18 +1967,15 @@ pub fn insert_constraint_in_smt(
                 value_b._eq(&value_right)
             } else{
                 let k = z3::ast::Int::new_const(&ctx, format!("k_{}_b", num_k));
+                auxiliary_variables.push(k.clone());
     
                 let value_right = &k*p;
-                solver.assert( 
-                    &k.ge(
-                        &z3::ast::Int::from_str(&ctx, &lower_limit_k_b.to_string()).unwrap()
-                    )
-                );
-                solver.assert(
-                    &k.le(
-                        &z3::ast::Int::from_str(&ctx, &upper_limit_k_b.to_string()).unwrap()
-                    )
-                );
+                conditions.push(k.ge(
+                    &z3::ast::Int::from_str(&ctx, &lower_limit_k_b.to_string()).unwrap()
+                ));
+                conditions.push(k.le(
+                    &z3::ast::Int::from_str(&ctx, &upper_limit_k_b.to_string()).unwrap()
+                ));
                 
                 value_b._eq(&value_right)
             };
@@ -1398,7 +1984,7 @@ pub fn insert_constraint_in_smt(
         value_or |= !condition_c;
         value_or |= condition_a;
         value_or |= condition_b;
-        solver.assert(&value_or);
+        conditions.push(value_or);
         
 
         // APPLY TRANSFORMATION RULE REMOVE MOD
@@ -1406,26 +1992,24 @@ pub fn insert_constraint_in_smt(
     
             let value_left = value_c - (value_a * value_b);
             let value_right = z3::ast::Int::from_str(ctx, &lower_limit_k.to_string()).unwrap() * p;
-            solver.assert(&value_left._eq(&value_right));
+            conditions.push(value_left._eq(&value_right));
         } else{
             let k = z3::ast::Int::new_const(&ctx, format!("k_{}", num_k));
+            auxiliary_variables.push(k.clone());
         
             let value_left =  value_c - (value_a * value_b);
             let value_right = &k*p;
-            solver.assert(
-                 &k.ge(
-                    &z3::ast::Int::from_str(&ctx, &lower_limit_k.to_string()).unwrap()
-                )
-            );
-            solver.assert(
-                &k.le(
-                    &z3::ast::Int::from_str(&ctx, &upper_limit_k.to_string()).unwrap()
-                )               
-            );
-            solver.assert(&value_left._eq(&value_right));
+            conditions.push(k.ge(
+                &z3::ast::Int::from_str(&ctx, &lower_limit_k.to_string()).unwrap()
+            ));
+            conditions.push(k.le(
+                &z3::ast::Int::from_str(&ctx, &upper_limit_k.to_string()).unwrap()
+            ));
+            conditions.push(value_left._eq(&value_right));
         }
     }
-    
+    let conditions: Vec<&z3::ast::Bool> = conditions.iter().collect();
+    (z3::ast::Bool::and(ctx, &conditions), auxiliary_variables)
 }
 
 
@@ -1435,7 +2019,16 @@ pub fn insert_implication_in_smt(
     solver: &Solver,
     signals_to_smt_symbols: &HashMap<usize, z3::ast::Int>,
     _field: &BigInt,
+    tracker: Option<&z3::ast::Bool>,
 ){
+    assert_tracked(solver, tracker, &implication_in_smt(implication, ctx, signals_to_smt_symbols));
+}
+
+fn implication_in_smt<'a>(
+    implication: &ExecutedImplication,
+    ctx: &'a Context,
+    signals_to_smt_symbols: &HashMap<usize, z3::ast::Int<'a>>,
+) -> z3::ast::Bool<'a>{
     let mut value_left = z3::ast::Bool::from_bool(ctx, true);
     let mut value_right = z3::ast::Bool::from_bool(ctx, true);
 
@@ -1448,12 +2041,228 @@ pub fn insert_implication_in_smt(
 
     }
 
-    solver.assert(&value_left.implies(&value_right));
-    
+    value_left.implies(&value_right)
+}
+
+// When recording certificates each constraint and contract is guarded by a tracking
+// literal, so the unsat core of the proof can be recovered from the solver
+fn assert_tracked(solver: &Solver, tracker: Option<&z3::ast::Bool>, condition: &z3::ast::Bool){
+    match tracker{
+        Some(t) => solver.assert(&t.implies(condition)),
+        None => solver.assert(condition),
+    }
+}
+
+struct SmtTrackers<'a>{
+    enabled: bool,
+    literals: Vec<z3::ast::Bool<'a>>,
+    constraints: HashMap<String, usize>,
+    contracts: HashMap<String, String>,
+    assumptions: HashMap<String, String>,
+    assumption_literals: HashMap<String, z3::ast::Bool<'a>>,
+}
+
+impl<'a> SmtTrackers<'a>{
+    // minimize asks the solver for minimal unsat cores, which is more expensive
+    fn new(ctx: &'a Context, solver: &Solver<'a>, enabled: bool, minimize: bool) -> SmtTrackers<'a>{
+        if enabled && minimize{
+            let mut params = Params::new(ctx);
+            params.set_bool("core.minimize", true);
+            solver.set_params(&params);
+        }
+        SmtTrackers{
+            enabled, 
+            literals: Vec::new(), 
+            constraints: HashMap::new(), 
+            contracts: HashMap::new(),
+            assumptions: HashMap::new(),
+            assumption_literals: HashMap::new(),
+        }
+    }
+
+    // the same assumption uses the same literal in both copies of the signals used to check safety
+    fn assumption_literal(&mut self, ctx: &'a Context, description: String) -> Option<z3::ast::Bool<'a>>{
+        if !self.enabled{
+            return None;
+        }
+        if let Some(literal) = self.assumption_literals.get(&description){
+            return Some(literal.clone());
+        }
+        let name = format!("track_assumption_{}", self.assumptions.len());
+        let literal = z3::ast::Bool::new_const(ctx, name.clone());
+        self.literals.push(literal.clone());
+        self.assumptions.insert(name, description.clone());
+        self.assumption_literals.insert(description, literal.clone());
+        Some(literal)
+    }
+
+    // adds the precondition to the conjunction of preconditions
+    fn assume(&mut self, ctx: &'a Context, preconditions: &mut z3::ast::Bool<'a>, precondition: &Expression, condition: z3::ast::Bool<'a>){
+        match self.assumption_literal(ctx, spec_expression_to_string(precondition)){
+            Some(literal) => *preconditions &= literal.implies(&condition),
+            None => *preconditions &= condition,
+        }
+    }
+
+    fn bounds_literal(&mut self, ctx: &'a Context, signal: usize, bounds: &ExecutedInequation<usize>) -> Option<z3::ast::Bool<'a>>{
+        if !self.enabled{
+            return None;
+        }
+        self.assumption_literal(ctx, format!("deduced bounds of s_{}: [{}, {}]", signal, bounds.min, bounds.max))
+    }
+
+    fn track_constraint(&mut self, ctx: &'a Context, index: usize) -> Option<z3::ast::Bool<'a>>{
+        if !self.enabled{
+            return None;
+        }
+        let name = format!("track_c_{}", index);
+        let literal = z3::ast::Bool::new_const(ctx, name.clone());
+        self.literals.push(literal.clone());
+        self.constraints.insert(name, index);
+        Some(literal)
+    }
+
+    fn track_contract(&mut self, ctx: &'a Context, description: String) -> Option<z3::ast::Bool<'a>>{
+        if !self.enabled{
+            return None;
+        }
+        let name = format!("track_contract_{}", self.contracts.len());
+        let literal = z3::ast::Bool::new_const(ctx, name.clone());
+        self.literals.push(literal.clone());
+        self.contracts.insert(name, description);
+        Some(literal)
+    }
+
+    fn check(&self, solver: &Solver<'a>) -> SatResult{
+        if self.enabled{
+            solver.check_assumptions(&self.literals)
+        } else{
+            solver.check()
+        }
+    }
+
+    fn unsat_core(&self, solver: &Solver<'a>, constraints: &[Constraint<usize>], property: &str) -> SmtProof{
+        let mut names: Vec<String> = solver.get_unsat_core().iter().map(|l| l.to_string()).collect();
+        names.sort();
+        let mut proof = SmtProof{
+            property: property.to_string(), 
+            core_constraints: Vec::new(), 
+            core_contracts: Vec::new(), 
+            core_assumptions: Vec::new(),
+        };
+        for name in names{
+            if let Some(index) = self.constraints.get(&name){
+                proof.core_constraints.push(constraints[*index].clone());
+            } else if let Some(contract) = self.contracts.get(&name){
+                proof.core_contracts.push(contract.clone());
+            } else if let Some(assumption) = self.assumptions.get(&name){
+                proof.core_assumptions.push(assumption.clone());
+            }
+        }
+        proof
+    }
 }
 
 
 
+// checks the model of a counterexample against the constraints using the arithmetic
+// of the field, a model that does not satisfy them points to an error in the encoding
+pub(crate) fn spurious_model<'a>(
+    r1cs: &[Constraint<usize>],
+    field: &BigInt,
+    model: &z3::Model<'a>,
+    copies: &[&HashMap<usize, z3::ast::Int<'a>>],
+    logs: &mut Vec<String>,
+) -> Option<PossibleResult>{
+    let mut copies_values = Vec::new();
+    for signals_to_smt_symbols in copies{
+        let mut values = HashMap::new();
+        for (s, symbol) in signals_to_smt_symbols.iter(){
+            let value = model.eval(symbol, true)?.to_string().parse::<BigInt>().ok()?;
+            values.insert(*s, value);
+        }
+        copies_values.push(values);
+    }
+    spurious_values(r1cs, field, &copies_values, logs)
+}
+
+// checks the values of the signals in each copy of a counterexample against the constraints
+pub(crate) fn spurious_values(r1cs: &[Constraint<usize>], field: &BigInt, copies: &[HashMap<usize, BigInt>], logs: &mut Vec<String>) -> Option<PossibleResult>{
+    let mut diagnostics = Vec::new();
+    for (n_copy, values) in copies.iter().enumerate(){
+        for (i, c) in r1cs.iter().enumerate(){
+            if let Some((a, b, c)) = evaluate_constraint(c, values, field){
+                let result = modular_arithmetic::sub(&modular_arithmetic::mul(&a, &b, field), &c, field);
+                if result != BigInt::from(0){
+                    diagnostics.push(format!("Constraint {} (copy {}): A = {}, B = {}, C = {}, A * B - C = {}\n", i, n_copy + 1, a, b, c, result));
+                }
+            }
+        }
+    }
+    if diagnostics.is_empty(){
+        None
+    } else{
+        logs.push("### ENCODING ERROR: THE COUNTEREXAMPLE FOUND USING SMT DOES NOT SATISFY THE CONSTRAINTS OF THE TEMPLATE\n".to_string());
+        logs.append(&mut diagnostics);
+        Some(PossibleResult::SPURIOUS)
+    }
+}
+
+// values of the linear expressions A, B and C of a constraint, None if some signal has no value
+pub(crate) fn evaluate_constraint(c: &Constraint<usize>, values: &HashMap<usize, BigInt>, field: &BigInt) -> Option<(BigInt, BigInt, BigInt)>{
+    let evaluate = |le: &HashMap<usize, BigInt>| -> Option<BigInt>{
+        let mut result = BigInt::from(0);
+        for (s, coef) in le{
+            let value = if *s == Constraint::<usize>::constant_coefficient(){
+                BigInt::from(1)
+            } else{
+                values.get(s)?.clone()
+            };
+            result = modular_arithmetic::add(&result, &modular_arithmetic::mul(coef, &value, field), field);
+        }
+        Some(result)
+    };
+    Some((evaluate(c.a())?, evaluate(c.b())?, evaluate(c.c())?))
+}
+
+// signals appearing in a specification
+pub(crate) fn expression_signals(expr: &Expression, signals: &mut Vec<usize>){
+    match expr{
+        Expression::Variable { name, .. } => {
+            if let Ok(s) = name.parse::<usize>(){
+                signals.push(s);
+            }
+        }
+        Expression::InfixOp { lhe, rhe, .. } => {
+            expression_signals(lhe, signals);
+            expression_signals(rhe, signals);
+        }
+        Expression::PrefixOp { rhe, .. } => expression_signals(rhe, signals),
+        Expression::InlineSwitchOp { cond, if_true, if_false, .. } => {
+            expression_signals(cond, signals);
+            expression_signals(if_true, signals);
+            expression_signals(if_false, signals);
+        }
+        _ => {}
+    }
+}
+
+// signals of the specifications that do not hold in the model of a counterexample
+fn violated_signals<'a>(ctx: &'a Context, model: &z3::Model<'a>, specifications: &[&Expression], signals_to_smt_symbols: &HashMap<usize, z3::ast::Int<'a>>) -> Vec<usize>{
+    let mut signals = Vec::new();
+    for specification in specifications{
+        let holds = get_z3_expression_bool(ctx, specification, signals_to_smt_symbols).ok()
+            .and_then(|condition| model.eval(&condition, true))
+            .and_then(|value| value.as_bool());
+        if holds == Some(false){
+            expression_signals(specification, &mut signals);
+        }
+    }
+    signals.sort();
+    signals.dedup();
+    signals
+}
+
 pub fn get_z3_condition_bounds<'a>(ctx: &'a Context,signal: &'a z3::ast::Int<'a>, min: &'a BigInt, max: &'a BigInt, field: &'a BigInt) -> z3::ast::Bool<'a>{
     if min >= &BigInt::from(0){
         
@@ -1501,6 +2310,18 @@ fn get_z3_expression_int<'a>(ctx: &'a Context, expr: &Expression, signals_to_smt
             Variable {name, ..} => {
                 Ok(signals_to_smt_symbols.get(&name.parse::<usize>().unwrap()).unwrap().clone())
             }
+            // boolean subexpressions (e.g. coming from reference functions) take the values 1 and 0
+            InfixOp { infix_op: Lesser | LesserEq | Greater | GreaterEq | NotEq | BoolAnd | BoolOr | BoolImplication, .. }
+            | PrefixOp { prefix_op: ExpressionPrefixOpcode::BoolNot, .. } => {
+                let is_true = get_z3_expression_bool(ctx, expr, signals_to_smt_symbols)?;
+                Ok(is_true.ite(&z3::ast::Int::from_i64(&ctx, 1), &z3::ast::Int::from_i64(&ctx, 0)))
+            }
+            InlineSwitchOp { cond, if_true, if_false, .. } => {
+                let cond = get_z3_condition(ctx, cond, signals_to_smt_symbols)?;
+                let if_true = get_z3_expression_int(ctx, if_true, signals_to_smt_symbols)?;
+                let if_false = get_z3_expression_int(ctx, if_false, signals_to_smt_symbols)?;
+                Ok(cond.ite(&if_true, &if_false))
+            }
             InfixOp { lhe, infix_op, rhe, .. } => {
                 let l_string = get_z3_expression_int(ctx, lhe, signals_to_smt_symbols)?;
                 let r_string = get_z3_expression_int(ctx, rhe, signals_to_smt_symbols)?;
@@ -1546,6 +2367,16 @@ fn get_z3_expression_int<'a>(ctx: &'a Context, expr: &Expression, signals_to_smt
 
                     ExpressionInfixOpcode::IntDiv => Ok(l_string / r_string),
 
+                    // masks of the form 2^k - 1 select the k least significant bits
+                    BitAnd => {
+                        match *rhe.clone(){
+                            Expression::Number(_, value) if value > BigInt::from(0) && (&value & (&value + 1)) == BigInt::from(0) => {
+                                Ok(l_string % (r_string + z3::ast::Int::from_i64(&ctx, 1)))
+                            }
+                            _ => Err(())
+                        }
+                    }
+
                     ExpressionInfixOpcode::Eq => {
                         let is_eq = l_string._eq(&r_string);
                         Ok(is_eq.ite(&z3::ast::Int::from_i64(&ctx, 1), &z3::ast::Int::from_i64(&ctx, 0)))
@@ -1568,7 +2399,7 @@ fn get_z3_expression_int<'a>(ctx: &'a Context, expr: &Expression, signals_to_smt
         }
 }
 
-fn get_z3_expression_bool<'a>(ctx: &'a Context, expr: &Expression, signals_to_smt_symbols: &HashMap<usize, z3::ast::Int<'a>>) ->    Result<z3::ast::Bool<'a>, ()>{
+pub(crate) fn get_z3_expression_bool<'a>(ctx: &'a Context, expr: &Expression, signals_to_smt_symbols: &HashMap<usize, z3::ast::Int<'a>>) ->    Result<z3::ast::Bool<'a>, ()>{
     use Expression::*;
     use ExpressionInfixOpcode::*;
     use ExpressionPrefixOpcode::*;
@@ -1677,11 +2508,25 @@ fn get_z3_expression_bool<'a>(ctx: &'a Context, expr: &Expression, signals_to_sm
                 }
         
             }
+            InlineSwitchOp { cond, if_true, if_false, .. } => {
+                let cond = get_z3_condition(ctx, cond, signals_to_smt_symbols)?;
+                let if_true = get_z3_expression_bool(ctx, if_true, signals_to_smt_symbols)?;
+                let if_false = get_z3_expression_bool(ctx, if_false, signals_to_smt_symbols)?;
+                Ok(cond.ite(&if_true, &if_false))
+            }
             
             _ => {Err(()) }
         }
 }
 
+// conditions of the inline switches are true when their value is not 0
+fn get_z3_condition<'a>(ctx: &'a Context, expr: &Expression, signals_to_smt_symbols: &HashMap<usize, z3::ast::Int<'a>>) -> Result<z3::ast::Bool<'a>, ()>{
+    match get_z3_expression_int(ctx, expr, signals_to_smt_symbols){
+        Ok(value) => Ok(!value._eq(&z3::ast::Int::from_i64(&ctx, 0))),
+        Err(_) => get_z3_expression_bool(ctx, expr, signals_to_smt_symbols),
+    }
+}
+
 enum PossibleBounds{
     Signal(usize),
     Number(BigInt),
@@ -1910,4 +2755,141 @@ pub fn normalize_constraint(c: Constraint<usize>, bounds: &HashMap<usize, Execut
     //best_c.print_pretty_constraint();    
 
     best_c
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use program_structure::ast::Meta;
+
+    // x * x = y
+    fn square() -> Constraint<usize> {
+        Constraint::new(
+            HashMap::from([(1, BigInt::from(1))]),
+            HashMap::from([(1, BigInt::from(1))]),
+            HashMap::from([(2, BigInt::from(1))]),
+        )
+    }
+
+    fn symbols<'a>(ctx: &'a Context, names: [&str; 2]) -> HashMap<usize, z3::ast::Int<'a>> {
+        HashMap::from([(1, z3::ast::Int::new_const(ctx, names[0])), (2, z3::ast::Int::new_const(ctx, names[1]))])
+    }
+
+    #[test]
+    fn model_of_a_wrong_encoding_is_spurious() {
+        let ctx = Context::new(&Config::new());
+        let solver = Solver::new(&ctx);
+        let field = BigInt::from(7);
+        let signals = symbols(&ctx, ["x", "y"]);
+        // the multiples of 11 instead of 7: 4 * 4 = 5 + 11 holds in the encoding but 2 != 5 mod 7
+        let wrong_p = z3::ast::Int::from_i64(&ctx, 11);
+        insert_constraint_in_smt(&square(), &ctx, &solver, &signals, &field, &HashMap::new(), 0, &wrong_p, None);
+        solver.assert(&signals[&1]._eq(&z3::ast::Int::from_i64(&ctx, 4)));
+        solver.assert(&signals[&2]._eq(&z3::ast::Int::from_i64(&ctx, 5)));
+        assert_eq!(solver.check(), SatResult::Sat);
+
+        let mut logs = Vec::new();
+        let result = spurious_model(&[square()], &field, &solver.get_model().unwrap(), &[&signals], &mut logs);
+        assert_eq!(result, Some(PossibleResult::SPURIOUS));
+        assert!(logs[1].starts_with("Constraint 0 (copy 1): A = 4, B = 4, C = 5, A * B - C = 4"));
+    }
+
+    #[test]
+    fn genuine_counterexample_is_not_spurious() {
+        let ctx = Context::new(&Config::new());
+        let solver = Solver::new(&ctx);
+        let field = BigInt::from(7);
+        let p = z3::ast::Int::from_i64(&ctx, 7);
+        let first = symbols(&ctx, ["x_1", "y"]);
+        let second = symbols(&ctx, ["x_2", "y"]);
+        let bounds = HashMap::from([
+            (1, ExecutedInequation{signal: 1, min: BigInt::from(0), max: BigInt::from(6)}),
+            (2, ExecutedInequation{signal: 2, min: BigInt::from(0), max: BigInt::from(6)}),
+        ]);
+        for (copy, k) in [(&first, 0), (&second, 1)] {
+            insert_constraint_in_smt(&square(), &ctx, &solver, copy, &field, &bounds, k, &p, None);
+            solver.assert(&copy[&1].ge(&z3::ast::Int::from_i64(&ctx, 0)));
+            solver.assert(&copy[&1].lt(&p));
+        }
+        // the same input y with different outputs x, for example 3 and 4 when y = 2
+        solver.assert(&first[&2]._eq(&z3::ast::Int::from_i64(&ctx, 2)));
+        solver.assert(&first[&1]._eq(&second[&1]).not());
+        assert_eq!(solver.check(), SatResult::Sat);
+
+        let mut logs = Vec::new();
+        let result = spurious_model(&[square()], &field, &solver.get_model().unwrap(), &[&first, &second], &mut logs);
+        assert_eq!(result, None);
+        assert!(logs.is_empty());
+    }
+
+    fn variable(signal: usize) -> Expression {
+        Expression::Variable { meta: Meta::new(0, 0), name: signal.to_string(), access: Vec::new() }
+    }
+
+    fn number(value: i64) -> Expression {
+        Expression::Number(Meta::new(0, 0), BigInt::from(value))
+    }
+
+    fn infix(lhe: Expression, infix_op: ExpressionInfixOpcode, rhe: Expression) -> Expression {
+        Expression::InfixOp { meta: Meta::new(0, 0), lhe: Box::new(lhe), infix_op, rhe: Box::new(rhe) }
+    }
+
+    // checks that the expression takes the given value when the signal 1 takes the value x
+    fn evaluates_to(expr: &Expression, x: i64, value: i64) -> bool {
+        let ctx = Context::new(&Config::new());
+        let solver = Solver::new(&ctx);
+        let signals = symbols(&ctx, ["x", "y"]);
+        solver.assert(&signals[&1]._eq(&z3::ast::Int::from_i64(&ctx, x)));
+        let encoded = get_z3_expression_int(&ctx, expr, &signals).unwrap();
+        solver.assert(&encoded._eq(&z3::ast::Int::from_i64(&ctx, value)).not());
+        solver.check() == SatResult::Unsat
+    }
+
+    #[test]
+    fn bit_and_with_a_mask_selects_the_low_bits() {
+        let low_bits = infix(variable(1), ExpressionInfixOpcode::BitAnd, number(7));
+        assert!(evaluates_to(&low_bits, 13, 5));
+        assert!(evaluates_to(&low_bits, 7, 7));
+        assert!(evaluates_to(&low_bits, 8, 0));
+
+        let ctx = Context::new(&Config::new());
+        let signals = symbols(&ctx, ["x", "y"]);
+        let not_a_mask = infix(variable(1), ExpressionInfixOpcode::BitAnd, number(6));
+        assert!(get_z3_expression_int(&ctx, &not_a_mask, &signals).is_err());
+        let signal_mask = infix(variable(1), ExpressionInfixOpcode::BitAnd, variable(2));
+        assert!(get_z3_expression_int(&ctx, &signal_mask, &signals).is_err());
+    }
+
+    #[test]
+    fn conditions_of_the_switches_are_the_nonzero_values() {
+        let switch = |cond: Expression| Expression::InlineSwitchOp {
+            meta: Meta::new(0, 0),
+            cond: Box::new(cond),
+            if_true: Box::new(number(10)),
+            if_false: Box::new(number(20)),
+        };
+        // arithmetic condition
+        let arithmetic = switch(infix(variable(1), ExpressionInfixOpcode::Sub, number(2)));
+        assert!(evaluates_to(&arithmetic, 3, 10));
+        assert!(evaluates_to(&arithmetic, 2, 20));
+        // boolean condition
+        let boolean = switch(infix(variable(1), ExpressionInfixOpcode::Lesser, number(2)));
+        assert!(evaluates_to(&boolean, 1, 10));
+        assert!(evaluates_to(&boolean, 2, 20));
+        // boolean operation over an arithmetic operand compared with 0
+        let negation = |rhe: Expression| Expression::PrefixOp {
+            meta: Meta::new(0, 0),
+            prefix_op: ExpressionPrefixOpcode::BoolNot,
+            rhe: Box::new(rhe),
+        };
+        let odd = infix(variable(1), ExpressionInfixOpcode::Mod, number(2));
+        assert!(evaluates_to(&switch(negation(infix(odd.clone(), ExpressionInfixOpcode::NotEq, number(0)))), 4, 10));
+
+        let ctx = Context::new(&Config::new());
+        let signals = symbols(&ctx, ["x", "y"]);
+        let xor = infix(variable(1), ExpressionInfixOpcode::BitXor, number(2));
+        assert!(get_z3_condition(&ctx, &xor, &signals).is_err());
+        // the arithmetic operands of the boolean operations are not implicitly compared with 0
+        assert!(get_z3_condition(&ctx, &negation(odd.clone()), &signals).is_err());
+        assert!(get_z3_expression_bool(&ctx, &odd, &signals).is_err());
+    }
+}
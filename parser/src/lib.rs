@@ -12,7 +12,7 @@ mod parser_logic;
 mod syntax_sugar_remover;
 
 use include_logic::{FileStack, IncludesGraph};
-use program_structure::ast::{produce_compiler_version_report, produce_report, produce_report_with_message, produce_version_warning_report, Expression};
+use program_structure::ast::{MainComponent, produce_compiler_version_report, produce_report, produce_report_with_message, produce_version_warning_report, Expression};
 use program_structure::error_code::ReportCode;
 use program_structure::error_definition::ReportCollection;
 use program_structure::error_definition::Report;
@@ -61,6 +61,7 @@ pub fn run_parser(
     version: &str,
     link_libraries: Vec<PathBuf>,
     spec_libraries: Vec<String>,
+    default_main: Option<MainComponent>,
 ) -> Result<(ProgramArchive, ReportCollection), (FileLibrary, ReportCollection)> {
     let mut file_library = FileLibrary::new();
     let mut definitions = Vec::new();
@@ -68,6 +69,7 @@ pub fn run_parser(
     let mut file_stack = FileStack::new(PathBuf::from(file));
     let mut includes_graph = IncludesGraph::new();
     let mut warnings = Vec::new();
+    let mut first_file = None;
     let mut link_libraries2 = link_libraries.clone();
     let mut ext_link_libraries = vec![Path::new("").to_path_buf()];
     ext_link_libraries.append(&mut link_libraries2);
@@ -86,6 +88,9 @@ pub fn run_parser(
         let file_id = file_library.add_file(path.clone(), src.clone());
         let program =
             parser_logic::parse_file(&src, file_id).map_err(|e| (file_library.clone(), e))?;
+        if first_file.is_none() {
+            first_file = Some((file_id, program.custom_gates));
+        }
         if let Some(main) = program.main_component {
             main_components.push((file_id, main, program.custom_gates));
         }
@@ -116,6 +121,10 @@ pub fn run_parser(
         }
     }
 
+    // the default main is only used by projects without main component
+    if let (true, Some(main), Some((file_id, custom_gates))) = (main_components.is_empty(), default_main, first_file) {
+        main_components.push((file_id, main, custom_gates));
+    }
     if main_components.len() == 0 {
         let report = produce_report(ReportCode::NoMainFoundInProject,0..0, 0);
         warnings.push(report);
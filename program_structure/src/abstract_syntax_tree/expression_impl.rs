@@ -278,6 +278,14 @@ impl Expression {
                 Expression::PrefixOp { meta: meta.clone(),  prefix_op: *prefix_op, rhe: Box::new(value) }
         
             }
+            InlineSwitchOp { meta, cond, if_true, if_false } => {
+                Expression::InlineSwitchOp {
+                    meta: meta.clone(),
+                    cond: Box::new(cond.apply_correspondence(correspondence)),
+                    if_true: Box::new(if_true.apply_correspondence(correspondence)),
+                    if_false: Box::new(if_false.apply_correspondence(correspondence)),
+                }
+            }
             
             _ => {unreachable!("The rest of the expressions are not valid."); }
         }
@@ -305,6 +313,11 @@ impl Expression {
                 rhe.check_condition_io_signals(max_io_value)
 
             }
+            InlineSwitchOp { cond, if_true, if_false, .. } => {
+                cond.check_condition_io_signals(max_io_value)
+                    && if_true.check_condition_io_signals(max_io_value)
+                    && if_false.check_condition_io_signals(max_io_value)
+            }
 
             _ => {unreachable!("The rest of the expressions are not valid."); }
         }
@@ -337,6 +350,14 @@ impl Expression {
                 Expression::PrefixOp { meta: meta.clone(),  prefix_op: *prefix_op, rhe: Box::new(value) }
         
             }
+            InlineSwitchOp { meta, cond, if_true, if_false } => {
+                Expression::InlineSwitchOp {
+                    meta: meta.clone(),
+                    cond: Box::new(cond.apply_offset(offset)),
+                    if_true: Box::new(if_true.apply_offset(offset)),
+                    if_false: Box::new(if_false.apply_offset(offset)),
+                }
+            }
             
             _ => {unreachable!("The rest of the expressions are not valid."); }
         }
@@ -108,7 +108,12 @@ pub enum ReportCode {
     UninitializedComponent,
 
     ImplicationError,
-    CIVERError
+    CIVERError,
+    CIVERCounterexample,
+    CIVERIncompleteWitness,
+    CIVERUnsatisfiableConstraints,
+    CIVERVacuousProof,
+    CIVERTagSuggestion,
 }
 
 impl fmt::Display for ReportCode {
@@ -217,7 +222,12 @@ impl fmt::Display for ReportCode {
             TupleError => "TAC02",
             UnderscoreWithNoSignalWarning => "TAC03",
             ImplicationError => "TAC04",
-            CIVERError => "CIV01"
+            CIVERError => "CIV01",
+            CIVERCounterexample => "CIV02",
+            CIVERIncompleteWitness => "CIV03",
+            CIVERUnsatisfiableConstraints => "CIV04",
+            CIVERVacuousProof => "CIV05",
+            CIVERTagSuggestion => "CIV06",
         };
         f.write_str(string_format)
     }
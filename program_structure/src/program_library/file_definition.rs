@@ -31,6 +31,9 @@ impl FileLibrary {
             None => None,
         }
     }
+    pub fn get_name(&self, file_id: FileID) -> Option<&FilePath> {
+        self.files.get(file_id).map(|file| file.name())
+    }
     pub fn to_storage(&self) -> &FileStorage {
         &self.get_files()
     }
@@ -164,6 +164,12 @@ impl ProgramArchive {
     pub fn get_main_expression(&self) -> &Expression {
         &self.initial_template_call
     }
+    pub fn set_main_component(&mut self, main_component: MainComponent) {
+        let (public_inputs, mut initial_template_call) = main_component;
+        initial_template_call.fill(self.file_id_main, &mut self.id_max);
+        self.public_inputs = public_inputs;
+        self.initial_template_call = initial_template_call;
+    }
     // FileLibrary functions
     pub fn get_file_library(&self) -> &FileLibrary {
         &self.file_library